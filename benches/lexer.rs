@@ -0,0 +1,70 @@
+// times the lexer on generated sources of a few megabytes
+// run with `cargo bench --bench lexer`, optionally passing sizes in megabytes: `cargo bench --bench lexer -- 1 8`
+
+extern crate sangzf_compiler;
+
+use std::env;
+use std::time::Instant;
+
+// one procedure using every kind of token, repeated to build up a large program
+fn procedure(i: usize) -> String {
+  return format!("
+  // procedure number {i}
+  procedure Proc{i} : integer(variable val{i} : integer)
+    variable ret : integer;
+    variable flt : float;
+    variable str : string;
+    variable arr : integer[10];
+    /* block comments
+       span lines */
+    begin
+      flt := 3.14159 * val{i} / 2.5;
+      str := \"a string literal with some length to it\";
+      if (val{i} >= 10 & val{i} != 42 | not (flt < 1.0)) then
+        ret := val{i} - 1_000;
+      else
+        ret := val{i} + 2;
+      end if;
+      for (ret := 0; ret <= 100)
+        arr[ret] := Proc{i}(ret) * -1;
+        ret := ret + 1;
+      end for;
+      return ret;
+  end procedure;
+", i = i);
+}
+
+// build a program of at least `size` bytes
+fn generate(size: usize) -> String {
+  let mut program = String::from("program Bench is\n");
+
+  let mut i = 0;
+  while program.len() < size {
+    program.push_str(&procedure(i));
+    i += 1;
+  }
+
+  program.push_str("begin\nend program.\n");
+
+  return program;
+}
+
+fn main() {
+
+  // cargo passes '--bench' through to the benchmark, so ignore any flags
+  let mut sizes: Vec<usize> = env::args().skip(1).filter_map(|arg| arg.parse().ok()).collect();
+  if sizes.is_empty() {
+    sizes = vec![1, 4];
+  }
+
+  for size in sizes {
+    let program = generate(size * 1024 * 1024);
+
+    let start = Instant::now();
+    let tokens = sangzf_compiler::tokenize(&program);
+    let elapsed = start.elapsed();
+
+    let seconds = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 / 1e9;
+    println!("{} MB: {} tokens in {:.3}s ({:.2} MB/s)", size, tokens.len(), seconds, program.len() as f64 / 1048576.0 / seconds);
+  }
+}
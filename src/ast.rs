@@ -0,0 +1,182 @@
+use crate::diagnostics::Diagnostics;
+use crate::tokenize::token::{Span, Type};
+
+// the result of parsing a program
+// `program` is only missing if the parse failed before reaching the body of the program
+pub struct Ast {
+  pub program: Option<Program>,
+  pub diagnostics: Diagnostics
+}
+
+pub struct Program {
+  pub name: Identifier,
+  pub declarations: Vec<Declaration>,
+  pub statements: Vec<Statement>
+}
+
+#[derive(Clone)]
+pub struct Identifier {
+  pub name: String,
+  pub line_num: u32,
+
+  // where the identifier's token is in the program
+  pub span: Span
+}
+
+#[derive(Clone, Copy)]
+pub enum Scope {
+  Local,
+  Global
+}
+
+pub enum Declaration {
+  Procedure(ProcedureDeclaration),
+  Variable(VariableDeclaration),
+  Type(TypeDeclaration)
+}
+
+pub struct ProcedureDeclaration {
+  pub scope: Scope,
+
+  // line of the 'procedure' keyword starting the header
+  pub line_num: u32,
+  pub name: Identifier,
+  pub return_type: Type,
+  pub params: Vec<Variable>,
+  pub declarations: Vec<Declaration>,
+  pub statements: Vec<Statement>,
+
+  // line of the 'procedure' keyword closing the body
+  pub end_line: u32
+}
+
+impl ProcedureDeclaration {
+  // the type of the procedure as seen by callers
+  pub fn r#type(&self) -> Type {
+    let params = self.params.iter().map(|param| Box::new(param.r#type.clone())).collect();
+
+    return Type::Procedure(params, Box::new(self.return_type.clone()));
+  }
+}
+
+pub struct VariableDeclaration {
+  pub scope: Scope,
+  pub variable: Variable
+}
+
+// a variable with its type (used for declarations and parameters)
+pub struct Variable {
+  pub name: Identifier,
+  pub r#type: Type
+}
+
+pub struct TypeDeclaration {
+  pub scope: Scope,
+  pub name: Identifier,
+  pub r#type: Type
+}
+
+pub enum Statement {
+  Assignment(Assignment),
+  If(IfStatement),
+  Loop(LoopStatement),
+  Return(ReturnStatement)
+}
+
+impl Statement {
+  // the line the statement starts on
+  pub fn line_num(&self) -> u32 {
+    return match self {
+      Statement::Assignment(assignment) => assignment.destination.identifier.line_num,
+      Statement::If(if_statement) => if_statement.line_num,
+      Statement::Loop(loop_statement) => loop_statement.line_num,
+      Statement::Return(return_statement) => return_statement.line_num
+    };
+  }
+}
+
+pub struct Assignment {
+  pub destination: Name,
+
+  // line of the ':=' token
+  pub line_num: u32,
+  pub expression: Expr
+}
+
+pub struct IfStatement {
+  pub line_num: u32,
+  pub condition: Expr,
+
+  // line of the ')' closing the condition
+  pub condition_line: u32,
+  pub then_statements: Vec<Statement>,
+  pub else_statements: Vec<Statement>
+}
+
+pub struct LoopStatement {
+  pub line_num: u32,
+  pub assignment: Assignment,
+  pub condition: Expr,
+
+  // line of the ')' closing the condition
+  pub condition_line: u32,
+  pub statements: Vec<Statement>
+}
+
+pub struct ReturnStatement {
+  pub line_num: u32,
+  pub expression: Expr
+}
+
+// a variable reference, optionally indexed
+pub struct Name {
+  pub identifier: Identifier,
+  pub index: Option<Box<Expr>>
+}
+
+pub enum Expr {
+  // the characters and type of a number, string, true or false token
+  Literal{chars: String, r#type: Type, line_num: u32},
+  Name(Name),
+  Call{procedure: Identifier, args: Vec<Expr>},
+  Negate(Box<Expr>),
+  Not(Box<Expr>),
+  Binary{op: BinaryOp, left: Box<Expr>, right: Box<Expr>}
+}
+
+impl Expr {
+  // the line diagnostics about this expression are reported on
+  // for binary expressions this is the line where the right operand ends
+  pub fn line_num(&self) -> u32 {
+    return match self {
+      Expr::Literal{line_num, ..} => *line_num,
+      Expr::Name(name) => name.identifier.line_num,
+      Expr::Call{procedure, ..} => procedure.line_num,
+      Expr::Negate(operand) | Expr::Not(operand) => operand.line_num(),
+      Expr::Binary{right, ..} => right.line_num()
+    };
+  }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+  // expression
+  And,
+  Or,
+
+  // arith_op
+  Add,
+  Sub,
+
+  // relation
+  LT,
+  GTE,
+  LTE,
+  GT,
+  EQ,
+  NEQ,
+
+  // term
+  Mul,
+  Div
+}
@@ -0,0 +1,24 @@
+use std::io;
+use std::process;
+
+extern crate sangzf_compiler;
+
+use sangzf_compiler::lint::Levels;
+use sangzf_compiler::lsp::Server;
+
+
+// a language server for editors, talking over stdin and stdout
+fn main() {
+  let stdin = io::stdin();
+  let stdout = io::stdout();
+
+  let mut server = Server::new(Levels::new());
+
+  match server.run(stdin.lock(), stdout.lock()) {
+    Ok(code) => process::exit(code),
+    Err(err) => {
+      eprintln!("sangzf-lsp: {}", err);
+      process::exit(1);
+    }
+  }
+}
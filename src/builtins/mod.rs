@@ -1,3 +1,14 @@
 pub mod bool;
 pub mod float;
-pub mod integer;
\ No newline at end of file
+pub mod integer;
+
+use crate::tokenize::token::TokenEntry;
+
+// every builtin procedure available to programs
+pub fn initialize_builtins() -> Vec<TokenEntry> {
+  let (get_bool, put_bool) = bool::initialize_bool_funcs();
+  let (get_integer, put_integer) = integer::initialize_integer_funcs();
+  let (get_float, put_float) = float::initialize_float_funcs();
+  
+  return vec![get_bool, put_bool, get_integer, put_integer, get_float, put_float];
+}
@@ -0,0 +1,567 @@
+use std::collections::HashMap;
+use std::mem;
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::builtins;
+use crate::diagnostics::{Diagnostic, Diagnostics};
+use crate::lint::{Level, Lint};
+use crate::log;
+use crate::suggest;
+use crate::tokenize::token::{Span, Token, TokenEntry, Type};
+use crate::tokenize::literals;
+
+// each check either resolves the type of what was checked, or the reason it is invalid
+pub type CheckResult<T> = Result<T, Diagnostic>;
+
+// resolves names and checks types in a parsed program
+pub struct Checker {
+  pub symbol_table_chain: Vec<HashMap<String, Rc<TokenEntry>>>,
+  pub diagnostics: Diagnostics
+}
+
+impl Checker {
+  pub fn new() -> Self {
+    return Checker {
+      symbol_table_chain: vec![],
+      diagnostics: Diagnostics::new()
+    };
+  }
+
+  // check the program, returning everything wrong with it (including any errors from parsing it)
+  pub fn check(mut self, ast: &Ast) -> Diagnostics {
+    self.diagnostics = ast.diagnostics.clone();
+
+    if let Some(program) = &ast.program {
+      self.program(program);
+    }
+
+    for diagnostic in log::internal_errors() {
+      self.diagnostics.push(diagnostic);
+    }
+
+    return self.diagnostics;
+  }
+
+  pub fn program(&mut self, program: &Program) {
+
+    // create a global symbol table, starting with the built-in functions
+    self.symbol_table_chain.push(HashMap::new());
+    self.add_builtins();
+
+    // create a new symbol table for the program scope
+    self.symbol_table_chain.push(HashMap::new());
+
+    self.declarations(&program.declarations);
+
+    // program doesn't have a return type so neither do program statements
+    self.statements(&program.statements, &Type::None);
+
+    // leave the program scope, then the global one
+    self.symbol_table_chain.pop();
+    self.symbol_table_chain.pop();
+  }
+
+  pub fn declarations(&mut self, declarations: &[Declaration]) {
+    for declaration in declarations {
+      match declaration {
+        Declaration::Procedure(procedure) => self.procedure_declaration(procedure),
+        Declaration::Variable(variable) => {
+          self.add_symbol(variable.scope, Checker::symbol(&variable.variable.name, variable.variable.r#type.clone()));
+        },
+        Declaration::Type(type_declaration) => {
+          self.add_symbol(type_declaration.scope, Checker::symbol(&type_declaration.name, type_declaration.r#type.clone()));
+        }
+      }
+    }
+  }
+
+  pub fn procedure_declaration(&mut self, procedure: &ProcedureDeclaration) {
+
+    // Note: Using Rc struct gives immutable multiple ownership
+    // this means that the symbols in the table are immutable
+    // but I believe this language does not require this since this information is given completely at declaration
+    let procedure_symbol = Checker::symbol(&procedure.name, procedure.r#type());
+
+    // add the procedure to the containing scope
+    self.add_symbol(procedure.scope, Rc::clone(&procedure_symbol));
+
+    // create a new symbol table for the procedure scope
+    // include the procedure in this new symbol table (allow recursive calls)
+    debug!("checker", "checking procedure '{}' (line {})", procedure.name.name, procedure.line_num);
+    self.symbol_table_chain.push(HashMap::new());
+    self.add_symbol(Scope::Local, procedure_symbol);
+
+    for param in &procedure.params {
+      self.add_symbol(Scope::Local, Checker::symbol(&param.name, param.r#type.clone()));
+    }
+
+    self.declarations(&procedure.declarations);
+
+    // check the statements, keeping track of whether every path returns
+    let does_return = self.statements(&procedure.statements, &procedure.return_type);
+
+    // leave the current scope
+    self.symbol_table_chain.pop();
+
+    if !does_return {
+      self.diagnostics.push(Diagnostic::ErrMissingReturn{name: String::from(&procedure.name.name[..]),
+                                                         start_line: procedure.line_num,
+                                                         end_line: procedure.end_line});
+    }
+  }
+
+  // check a block of statements
+  // returns true if every path through these statements reaches a return statement
+  pub fn statements(&mut self, statements: &[Statement], return_type: &Type) -> bool {
+
+    let mut does_return = false;
+    let mut reported_unreachable = false;
+
+    for statement in statements {
+
+      // only warn about the first statement after the return, the rest are implied
+      if does_return && !reported_unreachable {
+        self.diagnostics.push(Diagnostic::WarnUnreachable{line_num: statement.line_num()});
+        reported_unreachable = true;
+      }
+
+      if self.statement(statement, return_type) {
+        does_return = true;
+      }
+    }
+
+    return does_return;
+  }
+
+  // check a statement, reporting any errors in it
+  // returns true if every path through the statement returns
+  pub fn statement(&mut self, statement: &Statement, return_type: &Type) -> bool {
+    match statement {
+      Statement::Assignment(assignment) => {
+        if let Err(err) = self.assignment_statement(assignment) {
+          self.diagnostics.push(err);
+        }
+
+        return false;
+      },
+      Statement::If(if_statement) => {
+        if let Err(err) = self.condition(&if_statement.condition, if_statement.condition_line) {
+          self.diagnostics.push(err);
+        }
+
+        let then_returns = self.statements(&if_statement.then_statements, return_type);
+
+        // without an else branch, there is a path through the if statement that does not return
+        let else_returns = self.statements(&if_statement.else_statements, return_type);
+
+        // the if statement only returns if both branches do
+        return then_returns && else_returns;
+      },
+      Statement::Loop(loop_statement) => {
+        if let Err(err) = self.assignment_statement(&loop_statement.assignment) {
+          self.diagnostics.push(err);
+        }
+
+        if let Err(err) = self.condition(&loop_statement.condition, loop_statement.condition_line) {
+          self.diagnostics.push(err);
+        }
+
+        // the body may never run, so a return inside of it doesn't make the loop return
+        self.statements(&loop_statement.statements, return_type);
+
+        return false;
+      },
+      Statement::Return(return_statement) => {
+        if let Err(err) = self.return_statement(return_statement, return_type) {
+          self.diagnostics.push(err);
+        }
+
+        // count this as returning even if the expression is invalid, to avoid also reporting a missing return
+        return true;
+      }
+    }
+  }
+
+  pub fn assignment_statement(&mut self, assignment: &Assignment) -> CheckResult<()> {
+
+    // look up the destination to retrieve the type
+    let dest_type = self.name(&assignment.destination)?;
+
+    let expr_type = self.expression(&assignment.expression)?;
+
+    // enforce that the expression type is compatible with destination type
+    if !Checker::is_compatible(&dest_type, &expr_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: assignment.line_num,
+                                            expected: vec![dest_type],
+                                            actual: expr_type});
+    }
+
+    self.conversion(&dest_type, &expr_type, assignment.line_num);
+
+    return Ok(());
+  }
+
+  // if and loop statement expressions should evaluate to a boolean
+  pub fn condition(&mut self, condition: &Expr, line_num: u32) -> CheckResult<()> {
+
+    let condition_type = Type::Bool;
+    let expr_type = self.expression(condition)?;
+
+    if !Checker::is_compatible(&condition_type, &expr_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num,
+                                            expected: vec![condition_type],
+                                            actual: expr_type});
+    }
+
+    return Ok(());
+  }
+
+  pub fn return_statement(&mut self, return_statement: &ReturnStatement, return_type: &Type) -> CheckResult<()> {
+
+    let expr_type = self.expression(&return_statement.expression)?;
+
+    // check that that the expression type is compatible with expected type
+    if !Checker::is_compatible(return_type, &expr_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: return_statement.line_num,
+                                            expected: vec![return_type.clone()],
+                                            actual: expr_type});
+    }
+
+    self.conversion(return_type, &expr_type, return_statement.line_num);
+
+    return Ok(());
+  }
+
+  // resolve the type of a (possibly indexed) variable
+  pub fn name(&mut self, name: &Name) -> CheckResult<Type> {
+
+    // make sure the identifier exists
+    let val_type = if let Some(symbol) = self.get_symbol(&name.identifier.name) {
+      symbol.r#type.clone()
+    } else {
+      // only variables can be read
+      let suggestion = self.suggest_symbol(&name.identifier.name, |r#type| !matches!(r#type, Type::Procedure(..) | Type::Type(..)));
+
+      return Err(Diagnostic::ErrSymbolNotFound{line_num: name.identifier.line_num, name: String::from(&name.identifier.name[..]), suggestion});
+    };
+
+    if let Some(index) = &name.index {
+      self.expression(index)?;
+
+      // if indexing, check that this is actually an array
+      if let Type::Array(el_type, _) = val_type {
+        return Ok(*el_type);
+      } else {
+        return Err(Diagnostic::ErrNotArray{line_num: name.identifier.line_num, name: String::from(&name.identifier.name[..]), actual: val_type});
+      }
+    }
+
+    return Ok(val_type);
+  }
+
+  pub fn expression(&mut self, expression: &Expr) -> CheckResult<Type> {
+    return match expression {
+      Expr::Literal{chars, r#type, line_num} => Checker::literal(chars, r#type, *line_num, false),
+      Expr::Name(name) => self.name(name),
+      Expr::Call{procedure, args} => self.procedure_call(procedure, args),
+
+      // the most negative integer is only in range once it is negated
+      Expr::Negate(operand) => match &**operand {
+        Expr::Literal{chars, r#type, line_num} => Checker::literal(chars, r#type, *line_num, true),
+        _ => self.expression(operand)
+      },
+
+      // TODO check the operand types of 'not' and '-'
+      Expr::Not(operand) => self.expression(operand),
+      Expr::Binary{op, left, right} => {
+        let left_type = self.expression(left)?;
+        let right_type = self.expression(right)?;
+
+        match op {
+          BinaryOp::And | BinaryOp::Or => Checker::logical_op(left_type, right_type, right.line_num()),
+          BinaryOp::Add | BinaryOp::Sub => Checker::arith_op(left_type, left.line_num(), right_type, right.line_num()),
+          BinaryOp::Mul | BinaryOp::Div => Checker::term(left_type, left.line_num(), right_type, right.line_num()),
+          BinaryOp::EQ | BinaryOp::NEQ => Checker::relation(left_type, left.line_num(), right_type, right.line_num(), true),
+          _ => Checker::relation(left_type, left.line_num(), right_type, right.line_num(), false)
+        }
+      }
+    };
+  }
+
+  // check that a number literal can be represented by its type (integers are 32 bit, floats are single precision)
+  pub fn literal(chars: &str, r#type: &Type, line_num: u32, negated: bool) -> CheckResult<Type> {
+    let in_range = match r#type {
+      Type::Integer => match literals::integer_value(chars) {
+        Ok(value) => value <= i32::MAX as u64 || (negated && value == i32::MAX as u64 + 1),
+        Err(_) => false
+      },
+      Type::Float => match literals::float_value(chars) {
+        Ok(value) => (value as f32).is_finite(),
+        Err(_) => false
+      },
+      _ => true
+    };
+
+    if !in_range {
+      return Err(Diagnostic::ErrLiteralRange{line_num, chars: String::from(chars), r#type: r#type.clone()});
+    }
+
+    return Ok(r#type.clone());
+  }
+
+  pub fn procedure_call(&mut self, procedure: &Identifier, args: &[Expr]) -> CheckResult<Type> {
+
+    // look up the procedure
+    let (procedure_type, decl_line) = match self.get_symbol(&procedure.name) {
+      Some(val) => (val.r#type.clone(), val.line_num),
+      None => {
+        let suggestion = self.suggest_symbol(&procedure.name, |r#type| matches!(r#type, Type::Procedure(..)));
+
+        return Err(Diagnostic::ErrSymbolNotFound{name: String::from(&procedure.name[..]), line_num: procedure.line_num, suggestion});
+      }
+    };
+
+    // check that the retrieved symbol is a procedure
+    if let Type::Procedure(params, ret) = procedure_type {
+      self.argument_list(procedure, decl_line, args, &params)?;
+
+      return Ok(*ret);
+    } else {
+      return Err(Diagnostic::ErrNotProcedure{line_num: procedure.line_num, name: String::from(&procedure.name[..]), actual: procedure_type});
+    }
+  }
+
+  // compare arguments to procedure parameters
+  // mismatched arguments are all reported, and the call still has the procedure's return type
+  pub fn argument_list(&mut self, procedure: &Identifier, decl_line: u32, args: &[Expr], params: &[Box<Type>]) -> CheckResult<()> {
+    // the count is checked even if an argument is invalid, so one doesn't hide the other
+    let arg_types: Vec<CheckResult<Type>> = args.iter().map(|arg| self.expression(arg)).collect();
+
+    if args.len() != params.len() {
+      self.diagnostics.push(Diagnostic::ErrArgumentCount{line_num: procedure.line_num,
+                                                         name: String::from(&procedure.name[..]),
+                                                         params: params.iter().map(|param| (**param).clone()).collect(),
+                                                         count: args.len(),
+                                                         decl_line});
+
+      // still report the first invalid argument
+      return arg_types.into_iter().collect::<CheckResult<Vec<Type>>>().map(|_| ());
+    }
+
+    let arg_types = arg_types.into_iter().collect::<CheckResult<Vec<Type>>>()?;
+
+    for (position, (arg, (arg_type, param_type))) in args.iter().zip(arg_types.iter().zip(params)).enumerate() {
+      if !Checker::is_compatible(param_type, arg_type) {
+        self.diagnostics.push(Diagnostic::ErrArgumentType{line_num: arg.line_num(),
+                                                          name: String::from(&procedure.name[..]),
+                                                          position: position + 1,
+                                                          expected: (**param_type).clone(),
+                                                          actual: arg_type.clone(),
+                                                          decl_line});
+      } else {
+        self.conversion(param_type, arg_type, arg.line_num());
+      }
+    }
+
+    return Ok(());
+  }
+
+  // '&' and '|' are logical for bools and bitwise for integers
+  fn logical_op(left_type: Type, right_type: Type, right_line: u32) -> CheckResult<Type> {
+
+    // check that both sides are the same type
+    if mem::discriminant(&right_type) != mem::discriminant(&left_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: right_line,
+                                            expected: vec![left_type],
+                                            actual: right_type});
+    }
+
+    // check that type is either int or bool (for bitwise or logical op)
+    if let Type::Bool | Type::Integer = &right_type {
+      return Ok(left_type);
+    } else {
+      return Err(Diagnostic::ErrInvalidType{line_num: right_line,
+                                            expected: vec![Type::Bool, Type::Integer],
+                                            actual: right_type});
+    }
+  }
+
+  // '+' and '-' take numbers, and result in a float if either side is a float
+  fn arith_op(left_type: Type, left_line: u32, right_type: Type, right_line: u32) -> CheckResult<Type> {
+    let float_type = Type::Float;
+
+    if !Checker::is_compatible(&float_type, &left_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: left_line,
+                                            expected: vec![float_type, Type::Integer],
+                                            actual: left_type});
+    }
+
+    if !Checker::is_compatible(&float_type, &right_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: right_line,
+                                            expected: vec![float_type, Type::Integer],
+                                            actual: right_type});
+    }
+
+    if let Type::Float = &right_type {
+      return Ok(right_type);
+    }
+
+    return Ok(left_type);
+  }
+
+  // '*' and '/' take numbers, and always result in a float
+  fn term(left_type: Type, left_line: u32, right_type: Type, right_line: u32) -> CheckResult<Type> {
+    let float_type = Type::Float;
+
+    if !Checker::is_compatible(&float_type, &left_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: left_line,
+                                            expected: vec![float_type, Type::Integer],
+                                            actual: left_type});
+    }
+
+    if !Checker::is_compatible(&float_type, &right_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: right_line,
+                                            expected: vec![float_type, Type::Integer],
+                                            actual: right_type});
+    }
+
+    return Ok(float_type);
+  }
+
+  // comparisons result in a bool. Strings can only be checked for (in)equality
+  fn relation(left_type: Type, left_line: u32, right_type: Type, right_line: u32, is_equality: bool) -> CheckResult<Type> {
+    let int_type = Type::Integer;
+    let string_type = Type::String;
+
+    if is_equality {
+      if !(Checker::is_compatible(&int_type, &left_type) || Checker::is_compatible(&string_type, &left_type)) {
+        return Err(Diagnostic::ErrInvalidType{line_num: left_line,
+                                              expected: vec![int_type, Type::Float, Type::Bool, string_type],
+                                              actual: left_type});
+      }
+    } else if !Checker::is_compatible(&int_type, &left_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: left_line,
+                                            expected: vec![int_type, Type::Float, Type::Bool],
+                                            actual: left_type});
+    }
+
+    // make sure that left is compatible with right (so that a comparison is possible)
+    if !Checker::is_compatible(&left_type, &right_type) {
+      return Err(Diagnostic::ErrInvalidType{line_num: right_line,
+                                            expected: vec![left_type],
+                                            actual: right_type});
+    }
+
+    return Ok(Type::Bool);
+  }
+
+  // warn about a value being implicitly converted to a type that can't represent it
+  // (codegen truncates floats to integers, and turns integers into bools by comparing with 0)
+  fn conversion(&mut self, to_type: &Type, from_type: &Type, line_num: u32) {
+    if let Some((to_type, from_type)) = Checker::lossy_conversion(to_type, from_type) {
+      self.diagnostics.push(Diagnostic::Lint{lint: Lint::LossyConversion,
+                                             level: Level::Warn,
+                                             line_num,
+                                             name: format!("from '{}' to '{}'", from_type, to_type)});
+    }
+  }
+
+  // the (element) types of a lossy conversion. Arrays are converted an element at a time
+  pub fn lossy_conversion<'t>(to_type: &'t Type, from_type: &'t Type) -> Option<(&'t Type, &'t Type)> {
+    return match (to_type, from_type) {
+      (Type::Integer, Type::Float) | (Type::Bool, Type::Integer) => Some((to_type, from_type)),
+      (Type::Array(to_el, _), Type::Array(from_el, _)) => Checker::lossy_conversion(to_el, from_el),
+      (Type::Array(to_el, _), _) => Checker::lossy_conversion(to_el, from_type),
+      (_, Type::Array(from_el, _)) => Checker::lossy_conversion(to_type, from_el),
+      _ => None
+    };
+  }
+
+  pub fn get_symbol(&self, name: &String) -> Option<&TokenEntry> {
+    // check the local table
+    if let Some(local_table) = self.symbol_table_chain.last() {
+      if let Some(symbol) = local_table.get(name) {
+        return Some(symbol);
+      } else if let Some(global_table) = self.symbol_table_chain.first() {
+        if let Some(symbol) = global_table.get(name) {
+          return Some(symbol);
+        } else {
+          return None;
+        }
+      } else {
+        log::internal_error("checker", format!("no global symbol table to look up '{}' in", name));
+        return None;
+      }
+    } else {
+      log::internal_error("checker", format!("no symbol table to look up '{}' in", name));
+      return None;
+    }
+  }
+
+  // the visible symbol (in the local or global table, like `get_symbol`) with a name closest to `name`, out of those
+  // whose type `is_candidate` accepts. Builtins are in the global table, so they are suggested as well
+  fn suggest_symbol<F: Fn(&Type) -> bool>(&self, name: &str, is_candidate: F) -> Option<String> {
+    let tables = self.symbol_table_chain.last().into_iter().chain(self.symbol_table_chain.first());
+
+    let candidates = tables.flat_map(|table| table.iter())
+      .filter(|(_, symbol)| is_candidate(&symbol.r#type))
+      .map(|(candidate, _)| &candidate[..]);
+
+    return suggest::closest(name, candidates);
+  }
+
+  pub fn add_symbol(&mut self, scope: Scope, tok_entry: Rc<TokenEntry>) {
+    let table = match scope {
+      Scope::Local => self.symbol_table_chain.last_mut(),
+      Scope::Global => self.symbol_table_chain.first_mut()
+    };
+
+    if let Some(table) = table {
+      table.insert(String::from(&tok_entry.chars[..]), tok_entry);
+    }
+  }
+
+  pub fn add_builtins(&mut self) {
+    for builtin in builtins::initialize_builtins() {
+      self.add_symbol(Scope::Global, Rc::new(builtin));
+    }
+  }
+
+  // the symbol table entry for a declared identifier
+  fn symbol(identifier: &Identifier, r#type: Type) -> Rc<TokenEntry> {
+    return Rc::new(TokenEntry {
+      chars: String::from(&identifier.name[..]),
+      tok_type: Token::Identifier,
+      line_num: identifier.line_num,
+      r#type,
+      span: Span::default()
+    });
+  }
+
+  pub fn is_compatible(expected_type: &Type, actual_type: &Type) -> bool {
+    return match (expected_type, actual_type) {
+      (Type::Integer, Type::Bool) | (Type::Bool, Type::Integer) => true,
+      (Type::Integer, Type::Float) | (Type::Float, Type:: Integer) => true,
+      (Type::Array(el_type_a, size_a), Type::Array(el_type_b, size_b)) => {
+        Checker::is_compatible(el_type_a, el_type_b) && size_a == size_b
+      },
+      (Type::Array(el_type, _), other_type) => {
+        Checker::is_compatible(el_type, other_type)
+      },
+      (other_type, Type::Array(el_type, _)) => {
+        Checker::is_compatible(el_type, other_type)
+      }
+      (a, b) => {
+        mem::discriminant(a) == mem::discriminant(b)
+      }
+    }
+  }
+
+}
+
+impl Default for Checker {
+  fn default() -> Checker {
+    return Checker::new();
+  }
+}
@@ -0,0 +1,540 @@
+extern crate llvm_sys;
+
+// import llvm dependencies
+use llvm_sys::{LLVMIntPredicate, LLVMRealPredicate};
+use crate::llvm::{Context, Module, Builder, Value};
+
+// llvm references used as guides
+// * introduction to building llvm program using c-apis: https://pauladamsmith.com/blog/2015/01/how-to-get-started-with-llvm-c-api.html
+// * using llvm with rust (+ webassembly, but I didn't use that part): https://medium.com/@jayphelps/using-llvm-from-rust-to-generate-webassembly-93e8c193fdb4
+// * walkthrough on a compiler with llvm: https://releases.llvm.org/1.1/docs/Stacker.html#terminate
+
+use std::collections::HashMap;
+use std::mem;
+
+use crate::ast::*;
+use crate::builtins;
+use crate::llvm_utils::{get_llvm_type, get_llvm_value};
+use crate::log;
+use crate::tokenize::token::Type;
+
+// a declared name and the llvm value backing it
+// variables are backed by a pointer to their storage, procedures by their function, and types by nothing
+#[derive(Clone)]
+pub struct Symbol<'m> {
+  pub r#type: Type,
+  pub value: Option<Value<'m>>
+}
+
+// the value an expression evaluates to
+pub struct Operand<'m> {
+  pub r#type: Type,
+  pub value: Value<'m>
+}
+
+// generates code for a program into an llvm module. The program must have passed the checker
+pub struct Codegen<'m> {
+  pub context: &'m Context,
+  pub llvm_module: &'m Module<'m>,
+  pub symbol_table_chain: Vec<HashMap<String, Symbol<'m>>>
+}
+
+impl <'m> Codegen<'m> {
+  pub fn new(context: &'m Context, llvm_module: &'m Module<'m>) -> Self {
+    return Codegen {
+      context,
+      llvm_module,
+      symbol_table_chain: vec![]
+    };
+  }
+
+  pub fn program(&mut self, program: &Program) {
+
+    // create a global symbol table with the built-in functions
+    // TODO load the builtins library (LLVMLoadLibraryPermanently) so manually linking isn't required
+    self.symbol_table_chain.push(HashMap::new());
+    self.add_builtins();
+
+    // create main program function (no arguments)
+    let program_type = self.context.void_type().function(&[]);
+    let main = self.llvm_module.add_function("main", program_type);
+
+    // add basic block to the program function
+    let entry = self.context.append_basic_block(main, "entry");
+
+    // create builder and position it at the end of basic block
+    let builder = self.llvm_module.create_builder();
+    builder.position_at_end(entry);
+
+    self.symbol_table_chain.push(HashMap::new());
+
+    self.declarations(&builder, &program.declarations);
+    self.statements(&builder, &program.statements, &Type::None);
+
+    // build the return
+    builder.build_ret_void();
+
+    self.symbol_table_chain.pop();
+    self.symbol_table_chain.pop();
+  }
+
+  pub fn declarations(&mut self, builder: &Builder<'m>, declarations: &[Declaration]) {
+    for declaration in declarations {
+      match declaration {
+        Declaration::Procedure(procedure) => self.procedure_declaration(procedure),
+        Declaration::Variable(variable) => self.variable_declaration(builder, variable),
+        Declaration::Type(type_declaration) => {
+          self.add_symbol(type_declaration.scope, &type_declaration.name.name, Symbol { r#type: type_declaration.r#type.clone(), value: None });
+        }
+      }
+    }
+  }
+
+  pub fn variable_declaration(&mut self, builder: &Builder<'m>, declaration: &VariableDeclaration) {
+    let variable = &declaration.variable;
+    let llvm_type = get_llvm_type(self.context, &variable.r#type);
+
+    // globals can be used from any procedure, so they can't live on the stack of the one declaring them
+    let storage = match declaration.scope {
+      Scope::Local => builder.build_alloca(llvm_type, &variable.name.name[..]),
+      Scope::Global => {
+        let global = self.llvm_module.add_global(llvm_type, &variable.name.name[..]);
+        global.set_initializer(llvm_type.const_null());
+
+        global
+      }
+    };
+
+    self.add_symbol(declaration.scope, &variable.name.name, Symbol { r#type: variable.r#type.clone(), value: Some(storage) });
+  }
+
+  pub fn procedure_declaration(&mut self, procedure: &ProcedureDeclaration) {
+
+    debug!("codegen", "generating procedure '{}'", procedure.name.name);
+
+    // add the function to the module
+    let procedure_type = procedure.r#type();
+    let function = self.llvm_module.add_function(&procedure.name.name[..], self.function_type(&procedure_type));
+
+    let procedure_symbol = Symbol { r#type: procedure_type, value: Some(function) };
+
+    // add the procedure to the containing scope, and to its own (allow recursive calls)
+    self.add_symbol(procedure.scope, &procedure.name.name, procedure_symbol.clone());
+    self.symbol_table_chain.push(HashMap::new());
+    self.add_symbol(Scope::Local, &procedure.name.name, procedure_symbol);
+
+    // procedures get their own builder, so the enclosing body can continue where it left off
+    let builder = self.llvm_module.create_builder();
+    let block = self.context.append_basic_block(function, "block");
+    builder.position_at_end(block);
+
+    // copy the arguments into the parameter variables
+    for (i, param) in procedure.params.iter().enumerate() {
+      let param_alloca = builder.build_alloca(get_llvm_type(self.context, &param.r#type), &param.name.name[..]);
+      builder.build_store(function.param(i as u32), param_alloca);
+
+      self.add_symbol(Scope::Local, &param.name.name, Symbol { r#type: param.r#type.clone(), value: Some(param_alloca) });
+    }
+
+    self.declarations(&builder, &procedure.declarations);
+    self.statements(&builder, &procedure.statements, &procedure.return_type);
+
+    // falling off the end of the body leaves the last block without a terminator
+    // the checker makes sure this can't happen, so mark it unreachable
+    Codegen::terminate_block(&builder);
+
+    self.symbol_table_chain.pop();
+  }
+
+  pub fn statements(&mut self, builder: &Builder<'m>, statements: &[Statement], return_type: &Type) {
+    for statement in statements {
+      match statement {
+        Statement::Assignment(assignment) => self.assignment_statement(builder, assignment),
+        Statement::If(if_statement) => self.if_statement(builder, if_statement, return_type),
+        Statement::Loop(loop_statement) => self.loop_statement(builder, loop_statement, return_type),
+        Statement::Return(return_statement) => self.return_statement(builder, return_statement, return_type)
+      }
+    }
+  }
+
+  pub fn assignment_statement(&mut self, builder: &Builder<'m>, assignment: &Assignment) {
+    let (dest_type, dest_value_ref) = self.get_variable(&assignment.destination.identifier.name);
+
+    // TODO index into the array (arrays aren't generated yet, see `get_llvm_type`)
+    let dest_type = Codegen::value_type(&dest_type);
+
+    let expression = self.expression(builder, &assignment.expression);
+
+    // cast the expression to the destination type
+    let value = Codegen::coerce(builder, &expression.r#type, &dest_type, expression.value).unwrap_or(expression.value);
+
+    builder.build_store(value, dest_value_ref);
+  }
+
+  pub fn if_statement(&mut self, builder: &Builder<'m>, if_statement: &IfStatement, return_type: &Type) {
+    let function = builder.insert_block().parent();
+
+    let then_block = self.context.append_basic_block(function, "then");
+    let else_block = self.context.append_basic_block(function, "else");
+    let end_block = self.context.append_basic_block(function, "end_if");
+
+    let condition = self.condition(builder, &if_statement.condition);
+    builder.build_cond_br(condition, then_block, else_block);
+
+    builder.position_at_end(then_block);
+    self.statements(builder, &if_statement.then_statements, return_type);
+    builder.build_br(end_block);
+
+    builder.position_at_end(else_block);
+    self.statements(builder, &if_statement.else_statements, return_type);
+    builder.build_br(end_block);
+
+    builder.position_at_end(end_block);
+  }
+
+  pub fn loop_statement(&mut self, builder: &Builder<'m>, loop_statement: &LoopStatement, return_type: &Type) {
+    let function = builder.insert_block().parent();
+
+    let condition_block = self.context.append_basic_block(function, "for_condition");
+    let body_block = self.context.append_basic_block(function, "for_body");
+    let end_block = self.context.append_basic_block(function, "end_for");
+
+    self.assignment_statement(builder, &loop_statement.assignment);
+    builder.build_br(condition_block);
+
+    // the condition is checked before every iteration
+    builder.position_at_end(condition_block);
+    let condition = self.condition(builder, &loop_statement.condition);
+    builder.build_cond_br(condition, body_block, end_block);
+
+    builder.position_at_end(body_block);
+    self.statements(builder, &loop_statement.statements, return_type);
+    builder.build_br(condition_block);
+
+    builder.position_at_end(end_block);
+  }
+
+  pub fn return_statement(&mut self, builder: &Builder<'m>, return_statement: &ReturnStatement, return_type: &Type) {
+    let expression = self.expression(builder, &return_statement.expression);
+
+    // cast the expression to the return type
+    let value = Codegen::coerce(builder, &expression.r#type, return_type, expression.value).unwrap_or(expression.value);
+
+    builder.build_ret(value);
+
+    // any statements following this are unreachable, but still need a block to be built into
+    let function = builder.insert_block().parent();
+    let block = self.context.append_basic_block(function, "after_return");
+    builder.position_at_end(block);
+  }
+
+  // evaluate a condition to an i1 for branching on
+  pub fn condition(&mut self, builder: &Builder<'m>, condition: &Expr) -> Value<'m> {
+    let expression = self.expression(builder, condition);
+    let int_type = self.context.int32_type();
+
+    return builder.build_icmp(LLVMIntPredicate::LLVMIntNE, expression.value, self.context.const_int(int_type, 0, false), "");
+  }
+
+  pub fn expression(&mut self, builder: &Builder<'m>, expression: &Expr) -> Operand<'m> {
+    return match expression {
+      Expr::Literal{chars, r#type, ..} => {
+        let value = match get_llvm_value(self.context, chars, r#type) {
+          Ok(value) => value,
+          Err(msg) => {
+            log::internal_error("codegen", msg);
+            self.placeholder(r#type)
+          }
+        };
+
+        Operand { r#type: r#type.clone(), value }
+      },
+      Expr::Name(name) => {
+        let (r#type, storage) = self.get_variable(&name.identifier.name);
+
+        // TODO index into the array (arrays aren't generated yet, see `get_llvm_type`)
+        let value = builder.build_load(storage, &name.identifier.name[..]);
+
+        Operand { r#type: Codegen::value_type(&r#type), value }
+      },
+      Expr::Call{procedure, args} => self.procedure_call(builder, procedure, args),
+      Expr::Negate(operand) => {
+        let operand = self.expression(builder, operand);
+
+        let value = if let Type::Float = &operand.r#type {
+          builder.build_fneg(operand.value, "")
+        } else {
+          builder.build_neg(operand.value, "")
+        };
+
+        Operand { r#type: operand.r#type, value }
+      },
+      Expr::Not(operand) => {
+        let operand = self.expression(builder, operand);
+
+        // bools are stored as integers, so only the lowest bit is flipped for them
+        let value = if let Type::Bool = &operand.r#type {
+          let int_type = self.context.int32_type();
+          let is_false = builder.build_icmp(LLVMIntPredicate::LLVMIntEQ, operand.value, self.context.const_int(int_type, 0, false), "");
+
+          builder.build_zext(is_false, int_type, "")
+        } else {
+          builder.build_not(operand.value, "")
+        };
+
+        Operand { r#type: operand.r#type, value }
+      },
+      Expr::Binary{op, left, right} => {
+        let left = self.expression(builder, left);
+        let right = self.expression(builder, right);
+
+        self.binary_op(builder, *op, left, right)
+      }
+    };
+  }
+
+  pub fn procedure_call(&mut self, builder: &Builder<'m>, procedure: &Identifier, args: &[Expr]) -> Operand<'m> {
+    let (params, ret, function) = match self.get_symbol(&procedure.name) {
+      Some(Symbol { r#type: Type::Procedure(params, ret), value: Some(function) }) => (params, *ret, function),
+      _ => {
+        log::internal_error("codegen", format!("'{}' is not a procedure", procedure.name));
+        return Operand { r#type: Type::Integer, value: self.placeholder(&Type::Integer) };
+      }
+    };
+
+    let mut arg_list = vec![];
+    for (arg, param_type) in args.iter().zip(params.iter()) {
+      let arg = self.expression(builder, arg);
+
+      // cast value if needed
+      arg_list.push(Codegen::coerce(builder, &arg.r#type, param_type, arg.value).unwrap_or(arg.value));
+    }
+
+    // llvm function call
+    let value = builder.build_call(function, &arg_list, "");
+
+    return Operand { r#type: ret, value };
+  }
+
+  pub fn binary_op(&mut self, builder: &Builder<'m>, op: BinaryOp, mut left: Operand<'m>, mut right: Operand<'m>) -> Operand<'m> {
+    match op {
+      BinaryOp::And => {
+        let value = builder.build_and(left.value, right.value, "");
+        return Operand { r#type: left.r#type, value };
+      },
+      BinaryOp::Or => {
+        let value = builder.build_or(left.value, right.value, "");
+        return Operand { r#type: left.r#type, value };
+      },
+      BinaryOp::Mul | BinaryOp::Div => {
+        // coerce left and right into floats
+        let float_type = Type::Float;
+        let left_value = Codegen::coerce(builder, &left.r#type, &float_type, left.value).unwrap_or(left.value);
+        let right_value = Codegen::coerce(builder, &right.r#type, &float_type, right.value).unwrap_or(right.value);
+
+        let value = if let BinaryOp::Mul = op {
+          builder.build_fmul(left_value, right_value, "")
+        } else {
+          builder.build_fdiv(left_value, right_value, "")
+        };
+
+        return Operand { r#type: float_type, value };
+      },
+      BinaryOp::Add | BinaryOp::Sub => {
+        // sync operand types
+        Codegen::upcast(builder, &mut left, &mut right);
+
+        let is_float = matches!(left.r#type, Type::Float);
+        let value = match (op, is_float) {
+          (BinaryOp::Add, true) => builder.build_fadd(left.value, right.value, ""),
+          (BinaryOp::Add, false) => builder.build_add(left.value, right.value, ""),
+          (_, true) => builder.build_fsub(left.value, right.value, ""),
+          (_, false) => builder.build_sub(left.value, right.value, "")
+        };
+
+        return Operand { r#type: left.r#type, value };
+      },
+      _ => {
+        // sync operand types, then compare them
+        Codegen::upcast(builder, &mut left, &mut right);
+
+        let is_true = if let Type::Float = &left.r#type {
+          let predicate = match op {
+            BinaryOp::LT => LLVMRealPredicate::LLVMRealOLT,
+            BinaryOp::GTE => LLVMRealPredicate::LLVMRealOGE,
+            BinaryOp::LTE => LLVMRealPredicate::LLVMRealOLE,
+            BinaryOp::GT => LLVMRealPredicate::LLVMRealOGT,
+            BinaryOp::EQ => LLVMRealPredicate::LLVMRealOEQ,
+            _ => LLVMRealPredicate::LLVMRealONE
+          };
+
+          builder.build_fcmp(predicate, left.value, right.value, "")
+        } else {
+          let predicate = match op {
+            BinaryOp::LT => LLVMIntPredicate::LLVMIntSLT,
+            BinaryOp::GTE => LLVMIntPredicate::LLVMIntSGE,
+            BinaryOp::LTE => LLVMIntPredicate::LLVMIntSLE,
+            BinaryOp::GT => LLVMIntPredicate::LLVMIntSGT,
+            BinaryOp::EQ => LLVMIntPredicate::LLVMIntEQ,
+            _ => LLVMIntPredicate::LLVMIntNE
+          };
+
+          builder.build_icmp(predicate, left.value, right.value, "")
+        };
+
+        // bools are stored as integers
+        let value = builder.build_zext(is_true, self.context.int32_type(), "");
+
+        return Operand { r#type: Type::Bool, value };
+      }
+    }
+  }
+
+  // the llvm type of a function, given its procedure type
+  pub fn function_type(&self, procedure_type: &Type) -> crate::llvm::Type<'m> {
+    if let Type::Procedure(params, ret) = procedure_type {
+      let mut types = vec![];
+
+      for param in params {
+        types.push(get_llvm_type(self.context, param));
+      }
+
+      return get_llvm_type(self.context, ret).function(&types);
+    } else {
+      log::internal_error("codegen", format!("non-procedure type '{}' has no function type", procedure_type));
+      return self.context.void_type().function(&[]);
+    }
+  }
+
+  // the type of the value loaded from a variable of the given type
+  // arrays aren't generated yet (see `get_llvm_type`). Their elements stand in for them, so the rest of the program can
+  // still be generated
+  pub fn value_type(r#type: &Type) -> Type {
+    if let Type::Array(el_type, _) = r#type {
+      return *el_type.clone();
+    }
+
+    return r#type.clone();
+  }
+
+  // look up a symbol in the local table, then the global table. The checker has already made sure that it exists
+  pub fn get_symbol(&self, name: &str) -> Option<Symbol<'m>> {
+    let local = self.symbol_table_chain.last().and_then(|table| table.get(name));
+    let global = self.symbol_table_chain.first().and_then(|table| table.get(name));
+
+    return local.or(global).cloned();
+  }
+
+  // the type of a variable, and the pointer to its storage
+  pub fn get_variable(&self, name: &str) -> (Type, Value<'m>) {
+    return match self.get_symbol(name) {
+      Some(Symbol { r#type, value: Some(storage) }) => (r#type, storage),
+      _ => {
+        // a placeholder, so the rest of the program can still be generated. No bitcode is written
+        log::internal_error("codegen", format!("variable '{}' has no storage", name));
+        (Type::Integer, self.context.int32_type().pointer().const_null())
+      }
+    };
+  }
+
+  // a value of the given type, standing in for one that couldn't be generated
+  pub fn placeholder(&self, r#type: &Type) -> Value<'m> {
+    return match r#type {
+      Type::None => self.context.const_int(self.context.int32_type(), 0, false),
+      _ => get_llvm_type(self.context, r#type).const_null()
+    };
+  }
+
+  pub fn add_symbol(&mut self, scope: Scope, name: &str, symbol: Symbol<'m>) {
+    let table = match scope {
+      Scope::Local => self.symbol_table_chain.last_mut(),
+      Scope::Global => self.symbol_table_chain.first_mut()
+    };
+
+    if let Some(table) = table {
+      table.insert(String::from(name), symbol);
+    }
+  }
+
+  // declare the builtins. They are defined in builtins.c, which is linked in afterwards
+  pub fn add_builtins(&mut self) {
+    for builtin in builtins::initialize_builtins() {
+      let function = self.llvm_module.add_function(&builtin.chars[..], self.function_type(&builtin.r#type));
+
+      self.add_symbol(Scope::Global, &builtin.chars, Symbol { r#type: builtin.r#type, value: Some(function) });
+    }
+  }
+
+  // terminate the builder's current block if it does not already end in a terminator
+  pub fn terminate_block(builder: &Builder<'m>) {
+    if builder.insert_block().terminator().is_none() {
+      builder.build_unreachable();
+    }
+  }
+
+  // losslessly converts the value of each operand to have matching types
+  pub fn upcast(builder: &Builder<'m>, from: &mut Operand<'m>, to: &mut Operand<'m>) {
+
+    // if types already match, no change required
+    if mem::discriminant(&from.r#type) == mem::discriminant(&to.r#type) {
+      return;
+    }
+
+    match (&from.r#type, &to.r#type) {
+      (Type::Integer, Type::Float) | (Type::Float, Type::Integer) => {
+        let int_operand = if let Type::Integer = &from.r#type {
+          from
+        } else {
+          to
+        };
+
+        // upcast int value to float
+        if let Ok(res) = Codegen::coerce(builder, &Type::Integer, &Type::Float, int_operand.value) {
+          int_operand.value = res;
+          int_operand.r#type = Type::Float;
+        }
+      },
+      (Type::Integer, Type::Bool) | (Type::Bool, Type::Integer) => {
+        let bool_operand = if let Type::Bool = &from.r#type {
+          from
+        } else {
+          to
+        };
+
+        // upcast bool value to integer
+        if let Ok(res) = Codegen::coerce(builder, &Type::Bool, &Type::Integer, bool_operand.value) {
+          bool_operand.value = res;
+          bool_operand.r#type = Type::Integer;
+        }
+      },
+      _ => () // no change if upcast pattern isn't matched
+    }
+  }
+
+  pub fn coerce(builder: &Builder<'m>, from_type: &Type, to_type: &Type, value: Value<'m>) -> Result<Value<'m>, ()> {
+
+    if mem::discriminant(from_type) == mem::discriminant(to_type) {
+      return Ok(value);
+    }
+
+    let context = builder.context();
+
+    return match (from_type, to_type) {
+      (Type::Integer, Type::Bool) => {
+        let int_type = context.int32_type();
+        let is_true = builder.build_icmp(LLVMIntPredicate::LLVMIntNE, value, context.const_int(int_type, 0, false), "");
+
+        Ok(builder.build_zext(is_true, int_type, ""))
+      },
+      (Type::Integer, Type::Float) => Ok(builder.build_si_to_fp(value, get_llvm_type(context, to_type), "")),
+      (Type::Float, Type::Integer) => Ok(builder.build_fp_to_si(value, get_llvm_type(context, to_type), "")),
+      (Type::Bool, Type::Integer) => Ok(builder.build_int_cast(value, get_llvm_type(context, to_type), "")),
+      (_, Type::Array(el_type, _)) => Codegen::coerce(builder, from_type, el_type, value),
+      _ => {
+        log::internal_error("codegen", format!("can't convert '{}' to '{}'", from_type, to_type));
+        Err(())
+      }
+    };
+  }
+
+}
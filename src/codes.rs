@@ -0,0 +1,195 @@
+// the registry of diagnostic codes, with a longer explanation of each for `--explain`
+// codes are stable: a code keeps its meaning once it has been used, and codes that are no longer reported aren't reused
+// E00xx are lexical errors, E01xx syntax errors, E02xx name errors, E03xx type errors, E04xx call errors
+// and E09xx internal errors. W00xx are warnings, and W01xx are lints
+
+use crate::lint::LINTS;
+
+pub struct Code {
+  pub code: &'static str,
+
+  // a one line summary
+  pub title: &'static str,
+
+  pub explanation: &'static str,
+
+  // a program that has the problem (empty if no program can cause it)
+  pub example: &'static str
+}
+
+pub static CODES: &[Code] = &[
+  Code {
+    code: "E0001",
+    title: "Unterminated block comment",
+    explanation: "A block comment was opened with '/*' but never closed with '*/'. Block comments can be nested, so every \
+'/*' inside a comment needs its own '*/' as well. The line where the unclosed comment was opened is reported.",
+    example: "program p is\nbegin\n/* the comment /* inside */ is closed, but this one isn't\nend program."
+  },
+  Code {
+    code: "E0002",
+    title: "Unrecognized character",
+    explanation: "A character outside of a string or comment doesn't start any token, e.g. '$' or '#'. The character \
+is skipped, but the parser usually reports the statement it is in as well (E0102).",
+    example: "program p is\nvariable x : integer;\nbegin\nx := 1 $ 2;\nend program."
+  },
+  Code {
+    code: "E0101",
+    title: "Unexpected end of program",
+    explanation: "The program ended in the middle of a declaration or statement, e.g. because the file was cut off. \
+A program that ends between statements is missing the end of its blocks instead (E0103).",
+    example: "program p is\nvariable x : integer;\nbegin\nx := 1 +"
+  },
+  Code {
+    code: "E0102",
+    title: "Unexpected token",
+    explanation: "The parser found a token that can't appear where it is. The message shows what was expected instead. \
+Common causes are a missing semicolon, a misspelled keyword (a suggestion is shown if the token is close to an expected \
+keyword) and unbalanced parentheses. The parser skips ahead to the next declaration or statement, so one mistake is only \
+reported once.",
+    example: "program p is\nvariable x : integer;\nbegin\nx := (1 + 2;\nend program."
+  },
+  Code {
+    code: "E0103",
+    title: "Missing end of block",
+    explanation: "An if statement, loop, procedure or the program isn't closed by its 'end if', 'end for', \
+'end procedure' or 'end program'. The error is reported where the enclosing block ends, and the line where the \
+unclosed block starts is shown.",
+    example: "program p is\nvariable x : integer;\nbegin\nif (true) then\nx := 1;\nend program."
+  },
+  Code {
+    code: "E0201",
+    title: "Undefined symbol",
+    explanation: "A name was used that isn't declared in the current scope or the global scope. Variables declared \
+in the program's body aren't visible inside procedures unless they are declared 'global'. If a visible name is close \
+to the one used, it is suggested.",
+    example: "program p is\nvariable total : integer;\nbegin\ntotal := totl + 1;\nend program."
+  },
+  Code {
+    code: "E0301",
+    title: "Mismatched types",
+    explanation: "A value has a type that can't be used where it is, e.g. a string is assigned to an integer or added \
+to a number. Integers, floats and bools convert to each other implicitly, but strings don't convert to anything.",
+    example: "program p is\nvariable x : integer;\nbegin\nx := \"one\";\nend program."
+  },
+  Code {
+    code: "E0302",
+    title: "Indexing a value that isn't an array",
+    explanation: "A name was indexed with '[...]', but it was declared without a bound. Only variables declared with \
+a bound (e.g. 'variable arr : integer[10]') are arrays.",
+    example: "program p is\nvariable x : integer;\nbegin\nx[0] := 1;\nend program."
+  },
+  Code {
+    code: "E0303",
+    title: "Calling a value that isn't a procedure",
+    explanation: "A name was called like a procedure, but it was declared as a variable or a type.",
+    example: "program p is\nvariable x : integer;\nbegin\nx := x(1);\nend program."
+  },
+  Code {
+    code: "E0304",
+    title: "Literal out of range",
+    explanation: "A number literal doesn't fit in its type. Integers are 32 bits (up to 2147483647) and floats are \
+single precision.",
+    example: "program p is\nvariable x : integer;\nbegin\nx := 3000000000;\nend program."
+  },
+  Code {
+    code: "E0305",
+    title: "Missing return",
+    explanation: "A procedure can reach its end without returning a value. Every path through a procedure's body has \
+to end with a return statement, including both branches of an if statement.",
+    example: "program p is\nprocedure f : integer(variable c : bool)\nbegin\nif (c) then return 1; end if;\nend procedure;\nbegin\nend program."
+  },
+  Code {
+    code: "E0401",
+    title: "Wrong number of arguments",
+    explanation: "A procedure was called with more or fewer arguments than it has parameters. The expected signature \
+and where the procedure is declared are shown.",
+    example: "program p is\nvariable x : integer;\nbegin\nx := getinteger(1);\nend program."
+  },
+  Code {
+    code: "E0402",
+    title: "Argument of the wrong type",
+    explanation: "An argument's type can't be converted to the type of its parameter. The position of the argument \
+and where the procedure is declared are shown.",
+    example: "program p is\nvariable x : bool;\nbegin\nx := putinteger(\"one\");\nend program."
+  },
+  Code {
+    code: "E0901",
+    title: "Internal compiler error",
+    explanation: "The compiler generated code that llvm rejected. This is a bug in the compiler, not the program. \
+Rerun with '--dump-ir' to save the generated ir, and include it in a bug report.",
+    example: ""
+  },
+  Code {
+    code: "E0902",
+    title: "Internal compiler error (unexpected state)",
+    explanation: "The compiler ran into something it should never see, like a type it can't generate code for after the \
+checker accepted it. This is a bug in the compiler, not the program. Rerun with '--log=debug' (or '-vv') to see what \
+the compiler did before it, and include that in a bug report.",
+    example: ""
+  },
+  Code {
+    code: "W0001",
+    title: "Unreachable statement",
+    explanation: "A statement comes after a return statement in the same block, so it never runs.",
+    example: "program p is\nprocedure f : integer()\nbegin\nreturn 1;\nreturn 2;\nend procedure;\nbegin\nend program."
+  },
+  Code {
+    code: "W0101",
+    title: "Unused variable (lint 'unused-variable')",
+    explanation: "A variable is declared, but its value is never read. Assigning to a variable doesn't count as using \
+it. Lints are warnings by default, and can be allowed or denied with '--allow=<lint>' and '--deny=<lint>'.",
+    example: "program p is\nvariable x : integer;\nbegin\nx := 1;\nend program."
+  },
+  Code {
+    code: "W0102",
+    title: "Unused parameter (lint 'unused-parameter')",
+    explanation: "A procedure's parameter is never read in its body.",
+    example: "program p is\nvariable x : integer;\nprocedure f : integer(variable a : integer)\nbegin\nreturn 1;\nend procedure;\nbegin\nx := f(1);\nx := x;\nend program."
+  },
+  Code {
+    code: "W0103",
+    title: "Unused procedure (lint 'unused-procedure')",
+    explanation: "A procedure is never called. A procedure calling itself doesn't count.",
+    example: "program p is\nprocedure f : integer()\nbegin\nreturn 1;\nend procedure;\nbegin\nend program."
+  },
+  Code {
+    code: "W0104",
+    title: "Possibly uninitialized variable (lint 'uninitialized')",
+    explanation: "A local variable may be read before it has been assigned, so its value is whatever was in memory. \
+A variable has to be assigned on every path to where it is read: in both branches of an if statement, and not only in \
+a loop's body, which may never run. Global variables and arrays aren't checked.",
+    example: "program p is\nvariable x : integer;\nvariable y : integer;\nbegin\nif (true) then x := 1; end if;\ny := x;\ny := y;\nend program."
+  },
+  Code {
+    code: "W0105",
+    title: "Lossy implicit conversion (lint 'lossy-conversion')",
+    explanation: "A value is implicitly converted to a type that can't hold all of it. Floats are truncated when they \
+are used as integers, and any non-zero integer becomes 'true' when it is used as a bool.",
+    example: "program p is\nvariable x : integer;\nbegin\nx := 1.5;\nx := x;\nend program."
+  }
+];
+
+// find a code (ignoring case). Lints can also be found by name
+pub fn find(code: &str) -> Option<&'static Code> {
+  let code = match LINTS.iter().find(|lint| lint.name() == code) {
+    Some(lint) => String::from(lint.code()),
+    None => code.to_uppercase()
+  };
+
+  return CODES.iter().find(|entry| entry.code == code);
+}
+
+// the full explanation of a code, as printed by `--explain`
+pub fn explain(code: &Code) -> String {
+  let mut text = format!("{}: {}\n\n{}\n", code.code, code.title, code.explanation);
+
+  if !code.example.is_empty() {
+    text.push_str("\nFor example:\n\n");
+
+    for line in code.example.lines() {
+      text.push_str(&format!("    {}\n", line));
+    }
+  }
+
+  return text;
+}
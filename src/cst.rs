@@ -0,0 +1,237 @@
+// a lossless concrete syntax tree. Every character of the program (including whitespace, comments,
+// the original casing of keywords and anything the parser couldn't make sense of) is kept in the tree,
+// so printing it gives back the original program
+
+use crate::lexer::Lexer;
+use crate::tokenize::char_group::CharGroup;
+use crate::tokenize::token::{Span, Token, TokenEntry};
+
+// the parse rules recorded in the tree
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SyntaxKind {
+  // the whole file, including anything after the program
+  File,
+
+  Program,
+  ProgramHeader,
+  ProgramBody,
+
+  // a declaration with its terminating semicolon (and anything skipped while resyncing)
+  Declaration,
+  ProcedureDeclaration,
+  ProcedureHeader,
+  ParameterList,
+  Parameter,
+  ProcedureBody,
+  VariableDeclaration,
+  TypeDeclaration,
+
+  // a statement with its terminating semicolon (and anything skipped while resyncing)
+  Statement,
+  AssignmentStatement,
+  IfStatement,
+  LoopStatement,
+  ReturnStatement,
+
+  Expression,
+  ArgumentList
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TriviaKind {
+  Whitespace,
+  LineComment,
+  BlockComment
+}
+
+// text between tokens
+#[derive(Clone, Debug)]
+pub struct Trivia {
+  pub kind: TriviaKind,
+  pub text: String
+}
+
+#[derive(Clone, Debug)]
+pub struct CstToken {
+  pub kind: Token,
+
+  // the token as it was written
+  pub text: String,
+
+  // whitespace and comments before the token
+  pub leading: Vec<Trivia>,
+
+  pub line_num: u32,
+  pub span: Span
+}
+
+#[derive(Clone, Debug)]
+pub enum Element {
+  Node(Node),
+  Token(CstToken)
+}
+
+#[derive(Clone, Debug)]
+pub struct Node {
+  pub kind: SyntaxKind,
+  pub children: Vec<Element>
+}
+
+#[derive(Clone, Debug)]
+pub struct Cst {
+  // always a `SyntaxKind::File` node
+  pub root: Node,
+
+  // whitespace and comments after the last token
+  pub trailing: Vec<Trivia>
+}
+
+impl Cst {
+
+  // the original program
+  pub fn text(&self) -> String {
+    let mut text = self.root.text();
+
+    for trivia in &self.trailing {
+      text.push_str(&trivia.text);
+    }
+
+    return text;
+  }
+
+}
+
+impl Node {
+
+  pub fn text(&self) -> String {
+    let mut text = String::new();
+
+    for token in self.tokens() {
+      for trivia in &token.leading {
+        text.push_str(&trivia.text);
+      }
+
+      text.push_str(&token.text);
+    }
+
+    return text;
+  }
+
+  // every token in this node, in order
+  pub fn tokens(&self) -> Vec<&CstToken> {
+    let mut tokens = vec![];
+
+    for child in &self.children {
+      match child {
+        Element::Node(node) => tokens.extend(node.tokens()),
+        Element::Token(token) => tokens.push(token)
+      }
+    }
+
+    return tokens;
+  }
+
+  // the nodes directly inside this one
+  pub fn nodes(&self) -> Vec<&Node> {
+    return self.children.iter().filter_map(|child| match child {
+      Element::Node(node) => Some(node),
+      Element::Token(_) => None
+    }).collect();
+  }
+
+}
+
+// builds the tree while the parser runs. Nodes are opened and closed around parse rules,
+// and each token is added to the innermost open node when the parser consumes it
+pub struct Builder<'a> {
+  program: &'a str,
+
+  // byte offset of the end of the last token added
+  last_end: usize,
+
+  // the open nodes, innermost last
+  stack: Vec<Node>
+}
+
+impl <'a> Builder<'a> {
+
+  pub fn new(program: &'a str) -> Self {
+    return Builder {
+      program,
+      last_end: 0,
+      stack: vec![Node { kind: SyntaxKind::File, children: vec![] }]
+    };
+  }
+
+  pub fn start_node(&mut self, kind: SyntaxKind) {
+    self.stack.push(Node { kind, children: vec![] });
+  }
+
+  pub fn finish_node(&mut self) {
+    // the file node is only closed by `finish`
+    if self.stack.len() > 1 {
+      if let Some(node) = self.stack.pop() {
+        self.push(Element::Node(node));
+      }
+    }
+  }
+
+  pub fn token(&mut self, entry: &TokenEntry) {
+    let token = CstToken {
+      kind: entry.tok_type,
+      text: String::from(&self.program[entry.span.start..entry.span.end]),
+      leading: Builder::trivia(&self.program[self.last_end..entry.span.start]),
+      line_num: entry.line_num,
+      span: entry.span
+    };
+
+    self.last_end = entry.span.end;
+    self.push(Element::Token(token));
+  }
+
+  // close any open nodes, and keep whatever follows the last token
+  pub fn finish(mut self) -> Cst {
+    while self.stack.len() > 1 {
+      self.finish_node();
+    }
+
+    let trailing = Builder::trivia(&self.program[self.last_end..]);
+
+    return Cst {
+      root: self.stack.remove(0),
+      trailing
+    };
+  }
+
+  fn push(&mut self, element: Element) {
+    if let Some(node) = self.stack.last_mut() {
+      node.children.push(element);
+    }
+  }
+
+  // split the text the lexer skipped between two tokens into whitespace and comments
+  fn trivia(mut text: &str) -> Vec<Trivia> {
+    let mut trivia = vec![];
+
+    while !text.is_empty() {
+      let (kind, mut len) = if text.starts_with("//") {
+        (TriviaKind::LineComment, text.find('\n').unwrap_or(text.len()))
+      } else if let Some(rest) = text.strip_prefix("/*") {
+        (TriviaKind::BlockComment, 2 + Lexer::block_comment_len(rest).0)
+      } else {
+        (TriviaKind::Whitespace, text.len() - text.trim_start_matches(CharGroup::is_ws).len())
+      };
+
+      // the lexer only skips whitespace and comments, but make sure there is always progress
+      if len == 0 {
+        len = text.chars().next().map_or(1, |ch| ch.len_utf8());
+      }
+
+      trivia.push(Trivia { kind, text: String::from(&text[..len]) });
+      text = &text[len..];
+    }
+
+    return trivia;
+  }
+
+}
@@ -0,0 +1,209 @@
+use crate::lint::{Level, Lint};
+use crate::tokenize::token::Type;
+
+// a problem found while compiling a program
+#[derive(Clone)]
+pub enum Diagnostic {
+  ErrUnexpectedEnd,
+  ErrUnterminatedComment{line_num: u32},
+
+  // a character that doesn't start any token
+  ErrUnrecognizedChar{line_num: u32, chars: String},
+  // `suggestion` is what was probably meant (a misspelled keyword or name), if anything close was expected or declared
+  ErrUnexpectedTok{ expected: String, actual: String, line_num: u32, suggestion: Option<String>},
+  ErrSymbolNotFound{name: String, line_num: u32, suggestion: Option<String>},
+  ErrInvalidType{line_num: u32, expected: Vec<Type>, actual: Type},
+
+  // a name that is indexed or called, but isn't an array or procedure
+  ErrNotArray{line_num: u32, name: String, actual: Type},
+  ErrNotProcedure{line_num: u32, name: String, actual: Type},
+  ErrLiteralRange{line_num: u32, chars: String, r#type: Type},
+  ErrMissingReturn{name: String, start_line: u32, end_line: u32},
+
+  // a block (e.g. 'if') that isn't closed by its 'end'. `start_line` is where the block starts
+  ErrMissingEnd{line_num: u32, construct: String, start_line: u32},
+
+  // calls that don't match the procedure's parameters. `decl_line` is where the procedure is declared (0 for builtins)
+  ErrArgumentCount{line_num: u32, name: String, params: Vec<Type>, count: usize, decl_line: u32},
+  ErrArgumentType{line_num: u32, name: String, position: usize, expected: Type, actual: Type, decl_line: u32},
+  ErrInternal{procedures: Vec<String>, msg: String},
+
+  // something the compiler should never run into (see `log::internal_error`). `phase` is the log target that found it
+  ErrCompilerBug{phase: String, msg: String},
+  WarnUnreachable{line_num: u32},
+
+  // reported as an error if the lint is denied
+  Lint{lint: Lint, level: Level, line_num: u32, name: String}
+}
+
+impl Diagnostic {
+  pub fn print(&self) {
+    match self {
+      // the name of a lint says more than its code, so it is printed as well
+      Diagnostic::Lint{lint, ..} => println!("{} [{} {}]", self.message(), self.code(), lint.name()),
+      _ => println!("{} [{}]", self.message(), self.code())
+    }
+
+    for note in self.notes() {
+      println!("  {}", note);
+    }
+  }
+
+  // the first line of the diagnostic, saying what is wrong and where
+  pub fn message(&self) -> String {
+    return match (self, self.line_num()) {
+      (Diagnostic::ErrMissingReturn{start_line, end_line, ..}, _) => format!("({}-{}) - {}", start_line, end_line, self.text()),
+      (_, Some(line_num)) => format!("({}) - {}", line_num, self.text()),
+      (_, None) => self.text()
+    };
+  }
+
+  // what is wrong, without where
+  pub fn text(&self) -> String {
+    return match self {
+      Diagnostic::ErrUnexpectedEnd => String::from("Unexpected end of program"),
+      Diagnostic::ErrUnterminatedComment{..} => String::from("Block comment is never closed"),
+      Diagnostic::ErrUnrecognizedChar{chars, ..} => format!("Unrecognized character '{}'", chars),
+      Diagnostic::ErrUnexpectedTok{expected, actual, ..} => format!("Unexpected token - Expected: '{}', got: '{}'", expected, actual),
+      Diagnostic::ErrSymbolNotFound{name, ..} => format!("Symbol undefined: '{}'", name),
+      Diagnostic::ErrInvalidType{expected, actual, ..} => {
+        let mut expected_str = String::new();
+        for r#type in expected {
+          expected_str.push_str(&r#type.to_string()[..]);
+          expected_str.push_str(", ");
+        }
+        format!("Unexpected type: '{}', expected: [{}]", actual, expected_str)
+      },
+      Diagnostic::ErrNotArray{name, actual, ..} => format!("'{}' is indexed, but has type '{}' instead of an array type", name, actual),
+      Diagnostic::ErrNotProcedure{name, actual, ..} => format!("'{}' is called, but has type '{}' instead of being a procedure", name, actual),
+      Diagnostic::ErrLiteralRange{chars, r#type, ..} => format!("Literal '{}' is out of range for type '{}'", chars, r#type),
+      Diagnostic::ErrMissingReturn{name, ..} => format!("Not all paths in procedure '{}' return a value", name),
+      Diagnostic::ErrMissingEnd{construct, start_line, ..} => format!("Missing 'end {}' for the '{}' on line {}", construct, construct, start_line),
+      Diagnostic::ErrArgumentCount{name, params, count, ..} => {
+        let signature: Vec<String> = params.iter().map(|param| param.to_string()).collect();
+        let arguments = if params.len() == 1 { "argument" } else { "arguments" };
+        let were = if *count == 1 { "was" } else { "were" };
+
+        format!("Procedure '{}' takes {} {} but {} {} given, expected: '{}({})'", name, params.len(), arguments, count, were, name, signature.join(", "))
+      },
+      Diagnostic::ErrArgumentType{name, position, expected, actual, ..} => {
+        format!("Argument {} of '{}' has type '{}', expected: '{}'", position, name, actual, expected)
+      },
+      Diagnostic::ErrInternal{procedures, ..} => format!("Internal compiler error - Generated code failed verification in: '{}'", procedures.join("', '")),
+      Diagnostic::ErrCompilerBug{phase, msg} => format!("Internal compiler error in {} - {}", phase, msg),
+      Diagnostic::WarnUnreachable{..} => String::from("Warning: Unreachable statement after return"),
+      Diagnostic::Lint{lint, level, name, ..} => {
+        let severity = if *level == Level::Deny { "Error" } else { "Warning" };
+        format!("{}: {}", severity, lint.message(name))
+      }
+    };
+  }
+
+  // the lines printed under the message (indented), with more about the problem
+  pub fn notes(&self) -> Vec<String> {
+    return match self {
+      Diagnostic::ErrUnexpectedTok{suggestion: Some(suggestion), ..} | Diagnostic::ErrSymbolNotFound{suggestion: Some(suggestion), ..} => {
+        vec![format!("Help: did you mean '{}'?", suggestion)]
+      },
+      Diagnostic::ErrArgumentCount{name, decl_line, ..} | Diagnostic::ErrArgumentType{name, decl_line, ..} => vec![Diagnostic::declared(name, *decl_line)],
+      Diagnostic::ErrInternal{msg, ..} => msg.trim_end().lines().map(String::from).collect(),
+      _ => vec![]
+    };
+  }
+
+  // the stable code identifying the kind of problem, see `codes` for what each one means
+  pub fn code(&self) -> &'static str {
+    return match self {
+      Diagnostic::ErrUnterminatedComment{..} => "E0001",
+      Diagnostic::ErrUnrecognizedChar{..} => "E0002",
+      Diagnostic::ErrUnexpectedEnd => "E0101",
+      Diagnostic::ErrUnexpectedTok{..} => "E0102",
+      Diagnostic::ErrMissingEnd{..} => "E0103",
+      Diagnostic::ErrSymbolNotFound{..} => "E0201",
+      Diagnostic::ErrInvalidType{..} => "E0301",
+      Diagnostic::ErrNotArray{..} => "E0302",
+      Diagnostic::ErrNotProcedure{..} => "E0303",
+      Diagnostic::ErrLiteralRange{..} => "E0304",
+      Diagnostic::ErrMissingReturn{..} => "E0305",
+      Diagnostic::ErrArgumentCount{..} => "E0401",
+      Diagnostic::ErrArgumentType{..} => "E0402",
+      Diagnostic::ErrInternal{..} => "E0901",
+      Diagnostic::ErrCompilerBug{..} => "E0902",
+      Diagnostic::WarnUnreachable{..} => "W0001",
+      Diagnostic::Lint{lint, ..} => lint.code()
+    };
+  }
+
+  // the line the problem is on (for problems spanning lines, the first one)
+  pub fn line_num(&self) -> Option<u32> {
+    return match self {
+      Diagnostic::ErrUnexpectedEnd | Diagnostic::ErrInternal{..} | Diagnostic::ErrCompilerBug{..} => None,
+      Diagnostic::ErrUnterminatedComment{line_num} | Diagnostic::WarnUnreachable{line_num} => Some(*line_num),
+      Diagnostic::ErrUnrecognizedChar{line_num, ..} => Some(*line_num),
+      Diagnostic::ErrUnexpectedTok{line_num, ..} | Diagnostic::ErrSymbolNotFound{line_num, ..} => Some(*line_num),
+      Diagnostic::ErrInvalidType{line_num, ..} | Diagnostic::ErrNotArray{line_num, ..} | Diagnostic::ErrNotProcedure{line_num, ..} => Some(*line_num),
+      Diagnostic::ErrLiteralRange{line_num, ..} | Diagnostic::ErrMissingEnd{line_num, ..} => Some(*line_num),
+      Diagnostic::ErrArgumentCount{line_num, ..} | Diagnostic::ErrArgumentType{line_num, ..} => Some(*line_num),
+      Diagnostic::ErrMissingReturn{start_line, ..} => Some(*start_line),
+      Diagnostic::Lint{line_num, ..} => Some(*line_num)
+    };
+  }
+
+  // a note pointing to where a procedure was declared
+  fn declared(name: &str, decl_line: u32) -> String {
+    if decl_line == 0 {
+      return format!("Note: '{}' is a builtin procedure", name);
+    } else {
+      return format!("({}) - Note: '{}' is declared here", decl_line, name);
+    }
+  }
+
+  // warnings are reported, but don't stop the program from being compiled
+  pub fn is_error(&self) -> bool {
+    return match self {
+      Diagnostic::WarnUnreachable{..} => false,
+      Diagnostic::Lint{level, ..} => *level == Level::Deny,
+      _ => true
+    };
+  }
+}
+
+// the diagnostics reported for a program, in the order they were found
+#[derive(Clone)]
+pub struct Diagnostics {
+  pub list: Vec<Diagnostic>
+}
+
+impl Diagnostics {
+  pub fn new() -> Diagnostics {
+    return Diagnostics { list: vec![] };
+  }
+
+  pub fn push(&mut self, diagnostic: Diagnostic) {
+    self.list.push(diagnostic);
+  }
+
+  pub fn append(&mut self, other: Diagnostics) {
+    self.list.extend(other.list);
+  }
+
+  pub fn has_errors(&self) -> bool {
+    return self.list.iter().any(|diagnostic| diagnostic.is_error());
+  }
+
+  pub fn has_internal_error(&self) -> bool {
+    return self.list.iter().any(|diagnostic| matches!(diagnostic, Diagnostic::ErrInternal{..}));
+  }
+
+  pub fn print(&self) {
+    for diagnostic in &self.list {
+      diagnostic.print();
+    }
+  }
+}
+
+impl Default for Diagnostics {
+  fn default() -> Diagnostics {
+    return Diagnostics::new();
+  }
+}
@@ -0,0 +1,104 @@
+// the compiler's internal state for a program, printed with `--dump` while it is compiled
+
+use crate::index::{Index, Symbol, SymbolKind};
+use crate::json::{self, Value};
+use crate::options::Format;
+
+// a symbol table and the tables of the procedures declared in its scope
+struct Table {
+  kind: &'static str,
+  name: String,
+  symbols: Vec<usize>,
+  tables: Vec<Table>
+}
+
+// the symbol tables of a program, nested the way the checker opens them: the global table, the program's table, and
+// one table per procedure inside the scope it is declared in. Globals are in the global table wherever they are
+// declared. A procedure's own table also contains the procedure (so it can call itself), which isn't repeated here
+pub fn symtab(index: &Index, format: Format) -> String {
+  let global = Table {
+    kind: "global",
+    name: String::new(),
+    symbols: (0..index.symbols.len()).filter(|&id| index.symbols[id].global).collect(),
+    tables: vec![table(index, "program", String::new(), None)]
+  };
+
+  return match format {
+    Format::Text => {
+      let mut output = String::new();
+      table_text(index, &global, 0, &mut output);
+      output
+    },
+    Format::Json => format!("{}\n", table_json(index, &global).to_json())
+  };
+}
+
+// the table of the program (with no procedure), or of a procedure
+fn table(index: &Index, kind: &'static str, name: String, procedure: Option<usize>) -> Table {
+  let in_scope = |symbol: &Symbol| symbol.procedure == procedure && symbol.kind != SymbolKind::Builtin;
+
+  let symbols = (0..index.symbols.len()).filter(|&id| in_scope(&index.symbols[id]) && !index.symbols[id].global).collect();
+
+  let tables = (0..index.symbols.len())
+    .filter(|&id| in_scope(&index.symbols[id]) && index.symbols[id].kind == SymbolKind::Procedure)
+    .map(|id| table(index, "procedure", index.symbols[id].name.clone(), Some(id)))
+    .collect();
+
+  return Table { kind, name, symbols, tables };
+}
+
+fn table_text(index: &Index, table: &Table, depth: usize, output: &mut String) {
+  let indent = "  ".repeat(depth);
+
+  if table.name.is_empty() {
+    output.push_str(&format!("{}{}:\n", indent, table.kind));
+  } else {
+    output.push_str(&format!("{}{} {}:\n", indent, table.kind, table.name));
+  }
+
+  for &id in &table.symbols {
+    let symbol = &index.symbols[id];
+    let position = match symbol.kind {
+      SymbolKind::Builtin => String::new(),
+      _ => format!("{}:{}", symbol.line_num, symbol.span.column)
+    };
+
+    let line = format!("{}  {:<16} {:<10} {:<32} {}", indent, symbol.name, symbol.kind.name(), symbol.r#type.to_string(), position);
+    output.push_str(line.trim_end());
+    output.push('\n');
+  }
+
+  for inner in &table.tables {
+    table_text(index, inner, depth + 1, output);
+  }
+}
+
+fn table_json(index: &Index, table: &Table) -> Value {
+  let symbols = table.symbols.iter().map(|&id| {
+    let symbol = &index.symbols[id];
+    let (line, column) = match symbol.kind {
+      SymbolKind::Builtin => (Value::Null, Value::Null),
+      _ => (Value::Number(symbol.line_num as f64), Value::Number(symbol.span.column as f64))
+    };
+
+    json::object(vec![
+      ("name", Value::String(symbol.name.clone())),
+      ("kind", Value::String(String::from(symbol.kind.name()))),
+      ("type", Value::String(symbol.r#type.to_string())),
+      ("line", line),
+      ("column", column)
+    ])
+  }).collect();
+
+  let name = match &table.name[..] {
+    "" => Value::Null,
+    name => Value::String(String::from(name))
+  };
+
+  return json::object(vec![
+    ("kind", Value::String(String::from(table.kind))),
+    ("name", name),
+    ("symbols", Value::Array(symbols)),
+    ("tables", Value::Array(table.tables.iter().map(|inner| table_json(index, inner)).collect()))
+  ]);
+}
@@ -0,0 +1,142 @@
+// intermediate forms of a program, printed with `--emit`
+
+use crate::index::{Index, Symbol, SymbolKind};
+use crate::json::{self, Value};
+use crate::options::Format;
+use crate::tokenize::token::{Span, TokenEntry, Type};
+
+// one line per token with its position, kind, text and literal type
+pub fn tokens(tokens: &[TokenEntry], format: Format) -> String {
+  return match format {
+    Format::Text => tokens_text(tokens),
+    Format::Json => tokens_json(tokens)
+  };
+}
+
+fn tokens_text(tokens: &[TokenEntry]) -> String {
+  let mut output = String::new();
+
+  for token in tokens {
+    let position = format!("{}:{}", token.line_num, token.span.column);
+    let text = format!("{:?}", token.chars);
+    let span = format!("{}..{}", token.span.start, token.span.end);
+
+    output.push_str(&format!("{:<8} {:<14} {:<24} {:<8} {}\n", position, token.tok_type.get_example(), text, token.r#type.to_string(), span));
+  }
+
+  return output;
+}
+
+fn tokens_json(tokens: &[TokenEntry]) -> String {
+  let entries = tokens.iter().map(|token| {
+    let r#type = match token.r#type {
+      Type::None => Value::Null,
+      _ => Value::String(token.r#type.to_string())
+    };
+
+    json::object(vec![
+      ("kind", Value::String(String::from(token.tok_type.get_example()))),
+      ("text", Value::String(token.chars.clone())),
+      ("type", r#type),
+      ("line", Value::Number(token.line_num as f64)),
+      ("column", Value::Number(token.span.column as f64)),
+      ("start", Value::Number(token.span.start as f64)),
+      ("end", Value::Number(token.span.end as f64))
+    ])
+  }).collect();
+
+  return format!("{}\n", Value::Array(entries).to_json());
+}
+
+// every symbol in a program (builtins first, then in the order they are declared), followed by every reference to
+// them in the order they appear. References name the symbol they resolve to by its position in the list
+pub fn symbols(index: &Index, format: Format) -> String {
+  return match format {
+    Format::Text => symbols_text(index),
+    Format::Json => symbols_json(index)
+  };
+}
+
+// where a symbol can be used: everywhere, in the program, or in the procedure it is declared in
+fn scope(index: &Index, symbol: &Symbol) -> String {
+  if symbol.global {
+    return String::from("global");
+  }
+
+  return match symbol.procedure {
+    Some(procedure) => index.symbols[procedure].name.clone(),
+    None => String::from("program")
+  };
+}
+
+fn symbols_text(index: &Index) -> String {
+  let mut output = String::from("symbols:\n");
+
+  for (id, symbol) in index.symbols.iter().enumerate() {
+    let (position, span) = match symbol.kind {
+      SymbolKind::Builtin => (String::from("-"), String::from("-")),
+      _ => (format!("{}:{}", symbol.line_num, symbol.span.column), format!("{}..{}", symbol.span.start, symbol.span.end))
+    };
+
+    output.push_str(&format!("{:<4} {:<8} {:<10} {:<10} {:<16} {:<12} {}\n", id, position, span, symbol.kind.name(), symbol.name, scope(index, symbol), symbol.r#type));
+  }
+
+  output.push_str("references:\n");
+
+  for reference in &index.references {
+    let symbol = &index.symbols[reference.symbol];
+    let position = format!("{}:{}", reference.line_num, reference.span.column);
+    let span = format!("{}..{}", reference.span.start, reference.span.end);
+
+    output.push_str(&format!("{:<8} {:<10} {:<4} {:<10} {}\n", position, span, reference.symbol, symbol.kind.name(), symbol.name));
+  }
+
+  return output;
+}
+
+// the position of a symbol or reference, which builtins don't have
+fn location_json(line_num: u32, span: &Span, declared: bool) -> Vec<(&'static str, Value)> {
+  let number = |value: usize| if declared { Value::Number(value as f64) } else { Value::Null };
+
+  return vec![
+    ("line", number(line_num as usize)),
+    ("column", number(span.column as usize)),
+    ("start", number(span.start)),
+    ("end", number(span.end))
+  ];
+}
+
+fn symbols_json(index: &Index) -> String {
+  let symbols = index.symbols.iter().enumerate().map(|(id, symbol)| {
+    let mut entries = vec![
+      ("id", Value::Number(id as f64)),
+      ("name", Value::String(symbol.name.clone())),
+      ("kind", Value::String(String::from(symbol.kind.name()))),
+      ("type", Value::String(symbol.r#type.to_string())),
+      ("scope", Value::String(scope(index, symbol)))
+    ];
+    entries.append(&mut location_json(symbol.line_num, &symbol.span, symbol.kind != SymbolKind::Builtin));
+
+    json::object(entries)
+  }).collect();
+
+  let references = index.references.iter().map(|reference| {
+    let symbol = &index.symbols[reference.symbol];
+
+    let mut entries = vec![
+      ("symbol", Value::Number(reference.symbol as f64)),
+      ("name", Value::String(symbol.name.clone())),
+      ("kind", Value::String(String::from(symbol.kind.name())))
+    ];
+    entries.append(&mut location_json(reference.line_num, &reference.span, true));
+
+    json::object(entries)
+  }).collect();
+
+  let output = json::object(vec![
+    ("symbols", Value::Array(symbols)),
+    ("references", Value::Array(references))
+  ]);
+
+  return format!("{}\n", output.to_json());
+}
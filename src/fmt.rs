@@ -0,0 +1,207 @@
+// prints programs in the canonical style. The program is printed from its concrete syntax tree, so
+// comments are kept (everything else between tokens is replaced):
+// - keywords are lowercase, identifiers keep their casing
+// - every declaration and statement is on its own line
+// - procedure and statement bodies are indented by one level, declarations inside a procedure as well
+// - tokens are separated by single spaces, except around brackets and before ';', ',' and '.'
+// - at most one blank line is kept wherever the program had blank lines
+
+use crate::cst::{Cst, CstToken, Element, Node, SyntaxKind, Trivia, TriviaKind};
+use crate::tokenize::spec;
+use crate::tokenize::token::Token;
+
+const INDENT: &str = "  ";
+
+pub fn format(cst: &Cst) -> String {
+  let mut printer = Printer {
+    out: String::new(),
+    last: None,
+    unary: false,
+    indent: 0,
+    at_line_start: true,
+    needs_newline: false,
+    next_line: Some(0)
+  };
+
+  printer.node(&cst.root, 0);
+
+  // comments after the end of the program
+  printer.trivia(&cst.trailing, 0);
+  printer.out.push('\n');
+
+  return printer.out;
+}
+
+struct Printer {
+  out: String,
+
+  // the last token printed (none after a comment), and whether it was a unary minus
+  last: Option<Token>,
+  unary: bool,
+
+  // the indent level of the current line
+  indent: usize,
+  at_line_start: bool,
+
+  // a line comment was printed, so the line has to end before anything else
+  needs_newline: bool,
+
+  // the next token starts a new line at this indent level
+  next_line: Option<usize>
+}
+
+impl Printer {
+
+  fn node(&mut self, node: &Node, indent: usize) {
+    for child in &node.children {
+      match child {
+        Element::Token(token) => {
+          let starts_line = matches!((node.kind, token.kind),
+            (SyntaxKind::ProgramBody, Token::BeginKW) | (SyntaxKind::ProgramBody, Token::EndKW) |
+            (SyntaxKind::ProcedureBody, Token::BeginKW) | (SyntaxKind::ProcedureBody, Token::EndKW) |
+            (SyntaxKind::IfStatement, Token::ElseKW) | (SyntaxKind::IfStatement, Token::EndKW) |
+            (SyntaxKind::LoopStatement, Token::EndKW));
+
+          if starts_line {
+            self.next_line = Some(indent);
+          }
+
+          self.token(token);
+        },
+        Element::Node(child) => {
+          match child.kind {
+            // declarations in a procedure are indented, but the program's aren't
+            SyntaxKind::Declaration if node.kind == SyntaxKind::ProcedureBody => {
+              self.next_line = Some(indent + 1);
+              self.node(child, indent + 1);
+            },
+            SyntaxKind::Declaration => {
+              self.next_line = Some(indent);
+              self.node(child, indent);
+            },
+            SyntaxKind::Statement => {
+              self.next_line = Some(indent + 1);
+              self.node(child, indent + 1);
+            },
+            _ => self.node(child, indent)
+          }
+        }
+      }
+    }
+  }
+
+  fn token(&mut self, token: &CstToken) {
+    let line = self.next_line.take();
+
+    // comments before the end of a block belong to the block's body
+    let comment_indent = match (line, token.kind) {
+      (Some(indent), Token::EndKW) | (Some(indent), Token::ElseKW) => indent + 1,
+      (Some(indent), _) => indent,
+      (None, _) => self.indent + 1
+    };
+
+    let newlines = self.trivia(&token.leading, comment_indent);
+
+    if let Some(indent) = line {
+      self.newline(indent, newlines);
+    } else if self.needs_newline {
+      // the line was ended by a comment in the middle of a declaration or statement
+      let indent = self.indent + 1;
+      self.newline(indent, newlines);
+    } else if !self.at_line_start && self.spaced(token.kind) {
+      self.out.push(' ');
+    }
+
+    if spec::KEYWORDS.iter().any(|&(_, keyword)| keyword == token.kind) {
+      self.out.push_str(&token.text.to_lowercase());
+    } else {
+      self.out.push_str(&token.text);
+    }
+
+    self.unary = token.kind == Token::Dash && !self.after_operand();
+    self.last = Some(token.kind);
+    self.at_line_start = false;
+  }
+
+  // print the comments in some trivia, returning the number of newlines after the last one
+  fn trivia(&mut self, trivia: &[Trivia], indent: usize) -> usize {
+    let mut newlines = 0;
+
+    for item in trivia {
+      let text = match item.kind {
+        TriviaKind::Whitespace => {
+          newlines += item.text.matches('\n').count();
+          continue;
+        },
+        TriviaKind::LineComment => item.text.trim_end(),
+        TriviaKind::BlockComment => &item.text[..]
+      };
+
+      if newlines == 0 && !self.out.is_empty() && !self.needs_newline {
+        // a comment on the same line as the code before it stays there
+        if !self.at_line_start {
+          self.out.push(' ');
+        }
+      } else {
+        self.newline(indent, newlines);
+      }
+
+      self.out.push_str(text);
+
+      self.needs_newline = item.kind == TriviaKind::LineComment;
+      self.last = None;
+      self.at_line_start = false;
+      newlines = 0;
+    }
+
+    return newlines;
+  }
+
+  // start a new line, keeping one blank line if there was at least one in the program
+  fn newline(&mut self, indent: usize, newlines: usize) {
+    if !self.out.is_empty() {
+      self.out.push('\n');
+
+      if newlines > 1 {
+        self.out.push('\n');
+      }
+    }
+
+    self.out.push_str(&INDENT.repeat(indent));
+
+    self.indent = indent;
+    self.at_line_start = true;
+    self.needs_newline = false;
+  }
+
+  // whether the last token ends an operand (so a following '-' is a subtraction)
+  fn after_operand(&self) -> bool {
+    return matches!(self.last,
+      Some(Token::Identifier) | Some(Token::Number) | Some(Token::String) | Some(Token::TrueKW) | Some(Token::FalseKW) |
+      Some(Token::RParen) | Some(Token::RBracket));
+  }
+
+  // whether a space goes between the last token and the next one
+  fn spaced(&self, next: Token) -> bool {
+    let last = match self.last {
+      Some(last) => last,
+      // after a comment
+      None => return true
+    };
+
+    return match (last, next) {
+      (_, Token::Semicolon) | (_, Token::Comma) | (_, Token::Period) => false,
+      (_, Token::RParen) | (_, Token::RBracket) | (_, Token::RBrace) | (_, Token::LBracket) => false,
+      (Token::LParen, _) | (Token::LBracket, _) | (Token::LBrace, _) => false,
+      (Token::Dash, _) if self.unary => false,
+
+      // calls, and the parameters of a procedure after its return type
+      (Token::Identifier, Token::LParen) | (Token::RBrace, Token::LParen) => false,
+      (Token::IntegerKW, Token::LParen) | (Token::FloatKW, Token::LParen) => false,
+      (Token::StringKW, Token::LParen) | (Token::BoolKW, Token::LParen) => false,
+
+      _ => true
+    };
+  }
+
+}
@@ -0,0 +1,471 @@
+// keeping a program parsed while it is edited (e.g. in an editor). After an edit only the tokens the lexer looked at
+// around the edit are lexed again, and only the top level declarations with changed tokens are parsed again. Everything
+// else is kept, moved to where it is after the edit: the rest of the tokens, the other declarations and the body of
+// the program, and the symbols they declare
+// edits the declarations can't be reparsed on their own for (e.g. in the header or the body, or near syntax errors
+// that recovery may have skipped over) parse the whole program again from its tokens
+
+use std::ops::Range;
+
+use crate::ast::*;
+use crate::diagnostics::{Diagnostic, Diagnostics};
+use crate::index::Index;
+use crate::lexer::Lexer;
+use crate::parser::{Item, Parser};
+use crate::tokenize::token::{Span, Token, TokenEntry};
+
+// the text in a range of bytes being replaced
+pub struct Edit {
+  pub range: Range<usize>,
+  pub text: String
+}
+
+// how much of a document was kept by its last update
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reuse {
+  // how many tokens were lexed again
+  pub relexed: usize,
+
+  // how many top level declarations were parsed again (None if the whole program was)
+  pub reparsed: Option<usize>,
+
+  // whether the symbols outside the reparsed declarations were kept
+  pub symbols: bool
+}
+
+// where everything after a changed part of the program has moved
+pub struct Splice {
+  // the declarations of the program that were replaced, and how many replaced them
+  pub declarations: Range<usize>,
+  pub added: usize,
+
+  // the bytes of the replaced part, before the edit
+  pub bytes: Range<usize>,
+
+  // how far everything after the edit has moved
+  pub byte_delta: isize,
+  pub line_delta: i64,
+
+  // the line (before the edit) the text after the edit starts on, and how far the columns of tokens on it moved
+  pub line: u32,
+  pub column_delta: i64
+}
+
+impl Splice {
+  // move the position of something after the edit
+  pub fn shift(&self, line_num: &mut u32, span: &mut Span) {
+    if *line_num == self.line {
+      span.column = (span.column as i64 + self.column_delta) as u32;
+    }
+
+    span.start = (span.start as isize + self.byte_delta) as usize;
+    span.end = (span.end as isize + self.byte_delta) as usize;
+    self.shift_line(line_num);
+  }
+
+  pub fn shift_line(&self, line_num: &mut u32) {
+    *line_num = (*line_num as i64 + self.line_delta) as u32;
+  }
+}
+
+// the tokens changed by an edit
+struct Damage {
+  // the first changed token, and the first token after the changed ones before and after the edit
+  first: usize,
+  old_end: usize,
+  new_end: usize,
+
+  byte_delta: isize,
+  line_delta: i64,
+  line: u32,
+  column_delta: i64
+}
+
+pub struct Document {
+  pub text: String,
+  pub tokens: Vec<TokenEntry>,
+
+  // how far the lexer had looked when it read each token (see `Lexer::examined`)
+  examined: Vec<usize>,
+
+  // problems found by the lexer, which are also the first of the ast's diagnostics
+  lexer_diagnostics: Diagnostics,
+
+  pub ast: Ast,
+
+  // the items read for the program's top level declarations, see `parser::Item`
+  items: Vec<Item>,
+
+  pub index: Index,
+
+  pub reuse: Reuse
+}
+
+impl Document {
+
+  pub fn new(text: String) -> Document {
+    let mut document = Document {
+      text,
+      tokens: vec![],
+      examined: vec![],
+      lexer_diagnostics: Diagnostics::new(),
+      ast: Ast { program: None, diagnostics: Diagnostics::new() },
+      items: vec![],
+      index: Index::new(&Ast { program: None, diagnostics: Diagnostics::new() }),
+      reuse: Reuse { relexed: 0, reparsed: None, symbols: false }
+    };
+
+    let mut lexer = Lexer::new(&document.text);
+    while let Some(token) = lexer.next() {
+      document.tokens.push(token);
+      document.examined.push(lexer.examined);
+    }
+
+    document.lexer_diagnostics = lexer.diagnostics;
+    document.reuse.relexed = document.tokens.len();
+    document.parse();
+
+    return document;
+  }
+
+  // apply an edit, updating the tokens, the syntax tree and the index
+  pub fn edit(&mut self, edit: &Edit) {
+    // the lexer's problems are at the start of the ast's, and only parsing the whole program replaces them
+    let had_lexer_errors = !self.lexer_diagnostics.list.is_empty();
+
+    let damage = self.relex(edit);
+
+    self.reuse = Reuse { relexed: damage.new_end - damage.first, reparsed: None, symbols: false };
+
+    if had_lexer_errors || !self.reparse(&damage) {
+      self.parse();
+    }
+
+    debug!("incremental", "edit at {}..{}: {:?}", edit.range.start, edit.range.end, self.reuse);
+  }
+
+  // parse the whole program from its tokens
+  fn parse(&mut self) {
+    let (mut ast, items) = Parser::with_lexer(Lexer::from_tokens(&self.text, self.tokens.clone())).parse_items();
+
+    let mut diagnostics = self.lexer_diagnostics.clone();
+    diagnostics.append(ast.diagnostics);
+    ast.diagnostics = diagnostics;
+
+    self.index = Index::new(&ast);
+    self.ast = ast;
+    self.items = items;
+  }
+
+  // lex the text around an edit again, until the lexer is back at the start of a token it read before the edit
+  fn relex(&mut self, edit: &Edit) -> Damage {
+    let byte_delta = edit.text.len() as isize - edit.range.len() as isize;
+
+    // tokens read before the lexer looked at the edited text are unchanged
+    let first = self.examined.iter().position(|&examined| examined > edit.range.start).unwrap_or(self.tokens.len());
+
+    let (pos, line_num, column) = match first.checked_sub(1).map(|last| &self.tokens[last]) {
+      Some(token) => end_of(&self.text, token),
+      None => (0, 1, 1)
+    };
+
+    self.text.replace_range(edit.range.clone(), &edit.text);
+
+    // an unclosed comment reaches the end of the program, so the lexer can't catch up to the old tokens before it
+    let can_sync = !self.lexer_diagnostics.list.iter().any(|diagnostic| matches!(diagnostic, Diagnostic::ErrUnterminatedComment{..}));
+    let unchanged = edit.range.start + edit.text.len();
+
+    let mut lexer = Lexer::resume(&self.text, pos, line_num, column);
+    let mut tokens = vec![];
+    let mut examined = vec![];
+    let mut sync = None;
+
+    while let Some(token) = lexer.next() {
+      if can_sync && token.span.start >= unchanged {
+        let old_start = (token.span.start as isize - byte_delta) as usize;
+
+        if let Ok(index) = self.tokens[first..].binary_search_by_key(&old_start, |old| old.span.start) {
+          sync = Some((first + index, token));
+          break;
+        }
+      }
+
+      tokens.push(token);
+      examined.push(lexer.examined);
+    }
+
+    let unterminated = lexer.diagnostics.list.into_iter().filter(|diagnostic| matches!(diagnostic, Diagnostic::ErrUnterminatedComment{..}));
+
+    let (old_end, line_delta, line, column_delta) = match sync {
+      Some((old_end, token)) => {
+        let old = &self.tokens[old_end];
+        (old_end, token.line_num as i64 - old.line_num as i64, old.line_num, token.span.column as i64 - old.span.column as i64)
+      },
+      None => (self.tokens.len(), 0, 0, 0)
+    };
+
+    let damage = Damage {
+      first,
+      old_end,
+      new_end: first + tokens.len(),
+      byte_delta,
+      line_delta,
+      line,
+      column_delta
+    };
+
+    // the rest of the tokens are the same, but have moved
+    let splice = damage.splice();
+
+    for token in &mut self.tokens[old_end..] {
+      splice.shift(&mut token.line_num, &mut token.span);
+    }
+
+    for examined in &mut self.examined[old_end..] {
+      *examined = (*examined as isize + byte_delta) as usize;
+    }
+
+    self.tokens.splice(first..old_end, tokens);
+    self.examined.splice(first..old_end, examined);
+
+    // the lexer only read the text around the edit, but unrecognized characters are tokens of their own,
+    // so the ones in the rest of the program are found again from the tokens
+    self.lexer_diagnostics = Diagnostics::new();
+
+    for token in self.tokens.iter().filter(|token| token.tok_type == Token::Unknown) {
+      self.lexer_diagnostics.push(Diagnostic::ErrUnrecognizedChar{line_num: token.line_num, chars: token.chars.clone()});
+    }
+
+    for diagnostic in unterminated {
+      self.lexer_diagnostics.push(diagnostic);
+    }
+
+    return damage;
+  }
+
+  // parse the top level declarations with changed tokens again, and splice them into the syntax tree
+  // returns false if the whole program has to be parsed again
+  fn reparse(&mut self, damage: &Damage) -> bool {
+    if !self.lexer_diagnostics.list.is_empty() || self.ast.program.is_none() || self.items.is_empty() {
+      return false;
+    }
+
+    let items = &self.items;
+
+    // the changed tokens have to be in the declarations, before the token the body starts at. The header looks at the
+    // two tokens after it
+    if damage.first < items[0].tokens.start + 2 || damage.old_end > items[items.len() - 1].tokens.end {
+      return false;
+    }
+
+    // the items that read the changed tokens, or looked at them (at most two tokens ahead)
+    let mut lo = match items.iter().position(|item| item.tokens.end + 2 > damage.first) {
+      Some(lo) => lo,
+      None => return false
+    };
+    let mut hi = match items.iter().rposition(|item| item.tokens.start < damage.old_end.max(damage.first + 1)) {
+      Some(hi) => hi.max(lo),
+      None => return false
+    };
+
+    // an item starting where an error was reported can't report an error there. Items that recovered from errors
+    // may depend on the lines of the tokens before them, and on where the last error was
+    while lo > 0 && items[lo - 1].ends_with_error {
+      lo -= 1;
+    }
+
+    while hi + 1 < items.len() && !items[hi + 1].diagnostics.is_empty() {
+      hi += 1;
+    }
+
+    // the errors after the reparsed items would have to be moved
+    let diagnostics = items[lo].diagnostics.start..items[hi].diagnostics.end;
+    if damage.line_delta != 0 && diagnostics.end < self.ast.diagnostics.list.len() {
+      return false;
+    }
+
+    let token_delta = damage.new_end as isize - damage.old_end as isize;
+    let start = items[lo].tokens.start;
+    let end = (items[hi].tokens.end as isize + token_delta) as usize;
+
+    // the program has to go on after the declarations
+    if end >= self.tokens.len() {
+      return false;
+    }
+
+    // the parser can look two tokens past the declarations
+    let window = self.tokens[start..(end + 2).min(self.tokens.len())].to_vec();
+    let prev_line = self.tokens[start - 1].line_num;
+
+    let (declarations, new_items, new_diagnostics) = match Parser::with_lexer(Lexer::from_tokens(&self.text, window)).reparse_declarations(end - start, prev_line) {
+      Some(reparsed) => reparsed,
+      None => return false
+    };
+
+    // an error at the token after the declarations stops one being reported there, by the next item or by the body
+    // of the program. Those errors may never have been reported, so they can't be checked for
+    let ends_with_error = match new_items.last() {
+      Some(item) => item.ends_with_error,
+      None => false
+    };
+    if ends_with_error != items[hi].ends_with_error {
+      return false;
+    }
+
+    let first_declaration: usize = items[..lo].iter().map(|item| item.declarations).sum();
+    let replaced: usize = items[lo..=hi].iter().map(|item| item.declarations).sum();
+
+    let mut splice = damage.splice();
+    splice.declarations = first_declaration..first_declaration + replaced;
+    splice.added = declarations.len();
+    splice.bytes = self.tokens[start].span.start..(self.tokens[end].span.start as isize - damage.byte_delta) as usize;
+
+    // the items after the reparsed ones have moved, in the tokens and in the errors
+    let error_delta = new_diagnostics.list.len() as isize - diagnostics.len() as isize;
+
+    for item in &mut self.items[hi + 1..] {
+      item.tokens = shift_range(&item.tokens, token_delta);
+      item.diagnostics = shift_range(&item.diagnostics, error_delta);
+    }
+
+    let new_items: Vec<Item> = new_items.into_iter().map(|mut item| {
+      item.tokens = shift_range(&item.tokens, start as isize);
+      item.diagnostics = shift_range(&item.diagnostics, diagnostics.start as isize);
+      item
+    }).collect();
+
+    self.items.splice(lo..=hi, new_items);
+    self.ast.diagnostics.list.splice(diagnostics, new_diagnostics.list);
+
+    if let Some(program) = &mut self.ast.program {
+      program.declarations.splice(splice.declarations.clone(), declarations);
+
+      for declaration in &mut program.declarations[splice.declarations.start + splice.added..] {
+        shift_declaration(declaration, &splice);
+      }
+
+      shift_statements(&mut program.statements, &splice);
+    }
+
+    self.reuse.reparsed = Some(hi + 1 - lo);
+    self.reuse.symbols = self.index.update(&self.ast, &splice);
+
+    return true;
+  }
+
+}
+
+impl Damage {
+  // how the tokens after the changed ones have moved
+  fn splice(&self) -> Splice {
+    return Splice {
+      declarations: 0..0,
+      added: 0,
+      bytes: 0..0,
+      byte_delta: self.byte_delta,
+      line_delta: self.line_delta,
+      line: self.line,
+      column_delta: self.column_delta
+    };
+  }
+}
+
+// the byte offset, line and column just after a token
+fn end_of(text: &str, token: &TokenEntry) -> (usize, u32, u32) {
+  let chars = &text[token.span.start..token.span.end];
+
+  return match chars.rfind('\n') {
+    Some(last) => (token.span.end, token.line_num + chars.matches('\n').count() as u32, chars[last + 1..].chars().count() as u32 + 1),
+    None => (token.span.end, token.line_num, token.span.column + chars.chars().count() as u32)
+  };
+}
+
+fn shift_range(range: &Range<usize>, delta: isize) -> Range<usize> {
+  return (range.start as isize + delta) as usize..(range.end as isize + delta) as usize;
+}
+
+fn shift_identifier(identifier: &mut Identifier, splice: &Splice) {
+  splice.shift(&mut identifier.line_num, &mut identifier.span);
+}
+
+fn shift_declaration(declaration: &mut Declaration, splice: &Splice) {
+  match declaration {
+    Declaration::Procedure(procedure) => {
+      splice.shift_line(&mut procedure.line_num);
+      splice.shift_line(&mut procedure.end_line);
+      shift_identifier(&mut procedure.name, splice);
+
+      for param in &mut procedure.params {
+        shift_identifier(&mut param.name, splice);
+      }
+
+      for declaration in &mut procedure.declarations {
+        shift_declaration(declaration, splice);
+      }
+
+      shift_statements(&mut procedure.statements, splice);
+    },
+    Declaration::Variable(variable) => shift_identifier(&mut variable.variable.name, splice),
+    Declaration::Type(type_declaration) => shift_identifier(&mut type_declaration.name, splice)
+  }
+}
+
+fn shift_statements(statements: &mut [Statement], splice: &Splice) {
+  for statement in statements {
+    match statement {
+      Statement::Assignment(assignment) => shift_assignment(assignment, splice),
+      Statement::If(if_statement) => {
+        splice.shift_line(&mut if_statement.line_num);
+        splice.shift_line(&mut if_statement.condition_line);
+        shift_expression(&mut if_statement.condition, splice);
+        shift_statements(&mut if_statement.then_statements, splice);
+        shift_statements(&mut if_statement.else_statements, splice);
+      },
+      Statement::Loop(loop_statement) => {
+        splice.shift_line(&mut loop_statement.line_num);
+        splice.shift_line(&mut loop_statement.condition_line);
+        shift_assignment(&mut loop_statement.assignment, splice);
+        shift_expression(&mut loop_statement.condition, splice);
+        shift_statements(&mut loop_statement.statements, splice);
+      },
+      Statement::Return(return_statement) => {
+        splice.shift_line(&mut return_statement.line_num);
+        shift_expression(&mut return_statement.expression, splice);
+      }
+    }
+  }
+}
+
+fn shift_assignment(assignment: &mut Assignment, splice: &Splice) {
+  splice.shift_line(&mut assignment.line_num);
+  shift_name(&mut assignment.destination, splice);
+  shift_expression(&mut assignment.expression, splice);
+}
+
+fn shift_name(name: &mut Name, splice: &Splice) {
+  shift_identifier(&mut name.identifier, splice);
+
+  if let Some(index) = &mut name.index {
+    shift_expression(index, splice);
+  }
+}
+
+fn shift_expression(expression: &mut Expr, splice: &Splice) {
+  match expression {
+    Expr::Literal{line_num, ..} => splice.shift_line(line_num),
+    Expr::Name(name) => shift_name(name, splice),
+    Expr::Call{procedure, args} => {
+      shift_identifier(procedure, splice);
+
+      for arg in args {
+        shift_expression(arg, splice);
+      }
+    },
+    Expr::Negate(operand) | Expr::Not(operand) => shift_expression(operand, splice),
+    Expr::Binary{left, right, ..} => {
+      shift_expression(left, splice);
+      shift_expression(right, splice);
+    }
+  }
+}
+
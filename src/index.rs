@@ -0,0 +1,404 @@
+// an index of every declaration in a program and every reference to them. Names are resolved the same way
+// the checker resolves them (in the current scope, then the global one), but the index is kept once it has been
+// built, so tools like the language server can look symbols up by position
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::ast::*;
+use crate::builtins;
+use crate::incremental::Splice;
+use crate::tokenize::token::{Span, Type};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SymbolKind {
+  Variable,
+  Parameter,
+  Procedure,
+  Type,
+  Builtin
+}
+
+impl SymbolKind {
+  pub fn name(&self) -> &'static str {
+    return match self {
+      SymbolKind::Variable => "variable",
+      SymbolKind::Parameter => "parameter",
+      SymbolKind::Procedure => "procedure",
+      SymbolKind::Type => "type",
+      SymbolKind::Builtin => "builtin"
+    };
+  }
+}
+
+pub struct Symbol {
+  pub name: String,
+  pub kind: SymbolKind,
+  pub r#type: Type,
+
+  // where the symbol is declared (line 0 and an empty span for builtins)
+  pub line_num: u32,
+  pub span: Span,
+
+  // whether the symbol is in the global table, so it is visible everywhere after its declaration
+  pub global: bool,
+
+  // the procedure the symbol is declared in (none for the program's scope)
+  pub procedure: Option<usize>,
+
+  // for procedures, the first and last line of the declaration
+  pub lines: Option<(u32, u32)>
+}
+
+// a use of a declared name
+pub struct Reference {
+  pub symbol: usize,
+  pub line_num: u32,
+  pub span: Span
+}
+
+pub struct Index {
+  // every symbol, in the order they were declared (builtins first)
+  pub symbols: Vec<Symbol>,
+
+  // every reference, in the order they appear in the program
+  pub references: Vec<Reference>,
+
+  // the symbols declared by each of the program's declarations (including those inside procedures)
+  pub declarations: Vec<Range<usize>>
+}
+
+impl Index {
+
+  pub fn new(ast: &Ast) -> Index {
+    let mut indexer = Indexer::new(0, vec![]);
+
+    if let Some(program) = &ast.program {
+      indexer.program(program);
+    }
+
+    indexer.index.references.sort_by_key(|reference| reference.span.start);
+
+    return indexer.index;
+  }
+
+  // update the index after some of the program's declarations have been parsed again. If they declare the same
+  // symbols as before, only they are indexed again, and everything else is kept. Otherwise the whole program is
+  // indexed again. Returns whether the rest of the index was kept
+  pub fn update(&mut self, ast: &Ast, splice: &Splice) -> bool {
+    let program = match &ast.program {
+      Some(program) if splice.added == splice.declarations.len() => program,
+      _ => {
+        *self = Index::new(ast);
+        return false;
+      }
+    };
+
+    // the symbols declared by the replaced declarations
+    let first = match self.declarations.get(splice.declarations.start) {
+      Some(symbols) => symbols.start,
+      None => self.symbols.len()
+    };
+    let end = match splice.declarations.end.checked_sub(1).and_then(|last| self.declarations.get(last)) {
+      Some(symbols) if !splice.declarations.is_empty() => symbols.end,
+      _ => first
+    };
+
+    // the tables as they were before the first replaced declaration
+    let mut global = HashMap::new();
+    let mut local = HashMap::new();
+
+    for (id, symbol) in self.symbols[..first].iter().enumerate() {
+      if symbol.global {
+        global.insert(symbol.name.clone(), id);
+      } else if symbol.procedure.is_none() {
+        local.insert(symbol.name.clone(), id);
+      }
+    }
+
+    let mut indexer = Indexer::new(first, vec![global, local]);
+
+    for declaration in &program.declarations[splice.declarations.start..splice.declarations.start + splice.added] {
+      indexer.declaration(declaration);
+    }
+
+    let same = indexer.index.symbols.len() == end - first && indexer.index.symbols.iter().zip(&self.symbols[first..end]).all(|(new, old)| {
+      new.name == old.name && new.kind == old.kind && new.r#type == old.r#type && new.global == old.global && new.procedure == old.procedure
+    });
+
+    if !same {
+      *self = Index::new(ast);
+      return false;
+    }
+
+    // the ids of the symbols haven't changed, so references to them from the rest of the program are still right
+    self.symbols.splice(first..end, indexer.index.symbols);
+
+    for symbol in &mut self.symbols[end..] {
+      splice.shift(&mut symbol.line_num, &mut symbol.span);
+
+      if let Some((start, end)) = &mut symbol.lines {
+        splice.shift_line(start);
+        splice.shift_line(end);
+      }
+    }
+
+    let start = self.references.iter().position(|reference| reference.span.start >= splice.bytes.start).unwrap_or(self.references.len());
+    let stop = self.references.iter().position(|reference| reference.span.start >= splice.bytes.end).unwrap_or(self.references.len());
+
+    for reference in &mut self.references[stop..] {
+      splice.shift(&mut reference.line_num, &mut reference.span);
+    }
+
+    indexer.index.references.sort_by_key(|reference| reference.span.start);
+    self.references.splice(start..stop, indexer.index.references);
+
+    return true;
+  }
+
+  // the symbol declared or referenced at a byte offset (which can be just after the name)
+  pub fn symbol_at(&self, offset: usize) -> Option<usize> {
+    let contains = |span: &Span| span.start <= offset && offset <= span.end && span.start < span.end;
+
+    if let Some(reference) = self.references.iter().find(|reference| contains(&reference.span)) {
+      return Some(reference.symbol);
+    }
+
+    return self.symbols.iter().position(|symbol| contains(&symbol.span));
+  }
+
+  pub fn references_to(&self, symbol: usize) -> Vec<&Reference> {
+    return self.references.iter().filter(|reference| reference.symbol == symbol).collect();
+  }
+
+  // the symbols that can be used on a line. A local symbol hides a global one with the same name
+  pub fn visible_at(&self, line_num: u32) -> Vec<usize> {
+    // the innermost procedure the line is in
+    let procedure = self.symbols.iter().enumerate()
+      .filter(|(_, symbol)| match symbol.lines {
+        Some((start, end)) => start <= line_num && line_num <= end,
+        None => false
+      })
+      .max_by_key(|(_, symbol)| symbol.line_num)
+      .map(|(id, _)| id);
+
+    let mut visible: Vec<usize> = vec![];
+
+    for (id, symbol) in self.symbols.iter().enumerate() {
+      // the procedure can call itself
+      let in_scope = (symbol.procedure == procedure && !symbol.global) || Some(id) == procedure;
+
+      if symbol.line_num > line_num || !(in_scope || symbol.global) {
+        continue;
+      }
+
+      match visible.iter().position(|&other| self.symbols[other].name == symbol.name) {
+        Some(index) if in_scope => visible[index] = id,
+        Some(_) => (),
+        None => visible.push(id)
+      }
+    }
+
+    return visible;
+  }
+
+}
+
+// walks a parsed program, declaring and resolving names the same way the checker does
+struct Indexer {
+  index: Index,
+
+  // the id of the first symbol the indexer declares
+  base: usize,
+
+  // the global table, then the tables of the scopes being indexed (by name, into the symbols)
+  scopes: Vec<HashMap<String, usize>>,
+
+  // the procedure whose body is being indexed
+  procedure: Option<usize>
+}
+
+impl Indexer {
+
+  fn new(base: usize, scopes: Vec<HashMap<String, usize>>) -> Indexer {
+    return Indexer {
+      index: Index { symbols: vec![], references: vec![], declarations: vec![] },
+      base,
+      scopes,
+      procedure: None
+    };
+  }
+
+  fn program(&mut self, program: &Program) {
+    self.scopes.push(HashMap::new());
+
+    for builtin in builtins::initialize_builtins() {
+      let symbol = Symbol {
+        name: builtin.chars,
+        kind: SymbolKind::Builtin,
+        r#type: builtin.r#type,
+        line_num: builtin.line_num,
+        span: Span::default(),
+        global: true,
+        procedure: None,
+        lines: None
+      };
+
+      self.declare(Scope::Global, symbol);
+    }
+
+    self.scopes.push(HashMap::new());
+
+    for declaration in &program.declarations {
+      let first = self.base + self.index.symbols.len();
+      self.declaration(declaration);
+      self.index.declarations.push(first..self.base + self.index.symbols.len());
+    }
+
+    self.statements(&program.statements);
+
+    self.scopes.pop();
+    self.scopes.pop();
+  }
+
+  fn declarations(&mut self, declarations: &[Declaration]) {
+    for declaration in declarations {
+      self.declaration(declaration);
+    }
+  }
+
+  fn declaration(&mut self, declaration: &Declaration) {
+    match declaration {
+      Declaration::Procedure(procedure) => self.procedure_declaration(procedure),
+      Declaration::Variable(variable) => {
+        let symbol = self.symbol(&variable.variable.name, SymbolKind::Variable, variable.variable.r#type.clone(), variable.scope);
+        self.declare(variable.scope, symbol);
+      },
+      Declaration::Type(type_declaration) => {
+        let symbol = self.symbol(&type_declaration.name, SymbolKind::Type, type_declaration.r#type.clone(), type_declaration.scope);
+        self.declare(type_declaration.scope, symbol);
+      }
+    }
+  }
+
+  fn procedure_declaration(&mut self, procedure: &ProcedureDeclaration) {
+    let mut symbol = self.symbol(&procedure.name, SymbolKind::Procedure, procedure.r#type(), procedure.scope);
+    symbol.lines = Some((procedure.line_num, procedure.end_line));
+
+    let id = self.declare(procedure.scope, symbol);
+
+    // the procedure can call itself
+    let mut table = HashMap::new();
+    table.insert(String::from(&procedure.name.name[..]), id);
+    self.scopes.push(table);
+
+    let outer_procedure = self.procedure.replace(id);
+
+    for param in &procedure.params {
+      let symbol = self.symbol(&param.name, SymbolKind::Parameter, param.r#type.clone(), Scope::Local);
+      self.declare(Scope::Local, symbol);
+    }
+
+    self.declarations(&procedure.declarations);
+    self.statements(&procedure.statements);
+
+    self.procedure = outer_procedure;
+    self.scopes.pop();
+  }
+
+  fn statements(&mut self, statements: &[Statement]) {
+    for statement in statements {
+      match statement {
+        Statement::Assignment(assignment) => self.assignment(assignment),
+        Statement::If(if_statement) => {
+          self.expression(&if_statement.condition);
+          self.statements(&if_statement.then_statements);
+          self.statements(&if_statement.else_statements);
+        },
+        Statement::Loop(loop_statement) => {
+          self.assignment(&loop_statement.assignment);
+          self.expression(&loop_statement.condition);
+          self.statements(&loop_statement.statements);
+        },
+        Statement::Return(return_statement) => self.expression(&return_statement.expression)
+      }
+    }
+  }
+
+  fn assignment(&mut self, assignment: &Assignment) {
+    self.name(&assignment.destination);
+    self.expression(&assignment.expression);
+  }
+
+  fn expression(&mut self, expression: &Expr) {
+    match expression {
+      Expr::Literal{..} => (),
+      Expr::Name(name) => self.name(name),
+      Expr::Call{procedure, args} => {
+        self.reference(procedure);
+
+        for arg in args {
+          self.expression(arg);
+        }
+      },
+      Expr::Negate(operand) | Expr::Not(operand) => self.expression(operand),
+      Expr::Binary{left, right, ..} => {
+        self.expression(left);
+        self.expression(right);
+      }
+    }
+  }
+
+  fn name(&mut self, name: &Name) {
+    self.reference(&name.identifier);
+
+    if let Some(index) = &name.index {
+      self.expression(index);
+    }
+  }
+
+  // record a use of a name, if it is declared
+  fn reference(&mut self, identifier: &Identifier) {
+    // look in the current scope, then the global one (like `Checker::get_symbol`)
+    let local = self.scopes.last().and_then(|table| table.get(&identifier.name));
+    let global = self.scopes.first().and_then(|table| table.get(&identifier.name));
+
+    if let Some(&symbol) = local.or(global) {
+      self.index.references.push(Reference { symbol, line_num: identifier.line_num, span: identifier.span });
+    }
+  }
+
+  fn declare(&mut self, scope: Scope, symbol: Symbol) -> usize {
+    let id = self.base + self.index.symbols.len();
+
+    let table = match scope {
+      Scope::Local => self.scopes.last_mut(),
+      Scope::Global => self.scopes.first_mut()
+    };
+
+    if let Some(table) = table {
+      table.insert(symbol.name.clone(), id);
+    }
+
+    self.index.symbols.push(symbol);
+
+    return id;
+  }
+
+  fn symbol(&self, identifier: &Identifier, kind: SymbolKind, r#type: Type, scope: Scope) -> Symbol {
+    return Symbol {
+      name: String::from(&identifier.name[..]),
+      kind,
+      r#type,
+      line_num: identifier.line_num,
+      span: identifier.span,
+      global: match scope {
+        Scope::Global => true,
+        Scope::Local => false
+      },
+      procedure: self.procedure,
+      lines: None
+    };
+  }
+
+}
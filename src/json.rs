@@ -0,0 +1,322 @@
+// helpers for reading and writing json by hand
+
+// a quoted json string
+pub fn string(value: &str) -> String {
+  let mut quoted = String::from("\"");
+
+  for ch in value.chars() {
+    match ch {
+      '"' => quoted.push_str("\\\""),
+      '\\' => quoted.push_str("\\\\"),
+      '\n' => quoted.push_str("\\n"),
+      '\r' => quoted.push_str("\\r"),
+      '\t' => quoted.push_str("\\t"),
+      ch if (ch as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", ch as u32)),
+      ch => quoted.push(ch)
+    }
+  }
+
+  quoted.push('"');
+
+  return quoted;
+}
+
+// a parsed json value. Objects keep their keys in order
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+  Null,
+  Bool(bool),
+  Number(f64),
+  String(String),
+  Array(Vec<Value>),
+  Object(Vec<(String, Value)>)
+}
+
+impl Value {
+
+  // the value of a key in an object (null if it isn't there, or this isn't an object)
+  pub fn get(&self, key: &str) -> &Value {
+    if let Value::Object(entries) = self {
+      if let Some((_, value)) = entries.iter().find(|(other, _)| other == key) {
+        return value;
+      }
+    }
+
+    return &Value::Null;
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    return match self {
+      Value::String(value) => Some(value),
+      _ => None
+    };
+  }
+
+  pub fn as_u64(&self) -> Option<u64> {
+    return match self {
+      Value::Number(value) if *value >= 0.0 && value.fract() == 0.0 => Some(*value as u64),
+      _ => None
+    };
+  }
+
+  pub fn as_bool(&self) -> Option<bool> {
+    return match self {
+      Value::Bool(value) => Some(*value),
+      _ => None
+    };
+  }
+
+  pub fn as_array(&self) -> &[Value] {
+    return match self {
+      Value::Array(values) => values,
+      _ => &[]
+    };
+  }
+
+  pub fn is_null(&self) -> bool {
+    return *self == Value::Null;
+  }
+
+  // the value as compact json
+  pub fn to_json(&self) -> String {
+    return match self {
+      Value::Null => String::from("null"),
+      Value::Bool(value) => value.to_string(),
+      // whole numbers are written without a fraction
+      Value::Number(value) if value.fract() == 0.0 && value.abs() < 1e15 => format!("{}", *value as i64),
+      Value::Number(value) => value.to_string(),
+      Value::String(value) => string(value),
+      Value::Array(values) => {
+        let values: Vec<String> = values.iter().map(|value| value.to_json()).collect();
+        format!("[{}]", values.join(","))
+      },
+      Value::Object(entries) => {
+        let entries: Vec<String> = entries.iter().map(|(key, value)| format!("{}:{}", string(key), value.to_json())).collect();
+        format!("{{{}}}", entries.join(","))
+      }
+    };
+  }
+
+}
+
+// an object from its entries
+pub fn object(entries: Vec<(&str, Value)>) -> Value {
+  return Value::Object(entries.into_iter().map(|(key, value)| (String::from(key), value)).collect());
+}
+
+// parse a json document
+pub fn parse(text: &str) -> Result<Value, String> {
+  let mut parser = JsonParser { chars: text.chars().collect(), pos: 0, depth: 0 };
+
+  let value = parser.value()?;
+
+  parser.skip_ws();
+  if parser.pos < parser.chars.len() {
+    return Err(format!("Unexpected '{}' after the json value", parser.chars[parser.pos]));
+  }
+
+  return Ok(value);
+}
+
+// how deeply arrays and objects can be nested. Each level is parsed recursively,
+// so without a limit a deeply nested document would overflow the stack
+const MAX_DEPTH: usize = 128;
+
+struct JsonParser {
+  chars: Vec<char>,
+  pos: usize,
+
+  // how many arrays and objects the parser is inside of
+  depth: usize
+}
+
+impl JsonParser {
+
+  fn value(&mut self) -> Result<Value, String> {
+    self.skip_ws();
+
+    return match self.peek() {
+      Some('{') => self.nested(JsonParser::object),
+      Some('[') => self.nested(JsonParser::array),
+      Some('"') => Ok(Value::String(self.string()?)),
+      Some('t') => self.word("true", Value::Bool(true)),
+      Some('f') => self.word("false", Value::Bool(false)),
+      Some('n') => self.word("null", Value::Null),
+      Some(ch) if ch == '-' || ch.is_ascii_digit() => self.number(),
+      Some(ch) => Err(format!("Unexpected '{}' in json", ch)),
+      None => Err(String::from("Unexpected end of json"))
+    };
+  }
+
+  // parse an array or object, one level deeper
+  fn nested(&mut self, parse: fn(&mut JsonParser) -> Result<Value, String>) -> Result<Value, String> {
+    if self.depth >= MAX_DEPTH {
+      return Err(format!("Json is nested more than {} levels deep", MAX_DEPTH));
+    }
+
+    self.depth += 1;
+    let value = parse(self);
+    self.depth -= 1;
+
+    return value;
+  }
+
+  fn object(&mut self) -> Result<Value, String> {
+    self.expect('{')?;
+
+    let mut entries = vec![];
+
+    self.skip_ws();
+    if self.peek() == Some('}') {
+      self.pos += 1;
+      return Ok(Value::Object(entries));
+    }
+
+    loop {
+      self.skip_ws();
+      let key = self.string()?;
+
+      self.skip_ws();
+      self.expect(':')?;
+
+      entries.push((key, self.value()?));
+
+      self.skip_ws();
+      match self.next() {
+        Some(',') => continue,
+        Some('}') => return Ok(Value::Object(entries)),
+        _ => return Err(String::from("Expected ',' or '}' in json object"))
+      }
+    }
+  }
+
+  fn array(&mut self) -> Result<Value, String> {
+    self.expect('[')?;
+
+    let mut values = vec![];
+
+    self.skip_ws();
+    if self.peek() == Some(']') {
+      self.pos += 1;
+      return Ok(Value::Array(values));
+    }
+
+    loop {
+      values.push(self.value()?);
+
+      self.skip_ws();
+      match self.next() {
+        Some(',') => continue,
+        Some(']') => return Ok(Value::Array(values)),
+        _ => return Err(String::from("Expected ',' or ']' in json array"))
+      }
+    }
+  }
+
+  fn string(&mut self) -> Result<String, String> {
+    self.expect('"')?;
+
+    let mut value = String::new();
+
+    loop {
+      match self.next() {
+        Some('"') => return Ok(value),
+        Some('\\') => {
+          let escaped = match self.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+              let high = self.hex()?;
+
+              // characters outside the basic plane are written as a surrogate pair
+              let code = if (0xd800..0xdc00).contains(&high) && self.chars[self.pos..].starts_with(&['\\', 'u']) {
+                self.pos += 2;
+                let low = self.hex()?;
+                0x10000 + ((high - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff)
+              } else {
+                high
+              };
+
+              std::char::from_u32(code).unwrap_or('\u{fffd}')
+            },
+            _ => return Err(String::from("Invalid escape in json string"))
+          };
+
+          value.push(escaped);
+        },
+        Some(ch) => value.push(ch),
+        None => return Err(String::from("Unterminated json string"))
+      }
+    }
+  }
+
+  // the 4 hex digits of a '\u' escape
+  fn hex(&mut self) -> Result<u32, String> {
+    let digits: String = self.chars.iter().skip(self.pos).take(4).collect();
+    self.pos += 4;
+
+    // `from_str_radix` also accepts fewer digits and a leading sign
+    return match u32::from_str_radix(&digits, 16) {
+      Ok(code) if digits.len() == 4 && digits.chars().all(|ch| ch.is_ascii_hexdigit()) => Ok(code),
+      _ => Err(format!("Invalid escape '\\u{}' in json string", digits))
+    };
+  }
+
+  fn number(&mut self) -> Result<Value, String> {
+    let start = self.pos;
+
+    while let Some(ch) = self.peek() {
+      if ch.is_ascii_digit() || ch == '-' || ch == '+' || ch == '.' || ch == 'e' || ch == 'E' {
+        self.pos += 1;
+      } else {
+        break;
+      }
+    }
+
+    let text: String = self.chars[start..self.pos].iter().collect();
+
+    return text.parse::<f64>().map(Value::Number).map_err(|_| format!("Invalid number '{}' in json", text));
+  }
+
+  fn word(&mut self, word: &str, value: Value) -> Result<Value, String> {
+    for expected in word.chars() {
+      if self.next() != Some(expected) {
+        return Err(format!("Expected '{}' in json", word));
+      }
+    }
+
+    return Ok(value);
+  }
+
+  fn expect(&mut self, expected: char) -> Result<(), String> {
+    return match self.next() {
+      Some(ch) if ch == expected => Ok(()),
+      Some(ch) => Err(format!("Expected '{}' in json, got '{}'", expected, ch)),
+      None => Err(format!("Expected '{}' in json, got the end", expected))
+    };
+  }
+
+  fn skip_ws(&mut self) {
+    while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = self.peek() {
+      self.pos += 1;
+    }
+  }
+
+  fn peek(&self) -> Option<char> {
+    return self.chars.get(self.pos).cloned();
+  }
+
+  fn next(&mut self) -> Option<char> {
+    let ch = self.peek();
+    self.pos += 1;
+
+    return ch;
+  }
+
+}
@@ -1,105 +1,83 @@
-use std::iter::Peekable;
-use std::str::Chars;
-
-// bring Token into scope so we can use its associated items
-use crate::tokenize::lexable::Lexable;
 use crate::tokenize::token::Token;
 use crate::tokenize::token::TokenEntry;
+use crate::tokenize::token::Span;
 use crate::tokenize::token::Type;
 use crate::tokenize::char_group::CharGroup;
+use crate::tokenize::dfa::Dfa;
+use crate::tokenize::literals;
+use crate::diagnostics::{Diagnostic, Diagnostics};
 
-use crate::tokens;
-
-// import llvm dependencies
-use llvm_sys::core;
-use crate::llvm_utils;
+use std::collections::VecDeque;
 
 pub struct Lexer<'a> {
-  pub program: Peekable<Chars<'a>>,
+  pub program: &'a str,
+
+  // byte offset of the next unread character
+  pub pos: usize,
+
   pub line_num: u32,
-  pub errors: Vec<String>
+
+  // column (in characters, starting at 1) of the next unread character
+  pub column: u32,
+
+  // problems with the program found while lexing (e.g. unterminated comments)
+  pub diagnostics: Diagnostics,
+
+  // one past the furthest byte the lexer has looked at. A token can only change if the program changes before this
+  pub examined: usize,
+
+  dfa: &'static Dfa,
+
+  // tokens that have been lexed by `peek` and `peek_second`, but not consumed yet
+  peeked: VecDeque<TokenEntry>
 }
 
 impl <'a> Lexer<'a> {
 
-  pub fn new(program: Peekable<Chars<'a>>) -> Self {
-    return Lexer {program: program, line_num: 1, errors: vec![]};
+  pub fn new(program: &'a str) -> Self {
+    return Lexer::resume(program, 0, 1, 1);
+  }
+
+  // lex the rest of a program from a byte offset, which is at the given line and column
+  pub fn resume(program: &'a str, pos: usize, line_num: u32, column: u32) -> Self {
+    return Lexer {program, pos, line_num, column, diagnostics: Diagnostics::new(), examined: pos, dfa: Dfa::get(), peeked: VecDeque::new()};
   }
-  
-  fn all_tokens() -> Vec<Token> {
-  
-    // TODO put all these into a direct declaration rather than adding them all in
-    let mut token_types = Vec::new();
-    
-    // catch all token type. Goes first so that liveness will be overwritten by other token types
-    token_types.push(tokens::unknown::Unknown::start());
-    
-    token_types.push(tokens::period::Period::start());
-    token_types.push(tokens::semicolon::Semicolon::start());
-    token_types.push(tokens::colon::Colon::start());
-    token_types.push(tokens::parens::LParen::start());
-    token_types.push(tokens::parens::RParen::start());
-    token_types.push(tokens::comma::Comma::start());
-    token_types.push(tokens::braces::LBrace::start());
-    token_types.push(tokens::braces::RBrace::start());
-    token_types.push(tokens::dash::Dash::start());
-    token_types.push(tokens::brackets::LBracket::start());
-    token_types.push(tokens::brackets::RBracket::start());
-    token_types.push(tokens::pipe::Pipe::start());
-    token_types.push(tokens::ampersand::Ampersand::start());
-    token_types.push(tokens::plus::Plus::start());
-    token_types.push(tokens::lt::LT::start());
-    token_types.push(tokens::gt::GT::start());
-    token_types.push(tokens::lte::LTE::start());
-    token_types.push(tokens::gte::GTE::start());
-    token_types.push(tokens::eq::EQ::start());
-    token_types.push(tokens::neq::NEQ::start());
-    token_types.push(tokens::asterisk::Asterisk::start());
-    token_types.push(tokens::slash::Slash::start());
-    token_types.push(tokens::assign::Assign::start());
-    
-    token_types.push(tokens::identifier::Identifier::start());
-    token_types.push(tokens::number::Number::start());
-    token_types.push(tokens::string::String::start());
-    
-    // These must be last (except comments) so that they overwrite others
-    token_types.push(tokens::program_kw::ProgramKW::start());
-    token_types.push(tokens::begin_kw::BeginKW::start());
-    token_types.push(tokens::end_kw::EndKW::start());
-    token_types.push(tokens::is_kw::IsKW::start());
-    token_types.push(tokens::global_kw::GlobalKW::start());
-    token_types.push(tokens::procedure_kw::ProcedureKW::start());
-    token_types.push(tokens::variable_kw::VariableKW::start());
-    token_types.push(tokens::type_kw::TypeKW::start());
-    token_types.push(tokens::integer_kw::IntegerKW::start());
-    token_types.push(tokens::float_kw::FloatKW::start());
-    token_types.push(tokens::string_kw::StringKW::start());
-    token_types.push(tokens::bool_kw::BoolKW::start());
-    token_types.push(tokens::enum_kw::EnumKW::start());
-    token_types.push(tokens::if_kw::IfKW::start());
-    token_types.push(tokens::then_kw::ThenKW::start());
-    token_types.push(tokens::else_kw::ElseKW::start());
-    token_types.push(tokens::for_kw::ForKW::start());
-    token_types.push(tokens::return_kw::ReturnKW::start());
-    token_types.push(tokens::not_kw::NotKW::start());
-    token_types.push(tokens::true_kw::TrueKW::start());
-    token_types.push(tokens::false_kw::FalseKW::start());
-    
-    // comments must go last to overwrite everything else
-    token_types.push(tokens::line_comment::LineComment::start());
-    
-    // this currently breaks. check state machine..
-    token_types.push(tokens::multiline_comment::MultilineComment::start());
-    
-    return token_types;
+
+  // a lexer that returns tokens that have already been lexed from the program, and then ends
+  pub fn from_tokens(program: &'a str, tokens: Vec<TokenEntry>) -> Self {
+    let mut lexer = Lexer::resume(program, program.len(), 1, 1);
+    lexer.peeked = tokens.into();
+
+    return lexer;
+  }
+
+  // look at the next token without consuming it
+  pub fn peek(&mut self) -> Option<&TokenEntry> {
+    return self.peek_nth(0);
+  }
+
+  // look at the token after the next one
+  pub fn peek_second(&mut self) -> Option<&TokenEntry> {
+    return self.peek_nth(1);
+  }
+
+  fn peek_nth(&mut self, n: usize) -> Option<&TokenEntry> {
+    while self.peeked.len() <= n {
+      match self.lex() {
+        Some(entry) => self.peeked.push_back(entry),
+        None => return None
+      }
+    }
+
+    return self.peeked.get(n);
   }
-  
-  fn get_type(token: &Token, chars: &String) -> Type {
+
+  fn get_type(token: &Token, chars: &str) -> Type {
     return match token {
-      Token::FalseKW(_) | Token::TrueKW(_) => Type::Bool,
-      Token::String(_) => Type::String,
-      Token::Number(_) => {
-        if chars.contains(".") {
+      Token::FalseKW | Token::TrueKW => Type::Bool,
+      Token::String => Type::String,
+      Token::Number => {
+        if literals::is_float(chars) {
           Type::Float
         } else {
           Type::Integer
@@ -108,170 +86,167 @@ impl <'a> Lexer<'a> {
       _ => Type::None
     }
   }
-  
-}
 
-impl <'a> Iterator for Lexer<'a> {
-  type Item = TokenEntry;
-  
-  fn next(&mut self) -> Option<Self::Item> {
-    
-    // the value to eventually return
-    let mut next_token = None;
-  
-    loop {  
-    
-      let mut is_comment = false;
-      
-      // make a collection with state for all token types
-      let mut token_types = Lexer::all_tokens();
-
-      // information about an acceptable token
-      // (not accepted until last live token type)
-      let mut acceptable_idx = None;
-      let mut acceptable_chars = None;
-      
+  // move past the given number of bytes, keeping track of the line and column
+  fn advance(&mut self, len: usize) {
+    let end = self.pos + len;
+    let consumed = &self.program[self.pos..end];
+
+    match consumed.rfind('\n') {
+      Some(last) => {
+        self.line_num += consumed.matches('\n').count() as u32;
+        self.column = consumed[last + 1..].chars().count() as u32 + 1;
+      },
+      None => self.column += consumed.chars().count() as u32
+    }
+
+    self.pos = end;
+  }
+
+  // record that the program has been looked at up to (but not including) `end`
+  fn look(&mut self, end: usize) {
+    self.examined = self.examined.max(end);
+  }
+
+  // skip the rest of a block comment whose opening '/*' has been read
+  fn block_comment(&mut self, start_line: u32) {
+    let (len, terminated) = Lexer::block_comment_len(&self.program[self.pos..]);
+
+    // an unclosed comment looks for its end up to the end of the program
+    self.look(self.pos + len + if terminated { 0 } else { 1 });
+
+    // the comment runs to the end of the program, so report where it was opened
+    if !terminated {
+      self.diagnostics.push(Diagnostic::ErrUnterminatedComment{line_num: start_line});
+    }
+
+    self.advance(len);
+  }
+
+  // the length of the rest of a block comment (after its opening '/*'), and whether it is closed.
+  // Comments can be nested to any depth
+  pub fn block_comment_len(rest: &str) -> (usize, bool) {
+    let mut depth = 1;
+    let mut chars = rest.char_indices().peekable();
+
+    while let Some((i, ch)) = chars.next() {
+      match (ch, chars.peek()) {
+        ('/', Some((_, '*'))) => { chars.next(); depth += 1; },
+        ('*', Some((_, '/'))) => {
+          chars.next();
+          depth -= 1;
+
+          if depth == 0 {
+            return (i + 2, true);
+          }
+        },
+        _ => ()
+      }
+    }
+
+    return (rest.len(), false);
+  }
+
+  // lex the next token, skipping whitespace and comments
+  fn lex(&mut self) -> Option<TokenEntry> {
+
+    loop {
+
+      // ensure head of input is a non-ws
+      let rest = &self.program[self.pos..];
+      let ws = rest.len() - rest.trim_start_matches(CharGroup::is_ws).len();
+      self.advance(ws);
+
       // check for end of file
-      if let None = self.program.peek() {
-        println!("EOF Reached");
+      if self.pos == self.program.len() {
         return None;
       }
-      
-      // ensure head of iterator is a non-ws
-      while let Some(ch) = self.program.peek() {
-        if !CharGroup::is_ws(*ch) {
-          break;
-        }
-        
-        if '\n' == *ch {
-          self.line_num += 1;
-        }
-        
-        self.program.next();
-      }
-      
-      // while at more than one (disregarding 'unknown') token is alive (tok.state != None), keep consuming characters
-      // if the token is acceptable, record it
-      // when none are alive state, return accept token
-      // if acceptable == None, error
-      let mut alive = 2;
-      while alive > 1 {
-      
-        alive = 0;
-          
-        // get value at the head of the iterator
-        let curr_ch = self.program.peek();
-      
-        // ch is mutable since we reassign if a lowercase variant exists
-        if let Some(mut ch) = curr_ch {
-        
-          // make lowercase
-          let lower_ch = &ch.to_lowercase().next();
-          if let Some(lower) = lower_ch {
-            ch = &lower;
-          }
-        
-          for (i, token) in token_types.iter_mut().enumerate() {
-          
-            // advance curr type and ensure that it's still a valid state
-            if let Some(state) = token.next(*ch) {
-            
-              alive += 1;
-              
-              // reassign state if acceptable
-              if state.accept {
-              
-                // make a new Token that matches this element
-                acceptable_idx = Some(i);
-                
-                // get a copy of the string
-                acceptable_chars = Some(String::from(&state.chars[..]));
-              }
-            }
+
+      let line_num = self.line_num;
+      let start = self.pos;
+      let column = self.column;
+
+      // run the dfa for as long as possible (maximal munch), remembering the last token it accepted
+      let mut state = 0;
+      let mut accepted = None;
+
+      // a token that reaches the end of the program could be continued by anything added after it
+      let mut examined = self.program.len() + 1;
+
+      for (i, ch) in self.program[self.pos..].char_indices() {
+        match self.dfa.next(state, ch) {
+          Some(next) => state = next,
+          None => {
+            examined = self.pos + i + ch.len_utf8();
+            break;
           }
         }
-        
-        // if alive will be true in the next iteration, advance iterator
-        // or if head results in a live state, then consume this char
-        // token 'unknown' if dead and none accepted (alive == 1 && acceptable_idx.is_none())
-        // need to advance if alive or unknown (if unknown and we do not advance, will get stuck on the unknown token)
-        if alive > 1 || acceptable_idx.is_none() {
-          
-          let curr_ch = self.program.next();
-          
-          if let Some(ch) = curr_ch {
-            if '\n' == ch {
-              self.line_num += 1;
-            }
-          }
-          
+
+        if let Some(token) = self.dfa.accept(state) {
+          accepted = Some((token, i + ch.len_utf8()));
         }
-        
       }
-      
-      // After loop, setup return value
-      if let Some(idx) = acceptable_idx {
-      
-        if let Some(chars) = acceptable_chars {
-        
-          let tok_type = token_types.remove(idx);
-        
-          // check if tok_type is a comment
-          if let Token::LineComment(_tok) = &tok_type {
-            is_comment = true;
-          }
-          if let Token::MultilineComment(_tok) = &tok_type {
-            is_comment = true;
-          }
-          
-          let next_tok_type = Lexer::get_type(&tok_type, &chars);
-          let next_tok_val = match llvm_utils::get_llvm_value(&chars, &next_tok_type) {
-            Ok(val) => val,
-            Err(err) => {
-              println!("Error getting llvm value from token chars: {}", err);
-              
-              // default type is a 0 int
-              unsafe { core::LLVMConstInt(core::LLVMInt32Type(), 0, 0) }
-            }
-          };
-        
-          next_token = Some(TokenEntry {
-                              line_num: self.line_num,
-                              r#type: next_tok_type,
-                              chars: chars,
-                              tok_type: tok_type,
-                              value_ref: next_tok_val
-          });
+
+      self.look(examined);
+
+      let (token, len) = match accepted {
+        Some(accepted) => accepted,
+        None => {
+          // nothing starts with this character, so it is a token of its own
+          let ch = self.program[self.pos..].chars().next().unwrap();
+
+          (Token::Unknown, ch.len_utf8())
         }
-    
-      } else {
-      
-        let caught_tok = token_types.remove(0);
-        
-        if let Some(state) = caught_tok.get_state() {
-        
-          let chars = &state.chars;
-          
-          // only report errors on non-zero tokens
-          if chars.len() > 0 {
-            self.errors.push(format!("Error! Unrecognized token, '{}' at line: {}", chars, self.line_num));
-            next_token = Some(TokenEntry {
-                                line_num: self.line_num,
-                                chars: chars.to_string(),
-                                tok_type: caught_tok,
-                                r#type: Type::None,
-                                value_ref: unsafe { core::LLVMConstInt(core::LLVMInt32Type(), 0, 0) }
-            });
+      };
+
+      let mut chars = String::from(&self.program[self.pos..self.pos + len]);
+      self.advance(len);
+
+      let token = match token {
+        // comments are skipped
+        Token::LineComment => continue,
+        Token::MultilineComment => {
+          self.block_comment(line_num);
+          continue;
+        },
+
+        // the language is case insensitive, so identifiers are stored lowercase
+        Token::Identifier => {
+          chars = chars.to_lowercase();
+
+          match self.dfa.keyword(&chars) {
+            Some(keyword) => keyword,
+            None => Token::Identifier
           }
-        }
-          
-      }
-    
-      if !is_comment {
-        break;
-      }
+        },
+        Token::Unknown => {
+          self.diagnostics.push(Diagnostic::ErrUnrecognizedChar{line_num, chars: chars.clone()});
+          Token::Unknown
+        },
+        token => token
+      };
+
+      trace!("lexer", "{}:{} {} {:?}", line_num, column, token.get_example(), chars);
+
+      return Some(TokenEntry {
+        r#type: Lexer::get_type(&token, &chars),
+        line_num,
+        chars,
+        tok_type: token,
+        span: Span {start, end: start + len, column}
+      });
     }
-    
-    return next_token;
   }
-}
\ No newline at end of file
+
+}
+
+impl <'a> Iterator for Lexer<'a> {
+  type Item = TokenEntry;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    return match self.peeked.pop_front() {
+      Some(next) => Some(next),
+      None => self.lex()
+    };
+  }
+}
@@ -0,0 +1,240 @@
+extern crate llvm_sys;
+
+// leveled logging. Declared first, so its macros can be used in every module
+#[macro_use]
+pub mod log;
+
+// safe llvm wrappers and utility functions
+pub mod llvm;
+mod llvm_utils;
+
+// signatures of the runtime functions
+mod builtins;
+
+// token definitions and the automaton the lexer runs
+pub mod tokenize;
+
+pub mod lexer;
+pub mod ast;
+pub mod cst;
+pub mod parser;
+pub mod checker;
+pub mod lint;
+mod codegen;
+
+pub mod diagnostics;
+pub mod codes;
+pub mod suggest;
+
+// finding declarations and references by position (for editors)
+pub mod index;
+pub mod incremental;
+pub mod lsp;
+pub mod options;
+
+// printing intermediate forms of a program, and the compiler's state
+pub mod emit;
+pub mod dump;
+pub mod json;
+
+// printing programs in the canonical style
+pub mod fmt;
+
+// timing each phase of compiling a program
+pub mod timing;
+
+// llvm passes for each optimization level
+pub mod optimize;
+
+use std::mem;
+
+use crate::ast::Ast;
+use crate::cst::Cst;
+use crate::diagnostics::{Diagnostic, Diagnostics};
+use crate::lexer::Lexer;
+use crate::options::{Dump, Options};
+use crate::timing::Timings;
+use crate::tokenize::token::TokenEntry;
+
+// the output of compiling a program
+pub struct Artifact {
+  // the name the program was declared with (empty if the header could not be parsed)
+  pub name: String,
+
+  // llvm bitcode for the program. Only produced if there were no errors
+  pub bitcode: Option<Vec<u8>>,
+
+  // the generated llvm ir, if it failed verification and `dump_ir` was set
+  pub ir: Option<String>,
+
+  // the symbol tables, if `dump` asked for them
+  pub symtab: Option<String>,
+
+  // how long each phase took, and what it produced
+  pub timings: Timings,
+
+  pub diagnostics: Diagnostics
+}
+
+// split a program into tokens (comments are skipped)
+pub fn tokenize(program: &str) -> Vec<TokenEntry> {
+  return Lexer::new(program).collect();
+}
+
+// split a program into tokens, also returning problems found by the lexer (e.g. unterminated comments)
+pub fn lex(program: &str) -> (Vec<TokenEntry>, Diagnostics) {
+  let mut lexer = Lexer::new(program);
+  let tokens = lexer.by_ref().collect();
+
+  return (tokens, lexer.diagnostics);
+}
+
+// parse a program into a syntax tree. Syntax errors are recorded in the tree's diagnostics
+pub fn parse(program: &str) -> Ast {
+  return parser::Parser::new(program).parse();
+}
+
+// parse a program into a syntax tree and a lossless concrete syntax tree (which also keeps whitespace and comments)
+pub fn parse_lossless(program: &str) -> (Ast, Cst) {
+  return parser::Parser::new(program).parse_lossless();
+}
+
+// print a program in the canonical style. Programs with syntax errors aren't formatted
+pub fn format(program: &str) -> Result<String, Diagnostics> {
+  let (ast, cst) = parse_lossless(program);
+
+  if ast.diagnostics.has_errors() {
+    return Err(ast.diagnostics);
+  }
+
+  return Ok(fmt::format(&cst));
+}
+
+// resolve names and check types, returning every problem with the program (including syntax errors)
+// lossy conversions are reported at the default lint level, see `lint::Levels::apply`
+pub fn check(ast: &Ast) -> Diagnostics {
+  return checker::Checker::new().check(ast);
+}
+
+// report valid code that probably isn't what was meant (e.g. unused variables), at the given levels
+pub fn lint(ast: &Ast, levels: &lint::Levels) -> Diagnostics {
+  return lint::Linter::new(levels).lint(ast);
+}
+
+// everything `compile` would report for a program, without generating code. This doesn't use llvm,
+// so it is fast enough to run whenever the program changes (e.g. in an editor)
+pub fn diagnose(program: &str, levels: &lint::Levels) -> Diagnostics {
+  return analyze(program, levels, &mut Timings::new(false)).1;
+}
+
+// index every declaration and reference in a program, also returning the problems `diagnose` would report
+pub fn symbols(program: &str, levels: &lint::Levels) -> (index::Index, Diagnostics) {
+  let (ast, diagnostics) = analyze(program, levels, &mut Timings::new(false));
+
+  return (index::Index::new(&ast), diagnostics);
+}
+
+// lex, parse, check and lint a program, timing each pass
+fn analyze(program: &str, levels: &lint::Levels, timings: &mut Timings) -> (Ast, Diagnostics) {
+  let (tokens, mut diagnostics) = timings.time("lex", || lex(program));
+  timings.count("tokens", || tokens.len());
+
+  // the parser reads the tokens that were already lexed, so parsing is timed on its own
+  let mut ast = timings.time("parse", || parser::Parser::with_lexer(Lexer::from_tokens(program, tokens)).parse());
+  let declarations = ast.program.as_ref().map_or(0, |program| program.declarations.len());
+  timings.count("declarations", || declarations);
+
+  diagnostics.append(mem::take(&mut ast.diagnostics));
+  ast.diagnostics = diagnostics;
+  debug!("parser", "parsed {} declarations with {} problems", declarations, ast.diagnostics.list.len());
+
+  let mut diagnostics = timings.time("check", || levels.apply(check(&ast)));
+  timings.count("symbols", || index::Index::new(&ast).symbols.iter().filter(|symbol| symbol.kind != index::SymbolKind::Builtin).count());
+  debug!("checker", "{} problems, including syntax errors", diagnostics.list.len());
+
+  let lints = timings.time("lint", || lint(&ast, levels));
+  timings.count("findings", || lints.list.len());
+  debug!("lint", "{} findings", lints.list.len());
+  diagnostics.append(lints);
+
+  return (ast, diagnostics);
+}
+
+// parse, check and generate code for a program
+pub fn compile(program: &str, options: Options) -> Artifact {
+  let mut timings = Timings::new(options.time_passes);
+  let (ast, diagnostics) = analyze(program, &options.lints, &mut timings);
+
+  let mut artifact = Artifact {
+    name: match &ast.program {
+      Some(program) => String::from(&program.name.name[..]),
+      None => String::new()
+    },
+    bitcode: None,
+    ir: None,
+    symtab: None,
+    timings,
+    diagnostics
+  };
+
+  if let Some(Dump::Symtab) = options.dump {
+    artifact.symtab = Some(dump::symtab(&index::Index::new(&ast), options.format));
+  }
+
+  if artifact.diagnostics.has_errors() {
+    return artifact;
+  }
+
+  if let Some(program) = &ast.program {
+
+    // everything llvm creates for this program lives in this context
+    let context = llvm::Context::new();
+    let module = context.create_module("compiler_module");
+
+    debug!("codegen", "generating code for '{}'", artifact.name);
+
+    let mut codegen = codegen::Codegen::new(&context, &module);
+    artifact.timings.time("codegen", || codegen.program(program));
+
+    artifact.timings.count("functions", || llvm_utils::defined_functions(&module).len());
+    artifact.timings.count("instructions", || llvm_utils::instruction_count(&module));
+
+    // the module may be valid, but it doesn't do what the program says
+    let bugs = log::internal_errors();
+    if !bugs.is_empty() {
+      for bug in bugs {
+        artifact.diagnostics.push(bug);
+      }
+
+      return artifact;
+    }
+
+    // verify the module
+    // a broken module is a bug in the compiler rather than the program, so report it as such
+    debug!("llvm", "verifying the module");
+
+    match artifact.timings.time("verify", || module.verify()) {
+      Ok(_) => {
+        // the module is only optimized once it is known to be valid
+        if !optimize::passes(options.opt_level).is_empty() {
+          artifact.timings.time("optimize", || optimize::optimize(&module, options.opt_level));
+          artifact.timings.count("instructions", || llvm_utils::instruction_count(&module));
+        }
+
+        let bitcode = artifact.timings.time("bitcode", || module.write_bitcode_to_memory());
+        artifact.timings.count("bytes", || bitcode.len());
+        artifact.bitcode = Some(bitcode);
+      },
+      Err(msg) => {
+        debug!("llvm", "verification failed: {}", msg.trim_end());
+        artifact.diagnostics.push(Diagnostic::ErrInternal{procedures: llvm_utils::broken_functions(&module), msg});
+
+        if options.dump_ir {
+          artifact.ir = Some(module.print_to_string());
+        }
+      }
+    }
+  }
+
+  return artifact;
+}
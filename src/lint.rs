@@ -0,0 +1,402 @@
+// lints report code that is valid, but probably isn't what was meant. They are warnings by default,
+// and each one can be allowed (not reported) or denied (reported as an error).
+// Most are found by the lint pass here, but lossy conversions are found by the checker since they need types
+
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+use crate::ast::*;
+use crate::builtins;
+use crate::diagnostics::{Diagnostic, Diagnostics};
+use crate::tokenize::token::Type;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Lint {
+  UnusedVariable,
+  UnusedParameter,
+  UnusedProcedure,
+
+  // a local variable is read on a path where it hasn't been assigned yet
+  Uninitialized,
+
+  // a float is implicitly truncated to an integer, or an integer to a bool
+  LossyConversion
+}
+
+pub static LINTS: &[Lint] = &[Lint::UnusedVariable, Lint::UnusedParameter, Lint::UnusedProcedure, Lint::Uninitialized, Lint::LossyConversion];
+
+impl Lint {
+
+  // the name used to allow or deny the lint
+  pub fn name(&self) -> &'static str {
+    return match self {
+      Lint::UnusedVariable => "unused-variable",
+      Lint::UnusedParameter => "unused-parameter",
+      Lint::UnusedProcedure => "unused-procedure",
+      Lint::Uninitialized => "uninitialized",
+      Lint::LossyConversion => "lossy-conversion"
+    };
+  }
+
+  // the stable code of the lint's diagnostic (see `codes`)
+  pub fn code(&self) -> &'static str {
+    return match self {
+      Lint::UnusedVariable => "W0101",
+      Lint::UnusedParameter => "W0102",
+      Lint::UnusedProcedure => "W0103",
+      Lint::Uninitialized => "W0104",
+      Lint::LossyConversion => "W0105"
+    };
+  }
+
+  pub fn from_name(name: &str) -> Option<Lint> {
+    return LINTS.iter().cloned().find(|lint| lint.name() == name);
+  }
+
+  // what is wrong with `name` (the symbol the lint is about, or for conversions the types)
+  pub fn message(&self, name: &str) -> String {
+    return match self {
+      Lint::UnusedVariable => format!("Variable '{}' is never read", name),
+      Lint::UnusedParameter => format!("Parameter '{}' is never read", name),
+      Lint::UnusedProcedure => format!("Procedure '{}' is never called", name),
+      Lint::Uninitialized => format!("Variable '{}' may be read before it is assigned", name),
+      Lint::LossyConversion => format!("Implicit conversion {} may lose information", name)
+    };
+  }
+
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Level {
+  Allow,
+  Warn,
+  Deny
+}
+
+// the level of each lint. Lints that haven't been set warn
+#[derive(Clone)]
+pub struct Levels {
+  levels: Vec<(Lint, Level)>
+}
+
+impl Levels {
+
+  pub fn new() -> Levels {
+    return Levels { levels: vec![] };
+  }
+
+  pub fn set(&mut self, lint: Lint, level: Level) {
+    self.levels.retain(|(other, _)| *other != lint);
+    self.levels.push((lint, level));
+  }
+
+  pub fn get(&self, lint: Lint) -> Level {
+    return match self.levels.iter().find(|(other, _)| *other == lint) {
+      Some((_, level)) => *level,
+      None => Level::Warn
+    };
+  }
+
+  // set the level of lints that were reported at the default level, removing any that are allowed
+  pub fn apply(&self, diagnostics: Diagnostics) -> Diagnostics {
+    let mut applied = Diagnostics::new();
+
+    for mut diagnostic in diagnostics.list {
+      if let Diagnostic::Lint{lint, level, ..} = &mut diagnostic {
+        *level = self.get(*lint);
+
+        if *level == Level::Allow {
+          continue;
+        }
+      }
+
+      applied.push(diagnostic);
+    }
+
+    return applied;
+  }
+
+}
+
+impl Default for Levels {
+  fn default() -> Levels {
+    return Levels::new();
+  }
+}
+
+// a declared name
+struct Symbol {
+  name: String,
+  line_num: u32,
+
+  // what to report if the symbol is never used (none for builtins and types)
+  unused: Option<Lint>,
+  used: bool,
+
+  // whether reads are checked for an assignment first. Only scalar local variables are tracked:
+  // globals can be assigned by any procedure, and arrays are usually assigned an element at a time
+  tracked: bool
+}
+
+// walks a checked program, resolving names the same way the checker does
+pub struct Linter {
+  levels: Levels,
+
+  // every symbol, in the order they were declared
+  symbols: Vec<Symbol>,
+
+  // the global table, then the tables of the scopes being linted (by name, into `symbols`)
+  scopes: Vec<HashMap<String, usize>>,
+
+  // tracked variables that are assigned on every path to the current statement
+  assigned: HashSet<usize>,
+
+  // variables that have already been reported as read before being assigned
+  reported: HashSet<usize>,
+
+  // the procedure whose body is being linted (it calling itself doesn't count as a use)
+  procedure: Option<usize>,
+
+  diagnostics: Diagnostics
+}
+
+impl Linter {
+
+  pub fn new(levels: &Levels) -> Self {
+    return Linter {
+      levels: levels.clone(),
+      symbols: vec![],
+      scopes: vec![],
+      assigned: HashSet::new(),
+      reported: HashSet::new(),
+      procedure: None,
+      diagnostics: Diagnostics::new()
+    };
+  }
+
+  // lint the program, returning the lints that were found in line order
+  pub fn lint(mut self, ast: &Ast) -> Diagnostics {
+    if let Some(program) = &ast.program {
+      self.program(program);
+    }
+
+    for symbol in &self.symbols {
+      if let (Some(lint), false) = (symbol.unused, symbol.used) {
+        Linter::report(&self.levels, &mut self.diagnostics, lint, symbol.line_num, &symbol.name);
+      }
+    }
+
+    self.diagnostics.list.sort_by_key(|diagnostic| match diagnostic {
+      Diagnostic::Lint{line_num, ..} => *line_num,
+      _ => 0
+    });
+
+    return self.diagnostics;
+  }
+
+  fn program(&mut self, program: &Program) {
+    self.scopes.push(HashMap::new());
+
+    for builtin in builtins::initialize_builtins() {
+      let symbol = Symbol { name: builtin.chars, line_num: builtin.line_num, unused: None, used: false, tracked: false };
+      self.declare(Scope::Global, symbol);
+    }
+
+    self.scopes.push(HashMap::new());
+
+    self.declarations(&program.declarations);
+    self.statements(&program.statements);
+
+    self.scopes.pop();
+    self.scopes.pop();
+  }
+
+  fn declarations(&mut self, declarations: &[Declaration]) {
+    for declaration in declarations {
+      match declaration {
+        Declaration::Procedure(procedure) => self.procedure_declaration(procedure),
+        Declaration::Variable(variable) => {
+          let tracked = !matches!((variable.scope, &variable.variable.r#type), (Scope::Global, _) | (_, Type::Array(..)));
+
+          self.declare(variable.scope, Linter::symbol(&variable.variable.name, Some(Lint::UnusedVariable), tracked));
+        },
+        Declaration::Type(type_declaration) => {
+          self.declare(type_declaration.scope, Linter::symbol(&type_declaration.name, None, false));
+        }
+      }
+    }
+  }
+
+  fn procedure_declaration(&mut self, procedure: &ProcedureDeclaration) {
+    let id = self.declare(procedure.scope, Linter::symbol(&procedure.name, Some(Lint::UnusedProcedure), false));
+
+    // the procedure can call itself
+    let mut table = HashMap::new();
+    table.insert(String::from(&procedure.name.name[..]), id);
+    self.scopes.push(table);
+
+    for param in &procedure.params {
+      self.declare(Scope::Local, Linter::symbol(&param.name, Some(Lint::UnusedParameter), false));
+    }
+
+    let outer_procedure = self.procedure.replace(id);
+    let outer_assigned = mem::take(&mut self.assigned);
+
+    self.declarations(&procedure.declarations);
+    self.statements(&procedure.statements);
+
+    self.procedure = outer_procedure;
+    self.assigned = outer_assigned;
+    self.scopes.pop();
+  }
+
+  // returns true if every path through the statements returns
+  fn statements(&mut self, statements: &[Statement]) -> bool {
+    let mut does_return = false;
+
+    for statement in statements {
+      if self.statement(statement) {
+        does_return = true;
+      }
+    }
+
+    return does_return;
+  }
+
+  fn statement(&mut self, statement: &Statement) -> bool {
+    match statement {
+      Statement::Assignment(assignment) => {
+        self.assignment(assignment);
+
+        return false;
+      },
+      Statement::If(if_statement) => {
+        self.expression(&if_statement.condition);
+
+        let before = self.assigned.clone();
+        let then_returns = self.statements(&if_statement.then_statements);
+        let after_then = mem::replace(&mut self.assigned, before);
+        let else_returns = self.statements(&if_statement.else_statements);
+
+        // a branch that returns doesn't reach the statements after the if statement
+        if else_returns {
+          self.assigned = after_then;
+        } else if !then_returns {
+          self.assigned = self.assigned.intersection(&after_then).cloned().collect();
+        }
+
+        return then_returns && else_returns;
+      },
+      Statement::Loop(loop_statement) => {
+        self.assignment(&loop_statement.assignment);
+        self.expression(&loop_statement.condition);
+
+        // the body may never run, so its assignments don't count after the loop
+        let before = self.assigned.clone();
+        self.statements(&loop_statement.statements);
+        self.assigned = before;
+
+        return false;
+      },
+      Statement::Return(return_statement) => {
+        self.expression(&return_statement.expression);
+
+        return true;
+      }
+    }
+  }
+
+  fn assignment(&mut self, assignment: &Assignment) {
+    if let Some(index) = &assignment.destination.index {
+      self.expression(index);
+    }
+
+    self.expression(&assignment.expression);
+
+    // assigning to a variable isn't a use of it
+    if let Some(id) = self.lookup(&assignment.destination.identifier.name) {
+      self.assigned.insert(id);
+    }
+  }
+
+  fn expression(&mut self, expression: &Expr) {
+    match expression {
+      Expr::Literal{..} => (),
+      Expr::Name(name) => self.read(name),
+      Expr::Call{procedure, args} => {
+        if let Some(id) = self.lookup(&procedure.name) {
+          if self.procedure != Some(id) {
+            self.symbols[id].used = true;
+          }
+        }
+
+        for arg in args {
+          self.expression(arg);
+        }
+      },
+      Expr::Negate(operand) | Expr::Not(operand) => self.expression(operand),
+      Expr::Binary{left, right, ..} => {
+        self.expression(left);
+        self.expression(right);
+      }
+    }
+  }
+
+  fn read(&mut self, name: &Name) {
+    if let Some(id) = self.lookup(&name.identifier.name) {
+      self.symbols[id].used = true;
+
+      if self.symbols[id].tracked && !self.assigned.contains(&id) && self.reported.insert(id) {
+        Linter::report(&self.levels, &mut self.diagnostics, Lint::Uninitialized, name.identifier.line_num, &name.identifier.name);
+      }
+    }
+
+    if let Some(index) = &name.index {
+      self.expression(index);
+    }
+  }
+
+  fn declare(&mut self, scope: Scope, symbol: Symbol) -> usize {
+    let id = self.symbols.len();
+
+    let table = match scope {
+      Scope::Local => self.scopes.last_mut(),
+      Scope::Global => self.scopes.first_mut()
+    };
+
+    if let Some(table) = table {
+      table.insert(symbol.name.clone(), id);
+    }
+
+    self.symbols.push(symbol);
+
+    return id;
+  }
+
+  // look in the current scope, then the global one (like `Checker::get_symbol`)
+  fn lookup(&self, name: &str) -> Option<usize> {
+    let local = self.scopes.last().and_then(|table| table.get(name));
+    let global = self.scopes.first().and_then(|table| table.get(name));
+
+    return local.or(global).cloned();
+  }
+
+  fn symbol(identifier: &Identifier, unused: Option<Lint>, tracked: bool) -> Symbol {
+    return Symbol {
+      name: String::from(&identifier.name[..]),
+      line_num: identifier.line_num,
+      unused,
+      used: false,
+      tracked
+    };
+  }
+
+  fn report(levels: &Levels, diagnostics: &mut Diagnostics, lint: Lint, line_num: u32, name: &str) {
+    let level = levels.get(lint);
+
+    if level != Level::Allow {
+      diagnostics.push(Diagnostic::Lint{lint, level, line_num, name: String::from(name)});
+    }
+  }
+
+}
@@ -0,0 +1,55 @@
+use std::marker::PhantomData;
+
+use llvm_sys::core;
+use llvm_sys::prelude::*;
+
+use crate::llvm::{Module, Value};
+
+// a basic block within a function. Owned by the function's module, so this is only a handle that borrows it
+#[derive(Clone, Copy)]
+pub struct BasicBlock<'m> {
+  raw: LLVMBasicBlockRef,
+  _marker: PhantomData<&'m Module<'m>>
+}
+
+impl <'m> BasicBlock<'m> {
+
+  pub(crate) fn from_raw(raw: LLVMBasicBlockRef) -> BasicBlock<'m> {
+    return BasicBlock { raw, _marker: PhantomData };
+  }
+  
+  pub fn as_raw(&self) -> LLVMBasicBlockRef {
+    return self.raw;
+  }
+  
+  // the function containing this block
+  pub fn parent(&self) -> Value<'m> {
+    return Value::from_raw(unsafe { core::LLVMGetBasicBlockParent(self.raw) });
+  }
+  
+  // how many instructions are in the block
+  pub fn instruction_count(&self) -> usize {
+    let mut count = 0;
+    
+    unsafe {
+      let mut instruction = core::LLVMGetFirstInstruction(self.raw);
+      while !instruction.is_null() {
+        count += 1;
+        instruction = core::LLVMGetNextInstruction(instruction);
+      }
+    }
+    
+    return count;
+  }
+  
+  pub fn terminator(&self) -> Option<Value<'m>> {
+    let terminator = unsafe { core::LLVMGetBasicBlockTerminator(self.raw) };
+    
+    if terminator.is_null() {
+      return None;
+    } else {
+      return Some(Value::from_raw(terminator));
+    }
+  }
+  
+}
@@ -0,0 +1,174 @@
+use llvm_sys::{core, LLVMIntPredicate, LLVMRealPredicate};
+use llvm_sys::prelude::*;
+
+use crate::llvm::{to_c_str, Context, BasicBlock, Type, Value};
+
+// builds instructions at the end of a basic block in a module. Disposed of when dropped
+pub struct Builder<'m> {
+  raw: LLVMBuilderRef,
+  context: &'m Context
+}
+
+impl <'m> Builder<'m> {
+
+  pub(crate) fn from_raw(context: &'m Context, raw: LLVMBuilderRef) -> Builder<'m> {
+    return Builder { raw, context };
+  }
+  
+  pub fn context(&self) -> &'m Context {
+    return self.context;
+  }
+  
+  pub fn position_at_end(&self, block: BasicBlock<'m>) {
+    unsafe { core::LLVMPositionBuilderAtEnd(self.raw, block.as_raw()); }
+  }
+  
+  pub fn insert_block(&self) -> BasicBlock<'m> {
+    return BasicBlock::from_raw(unsafe { core::LLVMGetInsertBlock(self.raw) });
+  }
+  
+  // memory
+  
+  pub fn build_alloca(&self, alloc_type: Type<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildAlloca(self.raw, alloc_type.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_load(&self, pointer: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildLoad(self.raw, pointer.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_store(&self, value: Value<'m>, pointer: Value<'m>) -> Value<'m> {
+    return Value::from_raw(unsafe { core::LLVMBuildStore(self.raw, value.as_raw(), pointer.as_raw()) });
+  }
+  
+  // calls and control flow
+  
+  pub fn build_call(&self, function: Value<'m>, args: &[Value<'m>], name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    let mut raw_args: Vec<LLVMValueRef> = args.iter().map(|arg| arg.as_raw()).collect();
+    
+    return Value::from_raw(unsafe { core::LLVMBuildCall(self.raw, function.as_raw(), raw_args.as_mut_ptr(), raw_args.len() as u32, name.as_ptr()) });
+  }
+  
+  pub fn build_ret(&self, value: Value<'m>) -> Value<'m> {
+    return Value::from_raw(unsafe { core::LLVMBuildRet(self.raw, value.as_raw()) });
+  }
+  
+  pub fn build_ret_void(&self) -> Value<'m> {
+    return Value::from_raw(unsafe { core::LLVMBuildRetVoid(self.raw) });
+  }
+  
+  pub fn build_br(&self, dest: BasicBlock<'m>) -> Value<'m> {
+    return Value::from_raw(unsafe { core::LLVMBuildBr(self.raw, dest.as_raw()) });
+  }
+  
+  pub fn build_cond_br(&self, condition: Value<'m>, then_block: BasicBlock<'m>, else_block: BasicBlock<'m>) -> Value<'m> {
+    return Value::from_raw(unsafe { core::LLVMBuildCondBr(self.raw, condition.as_raw(), then_block.as_raw(), else_block.as_raw()) });
+  }
+  
+  pub fn build_unreachable(&self) -> Value<'m> {
+    return Value::from_raw(unsafe { core::LLVMBuildUnreachable(self.raw) });
+  }
+  
+  // arithmetic
+  
+  pub fn build_add(&self, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildAdd(self.raw, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_fadd(&self, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildFAdd(self.raw, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_sub(&self, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildSub(self.raw, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_fsub(&self, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildFSub(self.raw, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_fmul(&self, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildFMul(self.raw, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_fdiv(&self, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildFDiv(self.raw, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_neg(&self, value: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildNeg(self.raw, value.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_fneg(&self, value: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildFNeg(self.raw, value.as_raw(), name.as_ptr()) });
+  }
+  
+  // logic
+  
+  pub fn build_and(&self, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildAnd(self.raw, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_or(&self, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildOr(self.raw, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_not(&self, value: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildNot(self.raw, value.as_raw(), name.as_ptr()) });
+  }
+  
+  // comparisons
+  
+  pub fn build_icmp(&self, predicate: LLVMIntPredicate, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildICmp(self.raw, predicate, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_fcmp(&self, predicate: LLVMRealPredicate, lhs: Value<'m>, rhs: Value<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildFCmp(self.raw, predicate, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) });
+  }
+  
+  // casts
+  
+  pub fn build_zext(&self, value: Value<'m>, dest_type: Type<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildZExt(self.raw, value.as_raw(), dest_type.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_int_cast(&self, value: Value<'m>, dest_type: Type<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildIntCast(self.raw, value.as_raw(), dest_type.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_si_to_fp(&self, value: Value<'m>, dest_type: Type<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildSIToFP(self.raw, value.as_raw(), dest_type.as_raw(), name.as_ptr()) });
+  }
+  
+  pub fn build_fp_to_si(&self, value: Value<'m>, dest_type: Type<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    return Value::from_raw(unsafe { core::LLVMBuildFPToSI(self.raw, value.as_raw(), dest_type.as_raw(), name.as_ptr()) });
+  }
+  
+}
+
+impl <'m> Drop for Builder<'m> {
+  fn drop(&mut self) {
+    unsafe { core::LLVMDisposeBuilder(self.raw); }
+  }
+}
@@ -0,0 +1,72 @@
+use llvm_sys::core;
+use llvm_sys::prelude::*;
+
+use crate::llvm::{to_c_str, Module, BasicBlock, Type, Value};
+
+// owns the llvm context that types, constants and modules are created in.
+// Each compilation creates its own, so separate compilations share no llvm state
+pub struct Context {
+  raw: LLVMContextRef
+}
+
+// a context may be handed to another thread, but it is never shared between them
+// (modules, builders, types and values all borrow it and are not Send)
+unsafe impl Send for Context {}
+
+impl Context {
+
+  pub fn new() -> Context {
+    return Context { raw: unsafe { core::LLVMContextCreate() } };
+  }
+  
+  pub fn create_module<'ctx>(&'ctx self, name: &str) -> Module<'ctx> {
+    let name = to_c_str(name);
+    let raw = unsafe { core::LLVMModuleCreateWithNameInContext(name.as_ptr(), self.raw) };
+    
+    return Module::from_raw(self, raw);
+  }
+  
+  // the block borrows the module of the function it is added to
+  pub fn append_basic_block<'m>(&'m self, function: Value<'m>, name: &str) -> BasicBlock<'m> {
+    let name = to_c_str(name);
+    
+    return BasicBlock::from_raw(unsafe { core::LLVMAppendBasicBlockInContext(self.raw, function.as_raw(), name.as_ptr()) });
+  }
+  
+  // types
+  
+  pub fn int32_type<'ctx>(&'ctx self) -> Type<'ctx> {
+    return Type::from_raw(unsafe { core::LLVMInt32TypeInContext(self.raw) });
+  }
+  
+  pub fn float_type<'ctx>(&'ctx self) -> Type<'ctx> {
+    return Type::from_raw(unsafe { core::LLVMFloatTypeInContext(self.raw) });
+  }
+  
+  pub fn void_type<'ctx>(&'ctx self) -> Type<'ctx> {
+    return Type::from_raw(unsafe { core::LLVMVoidTypeInContext(self.raw) });
+  }
+  
+  // constants
+  
+  pub fn const_int<'ctx>(&'ctx self, int_type: Type<'ctx>, val: u64, sign_extend: bool) -> Value<'ctx> {
+    return Value::from_raw(unsafe { core::LLVMConstInt(int_type.as_raw(), val, sign_extend as i32) });
+  }
+  
+  pub fn const_real<'ctx>(&'ctx self, real_type: Type<'ctx>, val: f64) -> Value<'ctx> {
+    return Value::from_raw(unsafe { core::LLVMConstReal(real_type.as_raw(), val) });
+  }
+  
+}
+
+impl Default for Context {
+  fn default() -> Context {
+    return Context::new();
+  }
+}
+
+impl Drop for Context {
+  fn drop(&mut self) {
+    unsafe { core::LLVMContextDispose(self.raw); }
+  }
+}
@@ -0,0 +1,39 @@
+// safe wrappers around the llvm c api
+// all codegen should go through these rather than calling llvm_sys directly,
+// so that ownership (and disposal) of llvm objects is tracked by the borrow checker
+pub mod context;
+pub mod module;
+pub mod builder;
+pub mod basic_block;
+pub mod types;
+pub mod value;
+pub mod pass_manager;
+
+pub use self::context::Context;
+pub use self::module::Module;
+pub use self::builder::Builder;
+pub use self::basic_block::BasicBlock;
+pub use self::types::Type;
+pub use self::value::Value;
+pub use self::pass_manager::{Pass, PassManager};
+
+use std::ffi::{CStr, CString};
+
+// build a c string to pass into llvm. This must outlive the pointer handed to llvm
+pub(crate) fn to_c_str(slice: &str) -> CString {
+  return CString::new(slice).expect("could not get c-string from slice");
+}
+
+// copy a message allocated by llvm into a rust string, then free the original
+pub(crate) fn take_message(message: *mut i8) -> String {
+  if message.is_null() {
+    return String::new();
+  }
+  
+  unsafe {
+    let msg = CStr::from_ptr(message).to_string_lossy().into_owned();
+    llvm_sys::core::LLVMDisposeMessage(message);
+    
+    return msg;
+  }
+}
@@ -0,0 +1,112 @@
+use std::ptr;
+
+use std::slice;
+
+use llvm_sys::{core, analysis, bit_writer};
+use llvm_sys::prelude::*;
+
+use crate::llvm::{to_c_str, take_message, Context, Builder, Type, Value};
+
+/// an llvm module, disposed of (along with everything in it) when dropped.
+/// Values borrow the module they are in, so they can't be used after it is dropped:
+///
+/// ```compile_fail,E0505
+/// use sangzf_compiler::llvm::Context;
+///
+/// let context = Context::new();
+/// let module = context.create_module("m");
+/// let function = module.add_function("f", context.void_type().function(&[]));
+///
+/// drop(module);
+/// function.name();
+/// ```
+pub struct Module<'ctx> {
+  raw: LLVMModuleRef,
+  
+  // the module can't outlive the context it was created in
+  context: &'ctx Context
+}
+
+impl <'ctx> Module<'ctx> {
+
+  pub(crate) fn from_raw(context: &'ctx Context, raw: LLVMModuleRef) -> Module<'ctx> {
+    return Module { raw, context };
+  }
+  
+  pub fn as_raw(&self) -> LLVMModuleRef {
+    return self.raw;
+  }
+  
+  // builders, values and blocks all borrow the module, so none of them can be used once it is dropped
+  pub fn create_builder<'m>(&'m self) -> Builder<'m> {
+    return Builder::from_raw(self.context, unsafe { core::LLVMCreateBuilderInContext(core::LLVMGetModuleContext(self.raw)) });
+  }
+  
+  pub fn add_function<'m>(&'m self, name: &str, function_type: Type<'m>) -> Value<'m> {
+    let name = to_c_str(name);
+    
+    return Value::from_raw(unsafe { core::LLVMAddFunction(self.raw, name.as_ptr(), function_type.as_raw()) });
+  }
+  
+  pub fn add_global<'m>(&'m self, global_type: Type<'m>, name: &str) -> Value<'m> {
+    let name = to_c_str(name);
+    
+    return Value::from_raw(unsafe { core::LLVMAddGlobal(self.raw, global_type.as_raw(), name.as_ptr()) });
+  }
+  
+  // all functions in the module, including declarations
+  pub fn functions<'m>(&'m self) -> Vec<Value<'m>> {
+    let mut functions = vec![];
+    
+    unsafe {
+      let mut function = core::LLVMGetFirstFunction(self.raw);
+      while !function.is_null() {
+        functions.push(Value::from_raw(function));
+        function = core::LLVMGetNextFunction(function);
+      }
+    }
+    
+    return functions;
+  }
+  
+  // verify the module without aborting, returning llvm's description of the problems on failure
+  pub fn verify(&self) -> Result<(), String> {
+    let mut message = ptr::null_mut();
+    let failed = unsafe {
+      analysis::LLVMVerifyModule(self.raw, analysis::LLVMVerifierFailureAction::LLVMReturnStatusAction, &mut message)
+    };
+    
+    let msg = take_message(message);
+    
+    if failed != 0 {
+      return Err(msg);
+    } else {
+      return Ok(());
+    }
+  }
+  
+  // the textual ir of the module
+  pub fn print_to_string(&self) -> String {
+    return take_message(unsafe { core::LLVMPrintModuleToString(self.raw) });
+  }
+  
+  pub fn write_bitcode_to_memory(&self) -> Vec<u8> {
+    unsafe {
+      let buffer = bit_writer::LLVMWriteBitcodeToMemoryBuffer(self.raw);
+      
+      let start = core::LLVMGetBufferStart(buffer) as *const u8;
+      let bitcode = slice::from_raw_parts(start, core::LLVMGetBufferSize(buffer)).to_vec();
+      
+      core::LLVMDisposeMemoryBuffer(buffer);
+      
+      return bitcode;
+    }
+  }
+  
+}
+
+impl <'ctx> Drop for Module<'ctx> {
+  fn drop(&mut self) {
+    unsafe { core::LLVMDisposeModule(self.raw); }
+  }
+}
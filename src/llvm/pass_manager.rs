@@ -0,0 +1,97 @@
+use llvm_sys::core;
+use llvm_sys::prelude::*;
+use llvm_sys::transforms::{ipo, scalar};
+
+use crate::llvm::Module;
+
+// an llvm optimization pass
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Pass {
+  // promote variables (allocas) to registers
+  PromoteMemoryToRegister,
+  InstructionCombining,
+  Reassociate,
+  EarlyCse,
+
+  // global value numbering, removing redundant loads and computations
+  Gvn,
+  CfgSimplification,
+  DeadStoreElimination,
+  FunctionInlining,
+
+  // loop passes: rotating loops into a do-while form, hoisting invariant code out of them, simplifying induction
+  // variables, and unrolling them
+  LoopRotate,
+  Licm,
+  IndVarSimplify,
+  LoopUnroll
+}
+
+impl Pass {
+  // the name llvm's `opt` uses for the pass
+  pub fn name(&self) -> &'static str {
+    return match self {
+      Pass::PromoteMemoryToRegister => "mem2reg",
+      Pass::InstructionCombining => "instcombine",
+      Pass::Reassociate => "reassociate",
+      Pass::EarlyCse => "early-cse",
+      Pass::Gvn => "gvn",
+      Pass::CfgSimplification => "simplifycfg",
+      Pass::DeadStoreElimination => "dse",
+      Pass::FunctionInlining => "inline",
+      Pass::LoopRotate => "loop-rotate",
+      Pass::Licm => "licm",
+      Pass::IndVarSimplify => "indvars",
+      Pass::LoopUnroll => "loop-unroll"
+    };
+  }
+}
+
+// runs passes over a whole module, in the order they were added. Disposed of when dropped
+pub struct PassManager {
+  raw: LLVMPassManagerRef
+}
+
+impl PassManager {
+
+  pub fn new() -> PassManager {
+    return PassManager { raw: unsafe { core::LLVMCreatePassManager() } };
+  }
+  
+  pub fn add(&self, pass: Pass) {
+    unsafe {
+      match pass {
+        Pass::PromoteMemoryToRegister => scalar::LLVMAddPromoteMemoryToRegisterPass(self.raw),
+        Pass::InstructionCombining => scalar::LLVMAddInstructionCombiningPass(self.raw),
+        Pass::Reassociate => scalar::LLVMAddReassociatePass(self.raw),
+        Pass::EarlyCse => scalar::LLVMAddEarlyCSEPass(self.raw),
+        Pass::Gvn => scalar::LLVMAddGVNPass(self.raw),
+        Pass::CfgSimplification => scalar::LLVMAddCFGSimplificationPass(self.raw),
+        Pass::DeadStoreElimination => scalar::LLVMAddDeadStoreEliminationPass(self.raw),
+        Pass::FunctionInlining => ipo::LLVMAddFunctionInliningPass(self.raw),
+        Pass::LoopRotate => scalar::LLVMAddLoopRotatePass(self.raw),
+        Pass::Licm => scalar::LLVMAddLICMPass(self.raw),
+        Pass::IndVarSimplify => scalar::LLVMAddIndVarSimplifyPass(self.raw),
+        Pass::LoopUnroll => scalar::LLVMAddLoopUnrollPass(self.raw)
+      }
+    }
+  }
+  
+  // run the passes over the module. Returns whether any of them changed it
+  pub fn run(&self, module: &Module) -> bool {
+    return unsafe { core::LLVMRunPassManager(self.raw, module.as_raw()) != 0 };
+  }
+  
+}
+
+impl Default for PassManager {
+  fn default() -> PassManager {
+    return PassManager::new();
+  }
+}
+
+impl Drop for PassManager {
+  fn drop(&mut self) {
+    unsafe { core::LLVMDisposePassManager(self.raw); }
+  }
+}
@@ -0,0 +1,41 @@
+use std::marker::PhantomData;
+
+use llvm_sys::core;
+use llvm_sys::prelude::*;
+
+use crate::llvm::{Context, Value};
+
+// an llvm type. Types are owned by their context, so this is only a handle
+#[derive(Clone, Copy)]
+pub struct Type<'ctx> {
+  raw: LLVMTypeRef,
+  _marker: PhantomData<&'ctx Context>
+}
+
+impl <'ctx> Type<'ctx> {
+
+  pub(crate) fn from_raw(raw: LLVMTypeRef) -> Type<'ctx> {
+    return Type { raw, _marker: PhantomData };
+  }
+  
+  pub fn as_raw(&self) -> LLVMTypeRef {
+    return self.raw;
+  }
+  
+  pub fn pointer(&self) -> Type<'ctx> {
+    return Type::from_raw(unsafe { core::LLVMPointerType(self.raw, 0) });
+  }
+  
+  // the zero value of this type
+  pub fn const_null(&self) -> Value<'ctx> {
+    return Value::from_raw(unsafe { core::LLVMConstNull(self.raw) });
+  }
+  
+  // a function type returning this type
+  pub fn function(&self, params: &[Type<'ctx>]) -> Type<'ctx> {
+    let mut raw_params: Vec<LLVMTypeRef> = params.iter().map(|param| param.as_raw()).collect();
+    
+    return Type::from_raw(unsafe { core::LLVMFunctionType(self.raw, raw_params.as_mut_ptr(), raw_params.len() as u32, 0) });
+  }
+  
+}
@@ -0,0 +1,66 @@
+use std::ffi::CStr;
+use std::marker::PhantomData;
+
+use llvm_sys::{core, analysis};
+use llvm_sys::prelude::*;
+
+use crate::llvm::{BasicBlock, Module};
+
+// an llvm value (constant, instruction, function, ...). Owned by its module, so this is only a handle.
+// It borrows the module, so it can't be used once the module has been disposed of
+#[derive(Clone, Copy)]
+pub struct Value<'m> {
+  raw: LLVMValueRef,
+  _marker: PhantomData<&'m Module<'m>>
+}
+
+impl <'m> Value<'m> {
+
+  pub(crate) fn from_raw(raw: LLVMValueRef) -> Value<'m> {
+    return Value { raw, _marker: PhantomData };
+  }
+  
+  pub fn as_raw(&self) -> LLVMValueRef {
+    return self.raw;
+  }
+  
+  pub fn name(&self) -> String {
+    return unsafe { CStr::from_ptr(core::LLVMGetValueName(self.raw)).to_string_lossy().into_owned() };
+  }
+  
+  // for globals: the value the global starts with
+  pub fn set_initializer(&self, value: Value<'m>) {
+    unsafe { core::LLVMSetInitializer(self.raw, value.as_raw()); }
+  }
+  
+  // for functions: the value of the parameter at the given index
+  pub fn param(&self, index: u32) -> Value<'m> {
+    return Value::from_raw(unsafe { core::LLVMGetParam(self.raw, index) });
+  }
+  
+  // for functions: whether this only declares the function (i.e. it has no body)
+  pub fn is_declaration(&self) -> bool {
+    return unsafe { core::LLVMIsDeclaration(self.raw) != 0 };
+  }
+  
+  // for functions: the basic blocks of the function's body, in order
+  pub fn basic_blocks(&self) -> Vec<BasicBlock<'m>> {
+    let mut blocks = vec![];
+    
+    unsafe {
+      let mut block = core::LLVMGetFirstBasicBlock(self.raw);
+      while !block.is_null() {
+        blocks.push(BasicBlock::from_raw(block));
+        block = core::LLVMGetNextBasicBlock(block);
+      }
+    }
+    
+    return blocks;
+  }
+  
+  // for functions: verify the function without printing or aborting. Returns true if it is valid
+  pub fn verify_function(&self) -> bool {
+    return unsafe { analysis::LLVMVerifyFunction(self.raw, analysis::LLVMVerifierFailureAction::LLVMReturnStatusAction) == 0 };
+  }
+  
+}
@@ -0,0 +1,198 @@
+// leveled logging to stderr, for following what the compiler does (`-v`, `-vv`, `--log=parser=trace`)
+// every message has a target, the part of the compiler it comes from (see `TARGETS`). Nothing below a warning is
+// written unless asked for, so normal runs are silent
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::diagnostics::Diagnostic;
+
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum Level {
+  Off,
+  Error,
+  Warn,
+  Info,
+  Debug,
+  Trace
+}
+
+const LEVELS: [Level; 6] = [Level::Off, Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+impl Level {
+  pub fn name(&self) -> &'static str {
+    return match self {
+      Level::Off => "off",
+      Level::Error => "error",
+      Level::Warn => "warn",
+      Level::Info => "info",
+      Level::Debug => "debug",
+      Level::Trace => "trace"
+    };
+  }
+
+  pub fn from_name(name: &str) -> Option<Level> {
+    return LEVELS.iter().find(|level| level.name() == name).copied();
+  }
+}
+
+// the parts of the compiler that log
+pub const TARGETS: &[&str] = &["driver", "lexer", "parser", "checker", "lint", "codegen", "llvm", "incremental", "lsp"];
+
+// which messages are written: those at or above a level for each target
+#[derive(Clone, PartialEq, Debug)]
+pub struct Filter {
+  pub level: Level,
+
+  // targets logged at a different level than the rest
+  pub targets: Vec<(String, Level)>
+}
+
+impl Filter {
+
+  pub fn new() -> Filter {
+    return Filter { level: Level::Warn, targets: vec![] };
+  }
+
+  // show one more level of detail everywhere (`-v` shows info, `-vv` debug and `-vvv` trace)
+  pub fn verbose(&mut self) {
+    if self.level < Level::Trace {
+      self.level = LEVELS[self.level as usize + 1];
+    }
+  }
+
+  // apply a comma separated list of levels, either for every target (`debug`) or for one (`parser=trace`)
+  pub fn parse(&mut self, spec: &str) -> Result<(), String> {
+    for directive in spec.split(',') {
+      let (target, level) = match directive.split_once('=') {
+        Some((target, level)) => (Some(target), level),
+        None => (None, directive)
+      };
+
+      let level = match Level::from_name(level) {
+        Some(level) => level,
+        None => return Err(format!("Unknown log level: '{}' (expected one of: off, error, warn, info, debug, trace)", level))
+      };
+
+      match target {
+        Some(target) if !TARGETS.contains(&target) => return Err(format!("Unknown log target: '{}' (expected one of: {})", target, TARGETS.join(", "))),
+        Some(target) => {
+          self.targets.retain(|(other, _)| other != target);
+          self.targets.push((String::from(target), level));
+        },
+        None => self.level = level
+      }
+    }
+
+    return Ok(());
+  }
+
+  pub fn enabled(&self, level: Level, target: &str) -> bool {
+    let max = match self.targets.iter().find(|(other, _)| other == target) {
+      Some((_, level)) => *level,
+      None => self.level
+    };
+
+    return level != Level::Off && level <= max;
+  }
+
+  // the most detailed level any target is logged at
+  fn max_level(&self) -> Level {
+    return self.targets.iter().map(|(_, level)| *level).fold(self.level, |max, level| if level > max { level } else { max });
+  }
+
+}
+
+impl Default for Filter {
+  fn default() -> Filter {
+    return Filter::new();
+  }
+}
+
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+
+// `max_level` of the filter, so most messages are skipped without taking the lock
+static MAX_LEVEL: AtomicUsize = AtomicUsize::new(Level::Warn as usize);
+
+// set which messages are written from now on
+pub fn init(filter: Filter) {
+  MAX_LEVEL.store(filter.max_level() as usize, Ordering::Relaxed);
+
+  if let Ok(mut current) = FILTER.write() {
+    *current = Some(filter);
+  }
+}
+
+pub fn enabled(level: Level, target: &str) -> bool {
+  if level as usize > MAX_LEVEL.load(Ordering::Relaxed) {
+    return false;
+  }
+
+  return match FILTER.read() {
+    Ok(filter) => match &*filter {
+      Some(filter) => filter.enabled(level, target),
+      None => Filter::new().enabled(level, target)
+    },
+    Err(_) => false
+  };
+}
+
+// write a message, whether or not it is enabled (see the `log!` macro)
+pub fn write(level: Level, target: &str, args: fmt::Arguments) {
+  eprintln!("[{} {}] {}", level.name(), target, args);
+}
+
+// log a message if its level is enabled for its target. The message is only formatted if it is written
+#[macro_export]
+macro_rules! log {
+  ($level:expr, $target:expr, $($arg:tt)+) => {
+    if $crate::log::enabled($level, $target) {
+      $crate::log::write($level, $target, format_args!($($arg)+));
+    }
+  };
+}
+
+#[macro_export]
+macro_rules! error {
+  ($target:expr, $($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $target, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+  ($target:expr, $($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $target, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+  ($target:expr, $($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $target, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+  ($target:expr, $($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $target, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+  ($target:expr, $($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $target, $($arg)+) };
+}
+
+thread_local! {
+  // internal errors found since they were last collected
+  static INTERNAL_ERRORS: RefCell<Vec<Diagnostic>> = const { RefCell::new(Vec::new()) };
+}
+
+// record something that should never happen, like a type codegen can't handle after the checker accepted it. These
+// are reported as internal compiler errors by the phase that found them (see `internal_errors`), rather than printed
+pub fn internal_error(target: &str, msg: String) {
+  debug!(target, "internal error: {}", msg);
+
+  INTERNAL_ERRORS.with(|errors| errors.borrow_mut().push(Diagnostic::ErrCompilerBug{phase: String::from(target), msg}));
+}
+
+// the internal errors recorded (on this thread) since this was last called
+pub fn internal_errors() -> Vec<Diagnostic> {
+  return INTERNAL_ERRORS.with(|errors| errors.borrow_mut().drain(..).collect());
+}
@@ -0,0 +1,551 @@
+// a language server, so editors can show problems and navigate programs as they are edited
+// messages are json-rpc, framed with a Content-Length header (usually over stdin and stdout)
+// documents are kept parsed as they are edited (see `incremental`), and re-analyzed whenever they change, without
+// generating code
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use crate::ast::Ast;
+use crate::diagnostics::{Diagnostic, Diagnostics};
+use crate::incremental::{self, Edit};
+use crate::index::SymbolKind;
+use crate::json::{self, Value};
+use crate::lint::Levels;
+use crate::tokenize::spec::KEYWORDS;
+use crate::tokenize::token::Span;
+
+// json-rpc error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+// an open document, and everything found when it was last analyzed
+struct Document {
+  program: incremental::Document,
+  lines: LineIndex,
+  diagnostics: Diagnostics
+}
+
+impl Document {
+  fn new(text: String) -> Document {
+    return Document {
+      lines: LineIndex::new(&text),
+      program: incremental::Document::new(text),
+      diagnostics: Diagnostics::new()
+    };
+  }
+
+  // apply one of the changes from a 'didChange' notification. Changes without a range replace the whole document
+  fn change(&mut self, change: &Value) -> Result<(), String> {
+    let text = match change.get("text").as_str() {
+      Some(text) => String::from(text),
+      None => return Err(String::from("Change is missing its text"))
+    };
+
+    if change.get("range").is_null() {
+      *self = Document::new(text);
+      return Ok(());
+    }
+
+    let offset = |position: &Value| match (position.get("line").as_u64(), position.get("character").as_u64()) {
+      (Some(line), Some(character)) => Ok(self.lines.offset(&self.program.text, line as usize, character as usize)),
+      _ => Err(String::from("Change has an invalid range"))
+    };
+
+    let start = offset(change.get("range").get("start"))?;
+    let end = offset(change.get("range").get("end"))?;
+
+    self.program.edit(&Edit { range: start..end.max(start), text });
+    self.lines = LineIndex::new(&self.program.text);
+
+    return Ok(());
+  }
+}
+
+// the same problems as `diagnose`, for a document that is already parsed
+fn analyze(ast: &Ast, levels: &Levels) -> Diagnostics {
+  let mut diagnostics = levels.apply(crate::check(ast));
+  diagnostics.append(crate::lint(ast, levels));
+
+  return diagnostics;
+}
+
+// converts between byte offsets in a document and lsp positions (lines from 0, and columns in utf-16 code units)
+struct LineIndex {
+  // byte offset where each line starts
+  starts: Vec<usize>
+}
+
+impl LineIndex {
+  fn new(text: &str) -> LineIndex {
+    let mut starts = vec![0];
+    starts.extend(text.match_indices('\n').map(|(offset, _)| offset + 1));
+
+    return LineIndex { starts };
+  }
+
+  // the line (from 0) an offset is on
+  fn line(&self, offset: usize) -> usize {
+    return match self.starts.binary_search(&offset) {
+      Ok(line) => line,
+      Err(line) => line - 1
+    };
+  }
+
+  // the text of a line, without its newline
+  fn line_text<'t>(&self, text: &'t str, line: usize) -> &'t str {
+    let start = self.starts[line];
+    let end = match self.starts.get(line + 1) {
+      Some(next) => next - 1,
+      None => text.len()
+    };
+
+    return text[start..end].trim_end_matches('\r');
+  }
+
+  fn position(&self, text: &str, offset: usize) -> Value {
+    let line = self.line(offset);
+    let character: usize = text[self.starts[line]..offset].chars().map(char::len_utf16).sum();
+
+    return position(line, character);
+  }
+
+  // the byte offset of a position. Positions past the end of a line are at its end
+  fn offset(&self, text: &str, line: usize, character: usize) -> usize {
+    if line >= self.starts.len() {
+      return text.len();
+    }
+
+    let start = self.starts[line];
+    let mut units = 0;
+
+    for (offset, ch) in self.line_text(text, line).char_indices() {
+      if units >= character {
+        return start + offset;
+      }
+      units += ch.len_utf16();
+    }
+
+    return start + self.line_text(text, line).len();
+  }
+
+  fn range(&self, text: &str, span: &Span) -> Value {
+    return range(self.position(text, span.start), self.position(text, span.end));
+  }
+
+  // a range covering whole lines (numbered from 1, like `line_num`s)
+  fn lines_range(&self, text: &str, first: u32, last: u32) -> Value {
+    let last_line = self.starts.len() - 1;
+    let first = (first.max(1) as usize - 1).min(last_line);
+    let last = (last.max(1) as usize - 1).min(last_line).max(first);
+
+    let end: usize = self.line_text(text, last).chars().map(char::len_utf16).sum();
+
+    return range(position(first, 0), position(last, end));
+  }
+}
+
+fn position(line: usize, character: usize) -> Value {
+  return json::object(vec![("line", number(line)), ("character", number(character))]);
+}
+
+fn range(start: Value, end: Value) -> Value {
+  return json::object(vec![("start", start), ("end", end)]);
+}
+
+fn number(value: usize) -> Value {
+  return Value::Number(value as f64);
+}
+
+fn string(value: &str) -> Value {
+  return Value::String(String::from(value));
+}
+
+// read one message, or None at the end of the input
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<String>> {
+  let mut length = None;
+
+  loop {
+    let mut header = String::new();
+    if input.read_line(&mut header)? == 0 {
+      return Ok(None);
+    }
+
+    let header = header.trim_end();
+    if header.is_empty() {
+      break;
+    }
+
+    if let Some(value) = header.strip_prefix("Content-Length:") {
+      length = value.trim().parse::<usize>().ok();
+    }
+  }
+
+  let length = match length {
+    Some(length) => length,
+    None => return Err(io::Error::new(io::ErrorKind::InvalidData, "Message is missing its Content-Length header"))
+  };
+
+  let mut content = vec![0; length];
+  input.read_exact(&mut content)?;
+
+  return match String::from_utf8(content) {
+    Ok(content) => Ok(Some(content)),
+    Err(_) => Err(io::Error::new(io::ErrorKind::InvalidData, "Message is not valid utf-8"))
+  };
+}
+
+pub fn write_message<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+  let content = message.to_json();
+
+  write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+  return output.flush();
+}
+
+pub struct Server {
+  documents: HashMap<String, Document>,
+
+  // the levels lints are reported at
+  levels: Levels,
+
+  initialized: bool,
+  shutdown: bool,
+
+  // the exit code, once an exit notification has been received
+  exit: Option<i32>
+}
+
+impl Server {
+
+  pub fn new(levels: Levels) -> Server {
+    return Server {
+      documents: HashMap::new(),
+      levels,
+      initialized: false,
+      shutdown: false,
+      exit: None
+    };
+  }
+
+  // handle messages until the client exits (or the input ends), returning the exit code
+  pub fn run<R: BufRead, W: Write>(&mut self, mut input: R, mut output: W) -> io::Result<i32> {
+    while let Some(content) = read_message(&mut input)? {
+      let replies = match json::parse(&content) {
+        Ok(message) => self.handle(&message),
+        Err(msg) => vec![error_response(Value::Null, PARSE_ERROR, &msg)]
+      };
+
+      for reply in replies {
+        write_message(&mut output, &reply)?;
+      }
+
+      if let Some(code) = self.exit {
+        return Ok(code);
+      }
+    }
+
+    return Ok(1);
+  }
+
+  // handle one message, returning the messages to send back (a response, and any notifications)
+  pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+    let method = match message.get("method").as_str() {
+      Some(method) => method,
+      // responses to requests from the server (it doesn't send any)
+      None => return vec![]
+    };
+
+    let params = message.get("params");
+    let id = message.get("id");
+
+    debug!("lsp", "{} {}", if id.is_null() { "notification" } else { "request" }, method);
+
+    // notifications have no id, and aren't answered
+    if id.is_null() {
+      return self.notification(method, params);
+    }
+
+    let result = if self.shutdown {
+      Err((INVALID_REQUEST, String::from("The server is shutting down")))
+    } else if !self.initialized && method != "initialize" {
+      Err((SERVER_NOT_INITIALIZED, String::from("The server hasn't been initialized")))
+    } else {
+      self.request(method, params)
+    };
+
+    return vec![match result {
+      Ok(result) => json::object(vec![("jsonrpc", string("2.0")), ("id", id.clone()), ("result", result)]),
+      Err((code, msg)) => error_response(id.clone(), code, &msg)
+    }];
+  }
+
+  fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    return match method {
+      "initialize" => {
+        self.initialized = true;
+        Ok(capabilities())
+      },
+      "shutdown" => {
+        self.shutdown = true;
+        Ok(Value::Null)
+      },
+      "textDocument/hover" => self.hover(params),
+      "textDocument/definition" => self.definition(params),
+      "textDocument/references" => self.references(params),
+      "textDocument/documentSymbol" => self.document_symbols(params),
+      "textDocument/completion" => self.completion(params),
+      _ => Err((METHOD_NOT_FOUND, format!("Unknown method: '{}'", method)))
+    };
+  }
+
+  fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+    let uri = params.get("textDocument").get("uri").as_str().map(String::from);
+
+    match (method, uri) {
+      ("exit", _) => self.exit = Some(if self.shutdown { 0 } else { 1 }),
+      ("textDocument/didOpen", Some(uri)) => {
+        let text = params.get("textDocument").get("text").as_str().unwrap_or("");
+        self.documents.insert(uri.clone(), Document::new(String::from(text)));
+
+        return self.analyze(&uri);
+      },
+      ("textDocument/didChange", Some(uri)) => {
+        let document = match self.documents.get_mut(&uri) {
+          Some(document) => document,
+          None => return vec![]
+        };
+
+        // changes are applied in order, each to the text left by the one before
+        for change in params.get("contentChanges").as_array() {
+          // a change that can't be applied means the document is out of sync, so it is dropped
+          if document.change(change).is_err() {
+            self.documents.remove(&uri);
+            return vec![publish_diagnostics(&uri, vec![])];
+          }
+        }
+
+        return self.analyze(&uri);
+      },
+      ("textDocument/didClose", Some(uri)) => {
+        self.documents.remove(&uri);
+        return vec![publish_diagnostics(&uri, vec![])];
+      },
+      // anything else (e.g. 'initialized') needs nothing done
+      _ => ()
+    }
+
+    return vec![];
+  }
+
+  // analyze a document after it has changed, and publish what is wrong with it
+  fn analyze(&mut self, uri: &str) -> Vec<Value> {
+    let document = match self.documents.get_mut(uri) {
+      Some(document) => document,
+      None => return vec![]
+    };
+
+    document.diagnostics = analyze(&document.program.ast, &self.levels);
+
+    let diagnostics = document.diagnostics.list.iter().map(|diagnostic| lsp_diagnostic(document, diagnostic)).collect();
+
+    return vec![publish_diagnostics(uri, diagnostics)];
+  }
+
+  // the document a request is about, and the byte offset of its position
+  fn document_at(&self, params: &Value) -> Result<(&Document, usize), (i64, String)> {
+    let document = self.document(params)?;
+
+    let position = params.get("position");
+    let (line, character) = match (position.get("line").as_u64(), position.get("character").as_u64()) {
+      (Some(line), Some(character)) => (line as usize, character as usize),
+      _ => return Err((INVALID_PARAMS, String::from("Missing position")))
+    };
+
+    return Ok((document, document.lines.offset(&document.program.text, line, character)));
+  }
+
+  fn document(&self, params: &Value) -> Result<&Document, (i64, String)> {
+    let uri = params.get("textDocument").get("uri").as_str().unwrap_or("");
+
+    return match self.documents.get(uri) {
+      Some(document) => Ok(document),
+      None => Err((INVALID_PARAMS, format!("Document isn't open: '{}'", uri)))
+    };
+  }
+
+  fn hover(&self, params: &Value) -> Result<Value, (i64, String)> {
+    let (document, offset) = self.document_at(params)?;
+
+    let symbol = match document.program.index.symbol_at(offset) {
+      Some(symbol) => &document.program.index.symbols[symbol],
+      None => return Ok(Value::Null)
+    };
+
+    let contents = json::object(vec![
+      ("kind", string("plaintext")),
+      ("value", Value::String(format!("{} {} : {}", symbol.kind.name(), symbol.name, symbol.r#type)))
+    ]);
+
+    return Ok(json::object(vec![("contents", contents)]));
+  }
+
+  fn definition(&self, params: &Value) -> Result<Value, (i64, String)> {
+    let (document, offset) = self.document_at(params)?;
+    let uri = params.get("textDocument").get("uri");
+
+    return Ok(match document.program.index.symbol_at(offset) {
+      // builtins aren't declared in the document
+      Some(symbol) if document.program.index.symbols[symbol].kind != SymbolKind::Builtin => {
+        location(uri, document.lines.range(&document.program.text, &document.program.index.symbols[symbol].span))
+      },
+      _ => Value::Null
+    });
+  }
+
+  fn references(&self, params: &Value) -> Result<Value, (i64, String)> {
+    let (document, offset) = self.document_at(params)?;
+    let uri = params.get("textDocument").get("uri");
+
+    let symbol = match document.program.index.symbol_at(offset) {
+      Some(symbol) => symbol,
+      None => return Ok(Value::Array(vec![]))
+    };
+
+    let mut locations = vec![];
+
+    let declaration = &document.program.index.symbols[symbol];
+    if params.get("context").get("includeDeclaration").as_bool() == Some(true) && declaration.kind != SymbolKind::Builtin {
+      locations.push(location(uri, document.lines.range(&document.program.text, &declaration.span)));
+    }
+
+    for reference in document.program.index.references_to(symbol) {
+      locations.push(location(uri, document.lines.range(&document.program.text, &reference.span)));
+    }
+
+    return Ok(Value::Array(locations));
+  }
+
+  // the declarations in a document, with the declarations in each procedure nested inside it
+  fn document_symbols(&self, params: &Value) -> Result<Value, (i64, String)> {
+    let document = self.document(params)?;
+
+    return Ok(Value::Array(document_symbols(document, None)));
+  }
+
+  fn completion(&self, params: &Value) -> Result<Value, (i64, String)> {
+    let (document, offset) = self.document_at(params)?;
+    let line_num = document.lines.line(offset) as u32 + 1;
+
+    let mut items = vec![];
+
+    for symbol in document.program.index.visible_at(line_num) {
+      let symbol = &document.program.index.symbols[symbol];
+
+      let kind = match symbol.kind {
+        SymbolKind::Procedure | SymbolKind::Builtin => 3,
+        SymbolKind::Variable | SymbolKind::Parameter => 6,
+        SymbolKind::Type => 25
+      };
+
+      items.push(json::object(vec![
+        ("label", string(&symbol.name)),
+        ("kind", number(kind)),
+        ("detail", Value::String(symbol.r#type.to_string()))
+      ]));
+    }
+
+    for (keyword, _) in KEYWORDS {
+      items.push(json::object(vec![("label", string(keyword)), ("kind", number(14))]));
+    }
+
+    return Ok(Value::Array(items));
+  }
+
+}
+
+// what the server can do, as the result of 'initialize'
+fn capabilities() -> Value {
+  let capabilities = json::object(vec![
+    // only the changed part of a document is sent when it changes
+    ("textDocumentSync", number(2)),
+    ("hoverProvider", Value::Bool(true)),
+    ("definitionProvider", Value::Bool(true)),
+    ("referencesProvider", Value::Bool(true)),
+    ("documentSymbolProvider", Value::Bool(true)),
+    ("completionProvider", json::object(vec![]))
+  ]);
+
+  let server_info = json::object(vec![("name", string("sangzf-lsp")), ("version", string(env!("CARGO_PKG_VERSION")))]);
+
+  return json::object(vec![("capabilities", capabilities), ("serverInfo", server_info)]);
+}
+
+fn error_response(id: Value, code: i64, msg: &str) -> Value {
+  let error = json::object(vec![("code", Value::Number(code as f64)), ("message", string(msg))]);
+
+  return json::object(vec![("jsonrpc", string("2.0")), ("id", id), ("error", error)]);
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+  let params = json::object(vec![("uri", string(uri)), ("diagnostics", Value::Array(diagnostics))]);
+
+  return json::object(vec![("jsonrpc", string("2.0")), ("method", string("textDocument/publishDiagnostics")), ("params", params)]);
+}
+
+fn location(uri: &Value, range: Value) -> Value {
+  return json::object(vec![("uri", uri.clone()), ("range", range)]);
+}
+
+// diagnostics cover the whole line they are reported on. Those without a line are at the end of the document
+fn lsp_diagnostic(document: &Document, diagnostic: &Diagnostic) -> Value {
+  let last_line = document.lines.starts.len() as u32;
+  let line_num = diagnostic.line_num().unwrap_or(last_line);
+
+  let mut message = diagnostic.text();
+  for note in diagnostic.notes() {
+    message.push('\n');
+    message.push_str(&note);
+  }
+
+  return json::object(vec![
+    ("range", document.lines.lines_range(&document.program.text, line_num, line_num)),
+    // errors are 1, warnings 2
+    ("severity", number(if diagnostic.is_error() { 1 } else { 2 })),
+    ("code", string(diagnostic.code())),
+    ("source", string("sangzf")),
+    ("message", Value::String(message))
+  ]);
+}
+
+// the symbols declared in a procedure (or outside every procedure)
+fn document_symbols(document: &Document, procedure: Option<usize>) -> Vec<Value> {
+  let mut symbols = vec![];
+
+  for (id, symbol) in document.program.index.symbols.iter().enumerate() {
+    if symbol.procedure != procedure || symbol.kind == SymbolKind::Builtin {
+      continue;
+    }
+
+    let selection = document.lines.range(&document.program.text, &symbol.span);
+
+    let (kind, range, children) = match (symbol.kind, symbol.lines) {
+      (SymbolKind::Procedure, Some((start, end))) => (12, document.lines.lines_range(&document.program.text, start, end), document_symbols(document, Some(id))),
+      (SymbolKind::Type, _) => (26, selection.clone(), vec![]),
+      _ => (13, selection.clone(), vec![])
+    };
+
+    symbols.push(json::object(vec![
+      ("name", string(&symbol.name)),
+      ("detail", Value::String(symbol.r#type.to_string())),
+      ("kind", number(kind)),
+      ("range", range),
+      ("selectionRange", selection),
+      ("children", Value::Array(children))
+    ]));
+  }
+
+  return symbols;
+}
@@ -1,24 +1,19 @@
 use std::env;
+use std::fs;
 use std::fs::File;
 use std::io::*;
+use std::process;
 
 extern crate llvm_sys;
+#[macro_use]
+extern crate sangzf_compiler;
 
 use llvm_sys::{target};
 
-// expose llvm utility functions
-mod llvm_utils;
-
-// expose runtime functions
-mod builtins;
-
-// expose token utilities (State struct, Token trait)
-mod tokenize;
-
-mod tokens;
-
-mod lexer;
-mod parser;
+use sangzf_compiler::codes;
+use sangzf_compiler::emit;
+use sangzf_compiler::log;
+use sangzf_compiler::options::{Command, Emit, Format, Options};
 
 
 fn initialise_llvm() {
@@ -29,42 +24,153 @@ fn initialise_llvm() {
         if target::LLVM_InitializeNativeAsmPrinter() != 0 {
             panic!("Could not initialise ASM Printer");
         }
-    }    
+    }
 }
 
 
 fn main() {
 
-  // check that llvm is ready to run
-  initialise_llvm();
+  // skip the executable name, the rest are options and the program name
+  let args : Vec<String> = env::args().skip(1).collect();
+
+  let options = match Options::from_args(args) {
+    Ok(options) => options,
+    Err(msg) => {
+      println!("{}", msg);
+      process::exit(1);
+    }
+  };
+
+  log::init(options.log.clone());
 
-  // mutable since we will want to remove the program_name arg
-  let mut args : Vec<String> = env::args().collect();
-  
-  let mut program_name = String::from("sample_programs/correct/source.src");
-  if args.len() == 2 {
-    // get the second argument if there are 2 args in argv
-    program_name = args.remove(1);
+  if let Some(code) = &options.explain {
+    match codes::find(code) {
+      Some(code) => print!("{}", codes::explain(code)),
+      None => {
+        println!("Unknown code: '{}'", code);
+        process::exit(1);
+      }
+    }
+
+    process::exit(0);
   }
-  
+
   // test program
-  let mut program_file = File::open(program_name).expect("Could not open file");
+  let mut program_file = File::open(&options.program_name).expect("Could not open file");
   let mut program = String::new();
-  
+
   program_file.read_to_string(&mut program).expect("Could not read file");
-  
-  // should have 51 tokens
-  //let program = String::from(" program procedure global   ??? variable begin end is type integer float string bool enum if then else for return not true false . ; ( ) , { } - & + < > <= >= == != * / [ ] | := abcdef 1234 898.99 \"stringgoeshere\" \"fancie$t  string_g0es\n\nhere\t\"");
-  
-  // should have 3 tokens
-  //let program = String::from("abc/**this is /*///*a***/*/ doc*/+bcd");
-  
-  //program = String::from("program p Is procedure proc : enum{a,b,c}(variable a : integer, variable b: bool, variable c: string, variable d: float) begin end procedure;  begin end program.");
-  
-  let program_chars = program.chars().peekable();
-  
-  let mut parser = parser::Parser::new(program_chars);
-  
-  parser.program();
-  
-}
\ No newline at end of file
+  info!("driver", "read '{}' ({} bytes)", options.program_name, program.len());
+
+  if options.command == Command::Fmt {
+    let formatted = match sangzf_compiler::format(&program) {
+      Ok(formatted) => formatted,
+      Err(diagnostics) => {
+        diagnostics.print();
+        process::exit(1);
+      }
+    };
+
+    if formatted == program {
+      process::exit(0);
+    }
+
+    if options.check {
+      println!("'{}' is not formatted", options.program_name);
+      process::exit(1);
+    }
+
+    if let Err(err) = fs::write(&options.program_name, formatted) {
+      println!("Could not write '{}': {}", options.program_name, err);
+      process::exit(1);
+    }
+
+    process::exit(0);
+  }
+
+  if let Some(Emit::Tokens) = options.emit {
+    let (tokens, diagnostics) = sangzf_compiler::lex(&program);
+
+    print!("{}", emit::tokens(&tokens, options.format));
+
+    // keep json output parseable
+    if options.format == Format::Text {
+      diagnostics.print();
+    }
+
+    process::exit(if diagnostics.has_errors() { 1 } else { 0 });
+  }
+
+  if let Some(Emit::Symbols) = options.emit {
+    let (index, diagnostics) = sangzf_compiler::symbols(&program, &options.lints);
+
+    print!("{}", emit::symbols(&index, options.format));
+
+    if options.format == Format::Text {
+      diagnostics.print();
+    }
+
+    process::exit(if diagnostics.has_errors() { 1 } else { 0 });
+  }
+
+  if options.command == Command::Check {
+    let diagnostics = sangzf_compiler::diagnose(&program, &options.lints);
+    diagnostics.print();
+
+    process::exit(if diagnostics.has_errors() { 1 } else { 0 });
+  }
+
+  // llvm is only needed to generate code
+  initialise_llvm();
+
+  // keep a json dump parseable, the exit code still shows whether there were errors
+  let quiet = options.dump.is_some() && options.format == Format::Json;
+  let time_passes = options.time_passes;
+  let format = options.format;
+
+  let artifact = sangzf_compiler::compile(&program, options);
+
+  if let Some(symtab) = &artifact.symtab {
+    print!("{}", symtab);
+  }
+
+  if !quiet {
+    artifact.diagnostics.print();
+  }
+
+  // on stderr, so it doesn't mix with the program's output
+  if time_passes {
+    eprint!("{}", artifact.timings.report(format));
+  }
+
+  // output contents of llvm program
+  if let Some(bitcode) = &artifact.bitcode {
+    let filename = format!("{}.bc", artifact.name);
+
+    if fs::write(&filename, bitcode).is_err() {
+      println!("Error writing bitcode to file: '{}'", filename);
+    } else {
+      info!("driver", "wrote {} bytes of bitcode to '{}'", bitcode.len(), filename);
+    }
+  }
+
+  if let Some(ir) = &artifact.ir {
+    let filename = format!("{}.ll", artifact.name);
+
+    match fs::write(&filename, ir) {
+      Ok(_) => println!("Generated ir written to '{}', please include it in a bug report.", filename),
+      Err(err) => println!("Could not write ir to '{}': {}", filename, err)
+    }
+  } else if artifact.diagnostics.has_internal_error() {
+    println!("Rerun with --dump-ir to save the generated ir for a bug report.");
+  }
+
+  if artifact.diagnostics.has_errors() {
+    process::exit(1);
+  }
+
+  if !quiet {
+    println!("Program parsed.");
+  }
+
+}
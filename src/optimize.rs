@@ -0,0 +1,54 @@
+// the llvm passes run over a generated module before it is written, for each optimization level (`-O0`..`-O3`, `-Os`)
+
+use crate::llvm::{Module, PassManager};
+use crate::options::OptLevel;
+
+pub use crate::llvm::Pass;
+
+// the passes for a level, in the order they run. Codegen stores every variable in an alloca, so each level past
+// `-O0` starts by promoting them to registers, and the rest clean up after it
+pub fn passes(level: OptLevel) -> Vec<Pass> {
+  // enough to turn loads and stores into plain values, and fold what that exposes
+  let basic = vec![Pass::PromoteMemoryToRegister, Pass::InstructionCombining, Pass::Reassociate, Pass::CfgSimplification];
+
+  return match level {
+    OptLevel::O0 => vec![],
+    OptLevel::O1 => basic,
+    OptLevel::O2 | OptLevel::O3 | OptLevel::Os => {
+      let mut passes = basic;
+
+      // inlining makes code larger, so it isn't done when optimizing for size
+      if level != OptLevel::Os {
+        passes.extend(&[Pass::FunctionInlining, Pass::PromoteMemoryToRegister, Pass::InstructionCombining]);
+      }
+
+      passes.extend(&[Pass::EarlyCse, Pass::Gvn]);
+
+      // rotating loops duplicates their condition, and unrolling them their body
+      if level != OptLevel::Os {
+        passes.push(Pass::LoopRotate);
+      }
+
+      passes.extend(&[Pass::Licm, Pass::IndVarSimplify]);
+
+      if level == OptLevel::O3 {
+        passes.push(Pass::LoopUnroll);
+      }
+
+      passes.extend(&[Pass::DeadStoreElimination, Pass::InstructionCombining, Pass::CfgSimplification]);
+      passes
+    }
+  };
+}
+
+// run the passes for a level over a module
+pub(crate) fn optimize(module: &Module, level: OptLevel) {
+  let manager = PassManager::new();
+
+  for pass in passes(level) {
+    manager.add(pass);
+  }
+
+  let changed = manager.run(module);
+  debug!("llvm", "ran {} passes, the module {}", passes(level).len(), if changed { "changed" } else { "didn't change" });
+}
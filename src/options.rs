@@ -0,0 +1,194 @@
+use crate::lint::{Level, Levels, Lint};
+use crate::log;
+
+// options controlling a single run of the compiler, read from the command line
+#[derive(Clone)]
+pub struct Options {
+  pub command: Command,
+
+  pub program_name: String,
+  
+  // write the llvm ir to '<program>.ll' if the generated module fails verification
+  pub dump_ir: bool,
+
+  // print an intermediate form of the program instead of compiling it
+  pub emit: Option<Emit>,
+
+  // print some of the compiler's internal state while compiling (see `dump`)
+  pub dump: Option<Dump>,
+
+  // how emitted and dumped output is printed
+  pub format: Format,
+
+  // for `fmt`, only check whether the program is formatted instead of rewriting it
+  pub check: bool,
+
+  // which lints are allowed, warned about or denied
+  pub lints: Levels,
+
+  // print the explanation of a diagnostic code instead of compiling
+  pub explain: Option<String>,
+
+  // which llvm passes are run before the module is written (see `optimize`)
+  pub opt_level: OptLevel,
+
+  // print how long each phase took (to stderr) after compiling
+  pub time_passes: bool,
+
+  // which log messages are written to stderr (`-v`, `-vv`, `--log=parser=trace`)
+  pub log: log::Filter
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Command {
+  Compile,
+
+  // rewrite the program in the canonical style
+  Fmt,
+
+  // only report problems with the program, without generating code
+  Check
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Emit {
+  Tokens,
+
+  // every declaration and reference, with its kind, type and scope
+  Symbols
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Dump {
+  // the symbol tables of every scope
+  Symtab
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OptLevel {
+  // no optimization, for debugging
+  O0,
+  O1,
+  O2,
+  O3,
+
+  // optimize, without making the code larger
+  Os
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+  Text,
+  Json
+}
+
+impl Options {
+
+  pub fn new() -> Options {
+    return Options {
+      command: Command::Compile,
+      program_name: String::from("sample_programs/correct/source.src"),
+      dump_ir: false,
+      emit: None,
+      dump: None,
+      format: Format::Text,
+      check: false,
+      lints: Levels::new(),
+      explain: None,
+      opt_level: OptLevel::O0,
+      time_passes: false,
+      log: log::Filter::new()
+    };
+  }
+  
+  // parse options from the command line arguments (excluding the executable name)
+  pub fn from_args(args: Vec<String>) -> Result<Options, String> {
+    let mut options = Options::new();
+    
+    let mut args = args.into_iter().peekable();
+
+    // an optional subcommand comes first
+    let command = match args.peek().map(|arg| &arg[..]) {
+      Some("fmt") => Some(Command::Fmt),
+      Some("check") => Some(Command::Check),
+      _ => None
+    };
+
+    if let Some(command) = command {
+      options.command = command;
+      args.next();
+    }
+
+    while let Some(arg) = args.next() {
+      match &arg[..] {
+        "--dump-ir" => options.dump_ir = true,
+        "--emit=tokens" => options.emit = Some(Emit::Tokens),
+        "--emit=symbols" => options.emit = Some(Emit::Symbols),
+        "--dump=symtab" => options.dump = Some(Dump::Symtab),
+        "--format=text" => options.format = Format::Text,
+        "--format=json" => options.format = Format::Json,
+        "--check" => options.check = true,
+        "--time-passes" => options.time_passes = true,
+        "-O0" => options.opt_level = OptLevel::O0,
+        "-O1" => options.opt_level = OptLevel::O1,
+        "-O2" => options.opt_level = OptLevel::O2,
+        "-O3" => options.opt_level = OptLevel::O3,
+        "-Os" => options.opt_level = OptLevel::Os,
+        "-v" => options.log.verbose(),
+        "-vv" | "-vvv" => {
+          for _ in 1..arg.len() {
+            options.log.verbose();
+          }
+        },
+        "--explain" => match args.next() {
+          Some(code) => options.explain = Some(code),
+          None => return Err(String::from("'--explain' needs a code, e.g. '--explain E0102'"))
+        },
+        _ => {
+          if let Some(spec) = arg.strip_prefix("--log=") {
+            options.log.parse(spec)?;
+            continue;
+          }
+
+          // set the level of a lint, e.g. '--deny=unused-variable'
+          if let Some((flag, name)) = arg.split_once('=') {
+            let level = match flag {
+              "--allow" => Some(Level::Allow),
+              "--warn" => Some(Level::Warn),
+              "--deny" => Some(Level::Deny),
+              _ => None
+            };
+
+            if let Some(level) = level {
+              match Lint::from_name(name) {
+                Some(lint) => options.lints.set(lint, level),
+                None => return Err(format!("Unknown lint: '{}'", name))
+              }
+
+              continue;
+            }
+          }
+
+          if arg.starts_with("-") {
+            return Err(format!("Unrecognized option: '{}'", arg));
+          }
+          
+          options.program_name = arg;
+        }
+      }
+    }
+    
+    if options.check && options.command != Command::Fmt {
+      return Err(String::from("'--check' can only be used with 'fmt'"));
+    }
+
+    return Ok(options);
+  }
+  
+}
+
+impl Default for Options {
+  fn default() -> Options {
+    return Options::new();
+  }
+}
@@ -1,1877 +1,1133 @@
-extern crate llvm_sys;
-
-// import llvm dependencies
-use llvm_sys::prelude::*;
-use llvm_sys::core::*;
-use llvm_sys::{core, bit_writer, analysis, support};
-
-// llvm references used as guides
-// * introduction to building llvm program using c-apis: https://pauladamsmith.com/blog/2015/01/how-to-get-started-with-llvm-c-api.html
-// * using llvm with rust (+ webassembly, but I didn't use that part): https://medium.com/@jayphelps/using-llvm-from-rust-to-generate-webassembly-93e8c193fdb4
-// * walkthrough on a compiler with llvm: https://releases.llvm.org/1.1/docs/Stacker.html#terminate
-
-use std::iter::Peekable;
-use std::str::Chars;
-use std::collections::HashMap;
-use std::mem;
-use std::rc::Rc;
-use std::slice::Iter;
-
-use crate::tokenize::lexable::Lexable;
 use crate::lexer::Lexer;
 use crate::tokenize::token::Token;
 use crate::tokenize::token::TokenEntry;
 use crate::tokenize::token::Type;
 
-use crate::builtins;
-use crate::llvm_utils::{c_str, null_str, error_buffer, get_llvm_type};
+use crate::ast::*;
+use crate::cst::{self, Cst, SyntaxKind};
+use crate::diagnostics::{Diagnostic, Diagnostics};
+use crate::suggest;
+
+use std::mem;
+use std::ops::Range;
+
 
-use crate::tokens;
+// each parse rule either produces its piece of the syntax tree, or the reason the parse failed
+pub type ParserResult<T> = Result<T, Diagnostic>;
 
+// one pass of the loop reading the program's declarations: a declaration (or one that failed to parse), or a
+// token that was deleted, along with everything skipped after it
+// these are recorded so that a run of them can be reparsed on its own when the program is edited
+#[derive(Clone, Debug)]
+pub struct Item {
+  // the indices of the tokens read
+  pub tokens: Range<usize>,
+
+  // how many declarations were added to the program (0 or 1)
+  pub declarations: usize,
+
+  // the indices of the errors reported in the parser's diagnostics
+  pub diagnostics: Range<usize>,
+
+  // whether the last error was reported at the token after the item, which stops an error there from being reported
+  pub ends_with_error: bool
+}
 
 pub struct Parser<'a> {
-  pub lexer: Peekable<Lexer<'a>>,
-  pub symbol_table_chain: Vec<HashMap<String, Rc<TokenEntry>>>,
-  pub global_symbol_table: HashMap<String, Rc<TokenEntry>>,
-  pub llvm_module: *mut llvm_sys::LLVMModule
+  pub lexer: Lexer<'a>,
+
+  // errors that were recovered from while parsing
+  pub diagnostics: Diagnostics,
+
+  // records the concrete syntax tree alongside the ast, when parsing losslessly
+  cst: Option<cst::Builder<'a>>,
+
+  // where the last error was reported (the byte position of the next token). Errors at the same
+  // position are caused by the first one, so they aren't reported
+  last_error: Option<usize>,
+
+  // the line of the last token consumed
+  prev_line: u32,
+
+  // how many tokens have been consumed
+  read: usize,
+
+  // the program's top level declarations
+  pub items: Vec<Item>
 }
 
 impl <'a>Parser<'a> {
-  pub fn new(program: Peekable<Chars<'a>>) -> Self {
-  
-    let lexer = Lexer::new(program);
-    
-    let symbol_table_chain = vec![];
-    
+  pub fn new(program: &'a str) -> Self {
+
+    return Parser::with_lexer(Lexer::new(program));
+  }
+
+  pub fn with_lexer(lexer: Lexer<'a>) -> Self {
     let parser = Parser {
-      lexer: lexer.peekable(),
-      symbol_table_chain: symbol_table_chain,
-      global_symbol_table: HashMap::new(),
-      llvm_module: unsafe { core::LLVMModuleCreateWithName(c_str("compiler_module")) }
+      lexer,
+      diagnostics: Diagnostics::new(),
+      cst: None,
+      last_error: None,
+      prev_line: 1,
+      read: 0,
+      items: vec![]
     };
-    
+
     return parser;
   }
-  
-  
-  /* 
+
+  // parse the whole program into a syntax tree
+  pub fn parse(mut self) -> Ast {
+    return self.parse_program();
+  }
+
+  // parse the whole program into a syntax tree, also returning the items read for its top level declarations
+  pub fn parse_items(mut self) -> (Ast, Vec<Item>) {
+    let ast = self.parse_program();
+
+    return (ast, self.items);
+  }
+
+  // parse the whole program into a syntax tree, along with a concrete syntax tree that keeps every character of the program
+  pub fn parse_lossless(mut self) -> (Ast, Cst) {
+    let mut builder = cst::Builder::new(self.lexer.program);
+    self.cst = Some(builder);
+
+    let ast = self.parse_program();
+
+    // anything after the end of the program is still part of the file (it has already been reported)
+    while self.next_tok().is_some() {}
+
+    builder = self.cst.take().unwrap_or_else(|| cst::Builder::new(self.lexer.program));
+
+    return (ast, builder.finish());
+  }
+
+  fn parse_program(&mut self) -> Ast {
+    let program = match self.node(SyntaxKind::Program, Parser::program) {
+      Ok(program) => Some(program),
+      Err(err) => {
+        self.report(err);
+        None
+      }
+    };
+
+    // problems found by the lexer come first, since they usually cause the syntax errors
+    let mut diagnostics = mem::take(&mut self.lexer.diagnostics);
+    diagnostics.append(mem::take(&mut self.diagnostics));
+
+    return Ast { program, diagnostics };
+  }
+
+
+  /*
     Below are functions for different parse rules
-    Each function operates on an instance of the parser, processes the parse rule, and returns the success state or the reason why the parse failed
+    Each function operates on an instance of the parser, processes the parse rule, and returns the parsed node or the reason why the parse failed
     On a failed parse, an unknown number of tokens will be consumed (may have partially consumed)
     For 'parse_tok', if unsuccessful, no tokens will have been consumed
   */
-  
-  pub fn program(&mut self) -> ParserResult {
-  
-    // create a global symbol table
-    self.symbol_table_chain.push(HashMap::new());
-    
-    // set up the built-in functions
-    // TODO make this actually work so manually linking isn't required
-    /*
-    unsafe {
-      if support::LLVMLoadLibraryPermanently(c_str("./src/builtins/builtins.so")) == 0 {
-        println!("load library: true");
+
+  pub fn program(&mut self) -> ParserResult<Program> {
+
+    let name = self.node(SyntaxKind::ProgramHeader, Parser::program_header)?;
+    let start_line = name.line_num;
+
+    let (declarations, statements) = self.node(SyntaxKind::ProgramBody, |slf| slf.program_body(start_line))?;
+
+    // Check for terminating period
+    // the rest of the program is still usable without it, so this isn't fatal
+    if let Err(err) = self.parse_tok(Token::Period) {
+      self.report(err);
+    } else if let Some(tok_entry) = self.lexer.peek() {
+      // unexpected token after end of program
+      let err = Diagnostic::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from("<end of program>"), actual: String::from(&tok_entry.chars[..]), suggestion: None};
+      self.report(err);
+    }
+
+    return Ok(Program { name, declarations, statements });
+  }
+
+  pub fn program_header(&mut self) -> ParserResult<Identifier> {
+    self.parse_tok(Token::ProgramKW)?;
+    let identifier = self.identifier()?;
+
+    // a missing 'is' is inserted if the body follows, and a token in its place is replaced
+    if let Err(err) = self.parse_tok(Token::IsKW) {
+      let is_body_start = |tok_entry: Option<&TokenEntry>| match tok_entry {
+        Some(tok_entry) => tok_entry.tok_type == Token::BeginKW || Parser::is_declaration_start(&tok_entry.tok_type),
+        None => false
+      };
+
+      if is_body_start(self.lexer.peek()) {
+        self.report(err);
+      } else if is_body_start(self.lexer.peek_second()) {
+        self.report(err);
+        self.next_tok();
       } else {
-        println!("load library: false");
+        return Err(err);
       }
     }
-    */
-    
-    let program_header = self.program_header();
-    if let ParserResult::Success(identifier_entry) = program_header {
-    
-      // create type for program
-      let program_ret_type = unsafe { core::LLVMVoidType() };
-      
-      // create arguments type for program
-      let program_param_types = unsafe {
-        let param_types = [core::LLVMInt32Type(), core::LLVMInt32Type()].as_mut_ptr();
-        param_types
-      };
-      
-      // create function type
-      let program_type = unsafe { core::LLVMFunctionType(program_ret_type, program_param_types, 0, 0) };
-      
-      // create main program function (type: core::LLVMValueRef)
-      let program = unsafe { core::LLVMAddFunction(self.llvm_module, c_str("main"), program_type) };
-      
-      // add basic block to the program function (type: core::LLVMBasicBlockRef)
-      let entry = unsafe { core::LLVMAppendBasicBlock(program, c_str("entry")) };
-      
-      // create builder and position it at the end of basic block (type: LLVMBuilderRef)
-      let mut builder = unsafe { 
-        let b = core::LLVMCreateBuilder();
-        core::LLVMPositionBuilderAtEnd(b, entry);
-        
-        b
-      };
-      
-      
-      self.add_builtins(&mut builder);
-    
-      let program_body = self.program_body(&mut builder);
-      if let ParserResult::Success(_) = program_body {
-      
-        // build the return
-        unsafe {
-          core::LLVMBuildRetVoid(builder);
-        }
-      
-        // verify the module
-        unsafe {
-          let mut error: *mut *mut i8 = error_buffer();
-          analysis::LLVMVerifyModule(self.llvm_module, analysis::LLVMVerifierFailureAction::LLVMAbortProcessAction, error);
-          
-          core::LLVMDisposeMessage(*error);
-        }
-      
-        let mut filename = identifier_entry.chars;
-        filename.push_str(".bc");
-      
-        // output contents of llvm program
-        unsafe {
-          if bit_writer::LLVMWriteBitcodeToFile(self.llvm_module, c_str(&filename)) != 0 {
-            //core::LLVMPrintModuleToFile(self.llvm_module, c_str("dump.ll"), error_buffer());
-            //println!("error writing bitcode to file, dumping ir to file\n");
-          }
-        }
-        
-        // clean up builder, module, and context
-        unsafe {
-          core::LLVMDisposeModule(self.llvm_module);
-          core::LLVMDisposeBuilder(builder);
-        }
-      
-        // Check for terminating period
-        let period = self.parse_tok(tokens::period::Period::start());
-        if let ParserResult::Success(period_entry) = period {
-          
-          if let Some(tok_entry) = self.lexer.peek() {
-            // unexpected token after end of program
-            let result = ParserResult::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from("<end of program>"), actual: String::from(&tok_entry.chars[..])};
-            
-            result.print();
-            
-            return result;
-          } else {
-            if let Some(global_table) = self.symbol_table_chain.pop() {
-              Parser::print_symbol_table(String::from("Global table"), &global_table);
-            }
-            println!("Program parsed.");
-            return ParserResult::Success(period_entry);
-          }
-        } else { period.print(); return period; }
-      } else { program_body.print(); return program_body; }
-    } else { program_header.print(); return program_header; }
-    
+
+    return Ok(identifier);
   }
-  
-  pub fn program_header(&mut self) -> ParserResult {
-    // tok_entry is a borrowed value so it will not be able to be moved
-    let program_kw = self.parse_tok(tokens::program_kw::ProgramKW::start());
-    if let ParserResult::Success(..) = program_kw {
-    
-      let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-      if let ParserResult::Success(identifier_entry) = identifier {
-        let is_kw = self.parse_tok(tokens::is_kw::IsKW::start());
-        if let ParserResult::Success(tok_entry) = &is_kw {
-          return ParserResult::Success(identifier_entry);
-        } else {
-          is_kw.print();
-          return is_kw;
-        }
-        
-      } else { identifier.print(); return identifier; }
-    } else { program_kw.print(); return program_kw; }
+
+  pub fn program_body(&mut self, start_line: u32) -> ParserResult<(Vec<Declaration>, Vec<Statement>)> {
+
+    let declarations = self.top_level_declarations(None);
+
+    self.body_begin()?;
+
+    // read in statements w/ semicolon terminator until the 'end'
+    let statements = self.statements(&[Token::EndKW]);
+
+    self.block_end(Token::ProgramKW, start_line);
+
+    return Ok((declarations, statements));
   }
-  
-  pub fn program_body(&mut self, builder: &mut LLVMBuilderRef) -> ParserResult {
-    
-    // create a new symbol table for this scope
-    self.symbol_table_chain.push(HashMap::new());
-    
-    // while next token is in First(declaration), read in a declaration w/ semicolon terminator 
-    loop {
-      if let Some(tok_entry) = self.lexer.peek() {
 
-        match &tok_entry.tok_type {
-          // these tokens are in First(declaration). Parse the declaration and a terminating semicolon
-          Token::GlobalKW(_) | Token::ProcedureKW(_) | Token::VariableKW(_) | Token::TypeKW(_) => {
-            let declaration = self.declaration(builder);
-            if let ParserResult::ErrUnexpectedEnd | ParserResult::ErrUnexpectedTok{..} | ParserResult::Error{..} = &declaration {
-              declaration.print();
-            }
-            
-            self.resync();
-          },
-          _ => break
+  // parse the declarations before the body of the program, recording each item. With `len`, parsing stops after that
+  // many tokens have been read
+  fn top_level_declarations(&mut self, len: Option<usize>) -> Vec<Declaration> {
+    let mut declarations = vec![];
+
+    loop {
+      if let Some(len) = len {
+        if self.read >= len {
+          break;
         }
-      } else {
+      }
+
+      let start = self.read;
+      let count = declarations.len();
+      let errors = self.diagnostics.list.len();
+
+      if !self.declaration_item(&mut declarations) {
         break;
       }
+
+      let position = self.position();
+      let ends_with_error = self.last_error == Some(position);
+
+      self.items.push(Item {
+        tokens: start..self.read,
+        declarations: declarations.len() - count,
+        diagnostics: errors..self.diagnostics.list.len(),
+        ends_with_error
+      });
+    }
+
+    return declarations;
+  }
+
+  // reparse the declarations in the first `len` tokens (the tokens after them are only looked at), where the token
+  // before them is on `prev_line`. None if the declarations don't end exactly after `len` tokens
+  pub fn reparse_declarations(mut self, len: usize, prev_line: u32) -> Option<(Vec<Declaration>, Vec<Item>, Diagnostics)> {
+    self.prev_line = prev_line;
+
+    let declarations = self.top_level_declarations(Some(len));
+
+    if self.read != len {
+      return None;
+    }
+
+    return Some((declarations, self.items, self.diagnostics));
+  }
+
+  // read in declarations w/ semicolon terminator until the 'begin' of the body
+  pub fn declarations(&mut self) -> Vec<Declaration> {
+
+    let mut declarations = vec![];
+
+    while self.declaration_item(&mut declarations) {}
+
+    return declarations;
+  }
+
+  // read the next declaration, returning false if the body has started instead
+  fn declaration_item(&mut self, declarations: &mut Vec<Declaration>) -> bool {
+    let tok_type = match self.lexer.peek() {
+      Some(tok_entry) => tok_entry.tok_type,
+      None => return false
+    };
+
+    // the body has started (statements mean its 'begin' is missing). Two identifiers in a row can't start a
+    // statement, so they are probably a misspelled declaration
+    let is_body = match tok_type {
+      Token::BeginKW | Token::EndKW | Token::IfKW | Token::ForKW | Token::ReturnKW => true,
+      Token::Identifier => self.lexer.peek_second().map(|entry| entry.tok_type) != Some(Token::Identifier),
+      _ => false
+    };
+
+    if is_body {
+      return false;
     }
-    
-    let begin_kw = self.parse_tok(tokens::begin_kw::BeginKW::start());
-    if let ParserResult::Success(_) = begin_kw {
-      // while next token is in First(statement), read in statement w/ semicolon terminator
-      loop {
-        if let Some(tok_entry) = self.lexer.peek() {
-          match &tok_entry.tok_type {
-            Token::Identifier(_) | Token::IfKW(_) | Token::ForKW(_) | Token::ReturnKW(_) => {
-              // if able to parse a statement, parse a terminating semicolon
-              // program doesn't have a return type so neither program statements
-              let statement = self.statement(builder, &Type::None);
-              self.resync();
-            },
-            _ => break
+
+    self.start_node(SyntaxKind::Declaration);
+
+    if Parser::is_declaration_start(&tok_type) {
+      // a procedure that fails to parse is skipped up to its 'end procedure', since its body can contain semicolons
+      let is_procedure = tok_type == Token::ProcedureKW ||
+        (tok_type == Token::GlobalKW && self.lexer.peek_second().map(|entry| entry.tok_type) == Some(Token::ProcedureKW));
+
+      match self.declaration() {
+        Ok(declaration) => {
+          declarations.push(declaration);
+          self.terminator();
+        },
+        Err(err) => {
+          self.report(err);
+
+          if is_procedure {
+            self.skip_block(Token::ProcedureKW);
+          } else {
+            self.recover();
           }
-          
-        } else {
-          break;
         }
       }
-      
-      // parse the end kw
-      let end_kw = self.parse_tok(tokens::end_kw::EndKW::start());
-      if let ParserResult::Success(_) = end_kw {
-        let program_kw = self.parse_tok(tokens::program_kw::ProgramKW::start());
-        if let ParserResult::Success(tok_entry) = program_kw {
-        
-          // leave the current scope
-          let popped_table = self.symbol_table_chain.pop();
-          
-          // debugging - print the values in this scope
-          if let Some(table) = popped_table {
-            Parser::print_symbol_table(String::from("Program scope"), &table);
-          }
-          
-          return ParserResult::Success(tok_entry);
-        } else { program_kw.print(); return program_kw; }
-      } else { end_kw.print(); return end_kw; }
-    } else { begin_kw.print(); return begin_kw; }
+    } else {
+      // a token that can't start a declaration is deleted. If it is a misspelled 'procedure', so is the procedure's body
+      let err = self.unexpected("(global|procedure|variable|type|begin)");
+      let is_procedure = match &err {
+        Diagnostic::ErrUnexpectedTok{actual, ..} => suggest::keyword(actual, Token::ProcedureKW.get_example()).is_some(),
+        _ => false
+      };
+
+      self.report(err);
+      self.next_tok();
+
+      if is_procedure {
+        self.skip_block(Token::ProcedureKW);
+      } else {
+        self.recover();
+      }
+    }
+
+    self.finish_node();
+
+    return true;
+  }
+
+  pub fn is_declaration_start(tok: &Token) -> bool {
+    return matches!(tok, Token::GlobalKW | Token::ProcedureKW | Token::VariableKW | Token::TypeKW);
   }
-  
-  pub fn declaration(&mut self, builder: &mut LLVMBuilderRef) -> ParserResult {
-  
-    let scope = if let ParserResult::Success(..) = self.parse_tok(tokens::global_kw::GlobalKW::start()) {
+
+  pub fn declaration(&mut self) -> ParserResult<Declaration> {
+
+    let scope = if self.parse_tok(Token::GlobalKW).is_ok() {
       Scope::Global
     } else {
       Scope::Local
     };
-    
+
     if let Some(tok_entry) = self.lexer.peek() {
       match &tok_entry.tok_type {
-        Token::ProcedureKW(_tok) => { return self.procedure_declaration(builder, &scope); },
-        Token::VariableKW(_tok) => {return self.variable_declaration(builder, &scope); },
-        Token::TypeKW(_tok) => { return self.type_declaration(builder, &scope); },
-        _ => { return ParserResult::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from("(procedure|variable|type)"), actual: String::from(&tok_entry.chars[..])}; }
+        Token::ProcedureKW => { return Ok(Declaration::Procedure(self.node(SyntaxKind::ProcedureDeclaration, |slf| slf.procedure_declaration(scope))?)); },
+        Token::VariableKW => { return Ok(Declaration::Variable(self.node(SyntaxKind::VariableDeclaration, |slf| slf.variable_declaration(scope))?)); },
+        Token::TypeKW => { return Ok(Declaration::Type(self.node(SyntaxKind::TypeDeclaration, |slf| slf.type_declaration(scope))?)); },
+        _ => { return Err(Diagnostic::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from("(procedure|variable|type)"), actual: String::from(&tok_entry.chars[..]), suggestion: None}); }
       }
-      
+
     } else {
-      return ParserResult::ErrUnexpectedEnd;
+      return Err(Diagnostic::ErrUnexpectedEnd);
     }
   }
-  
-  pub fn procedure_declaration(&mut self, builder: &mut LLVMBuilderRef, scope: &Scope) -> ParserResult {
-  
-    let mut builder = unsafe {
-      core::LLVMCreateBuilder()
-    };
-  
-    let procedure_header = self.procedure_header(&mut builder, scope);
-    if let ParserResult::Success(return_type) = procedure_header {
-      let procedure_body = self.procedure_body(&mut builder, scope, &return_type.r#type);
-      if let ParserResult::Success(_) = procedure_body {
-        return procedure_body;
-      } else { procedure_body.print(); return procedure_body;}
-    } else { procedure_header.print(); return procedure_header;}
+
+  pub fn procedure_declaration(&mut self, scope: Scope) -> ParserResult<ProcedureDeclaration> {
+
+    let mut procedure = self.node(SyntaxKind::ProcedureHeader, |slf| slf.procedure_header(scope))?;
+    self.node(SyntaxKind::ProcedureBody, |slf| slf.procedure_body(&mut procedure))?;
+
+    return Ok(procedure);
   }
-  
-  pub fn procedure_header(&mut self, builder: &mut LLVMBuilderRef, scope: &Scope) -> ParserResult {
-    let procedure_kw = self.parse_tok(tokens::procedure_kw::ProcedureKW::start());
-    if let ParserResult::Success(_) = procedure_kw {
-    
-      let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-      if let ParserResult::Success(mut procedure_id) = identifier {
-        let colon = self.parse_tok(tokens::colon::Colon::start());
-        if let ParserResult::Success(_) = colon {
-          let type_mark = self.type_mark();
-          if let ParserResult::Success(mut result_type) = type_mark {
-            let l_paren = self.parse_tok(tokens::parens::LParen::start());
-            if let ParserResult::Success(_) = l_paren {
-            
-              let return_type = Parser::get_type(&result_type);
-              result_type.r#type = return_type.clone();
-              
-              let mut procedure_type = Type::Procedure(vec![], Box::new(return_type));
-              
-              // create a new symbol table for the procedure scope
-              self.symbol_table_chain.push(HashMap::new());
-            
-              // read optional parameter list
-              let next_tok = self.lexer.peek();
-              if let Some(tok_entry) = next_tok {
-                if let Token::VariableKW(_) = &tok_entry.tok_type {
-                  self.parameter_list(builder, scope, &mut procedure_type);
-                }
-              }
-              
-              let r_paren = self.parse_tok(tokens::parens::RParen::start());
-              if let ParserResult::Success(_) = r_paren {
-            
-                // set the type of token to procedure
-                procedure_id.r#type = procedure_type;
-                
-                // build the return type
-                let mut ret_type;
-                let mut params_type;
-                
-                if let Type::Procedure(params, ret) = &procedure_id.r#type {
-                  ret_type = get_llvm_type(ret);
-                  
-                  let mut types = vec![];
-                  
-                  for param in params {
-                    types.push(get_llvm_type(param));
-                  }
-                  
-                  params_type = types.as_mut_ptr();
-                  
-                } else {
-                  return ParserResult::ErrInvalidType{line_num: procedure_id.line_num,
-                                                      expected: vec![Type::Procedure(vec![], Box::new(Type::None))],
-                                                      actual: procedure_id.r#type};
-                };
-                
-                
-                // build the llvm function
-                procedure_id.value_ref = unsafe {
-                  // build the function type
-                  let func_type = core::LLVMFunctionType(ret_type, params_type, 0, 0);
-                
-                  // add the function to the module
-                  let func_name = c_str(&procedure_id.chars[..]);
-                  let f = core::LLVMAddFunction(self.llvm_module, func_name, func_type);
-                  
-                  // set up basic block and builder for this function
-                  let block = core::LLVMAppendBasicBlock(f, c_str("block"));
-                  core::LLVMPositionBuilderAtEnd(*builder, block);
-                  
-                  f
-                };
-                
-                // Note: Using Rc struct gives immutable multiple ownership
-                // this means that the symbols in the table are immutable
-                // may want to mutate to change the type, chars, tok_type of a symbol
-                // but I believe this language does not require this since this information is given completely at declaration
-                
-                // if header is successful, save procedure_id to the symbol table
-                let procedure_symbol = Rc::new(procedure_id);
-                
-                // include procedure_id in this new symbol table (allow recursive calls)
-                let procedure_scope = Scope::Local;
-                self.add_symbol(builder, &procedure_scope, Rc::clone(&procedure_symbol));
-                
-                // add procedure_id to the containing scope (pop procedure scope off then push back)
-                if let Scope::Local = &scope {
-                  if let Some(procedure_table) = self.symbol_table_chain.pop() {
-                    self.add_symbol(builder, &scope, Rc::clone(&procedure_symbol));
-                    self.symbol_table_chain.push(procedure_table);
-                  }
-                } else if let Scope::Global = &scope {
-                  self.add_symbol(builder, &scope, Rc::clone(&procedure_symbol));
-                }
-              
-                return ParserResult::Success(result_type);
-                
-              } else { r_paren.print(); return r_paren; }
-            } else { l_paren.print(); return l_paren; }
-          } else { type_mark.print(); return type_mark; }
-        } else { colon.print(); return colon; }
-      } else { identifier.print(); return identifier; }
-    } else { procedure_kw.print(); return procedure_kw; }
+
+  // parse the header into a procedure with an empty body
+  pub fn procedure_header(&mut self, scope: Scope) -> ParserResult<ProcedureDeclaration> {
+    let procedure_kw_entry = self.parse_tok(Token::ProcedureKW)?;
+    let name = self.identifier()?;
+    self.parse_tok(Token::Colon)?;
+    let return_type = self.type_mark()?;
+    self.parse_tok(Token::LParen)?;
+
+    // read optional parameter list
+    let mut params = vec![];
+    if let Some(tok_entry) = self.lexer.peek() {
+      if let Token::VariableKW = &tok_entry.tok_type {
+        self.node(SyntaxKind::ParameterList, |slf| slf.parameter_list(&mut params))?;
+      }
+    }
+
+    self.parse_tok(Token::RParen)?;
+
+    return Ok(ProcedureDeclaration {
+      scope,
+      line_num: procedure_kw_entry.line_num,
+      name,
+      return_type,
+      params,
+      declarations: vec![],
+      statements: vec![],
+      end_line: procedure_kw_entry.line_num
+    });
   }
-  
-  pub fn type_mark(&mut self) -> ParserResult {
-  
+
+  pub fn type_mark(&mut self) -> ParserResult<Type> {
+
     let peek_tok = self.lexer.peek();
     if let Some(tok_entry) = peek_tok {
       match tok_entry.tok_type {
-        Token::IntegerKW(_) => { 
-          if let Some(entry) = self.lexer.next() {
-            return ParserResult::Success(entry); 
-          } else {
-            return ParserResult::ErrUnexpectedEnd;
-          }
-        },
-        Token::FloatKW(_) => {
-          if let Some(entry) = self.lexer.next() {
-            return ParserResult::Success(entry);
-          } else {
-            return ParserResult::ErrUnexpectedEnd;
-          }
-        },
-        Token::StringKW(_) => { 
-          if let Some(entry) = self.lexer.next() {
-            return ParserResult::Success(entry);
+        Token::IntegerKW | Token::FloatKW | Token::StringKW | Token::BoolKW | Token::Identifier => {
+          if let Some(entry) = self.next_tok() {
+            return Ok(Parser::get_type(&entry));
           } else {
-            return ParserResult::ErrUnexpectedEnd;
+            return Err(Diagnostic::ErrUnexpectedEnd);
           }
         },
-        Token::BoolKW(_) => {
-          if let Some(entry) = self.lexer.next() {
-            return ParserResult::Success(entry);
-          } else {
-            return ParserResult::ErrUnexpectedEnd;
-          }
-        },
-        Token::Identifier(_) => {
-          if let Some(entry) = self.lexer.next() {
-            return ParserResult::Success(entry);
-          } else {
-            return ParserResult::ErrUnexpectedEnd;
-          }
-        },
-        Token::EnumKW(_) => {
-          self.lexer.next();
-          
-          let l_brace = self.parse_tok(tokens::braces::LBrace::start());
-          if let ParserResult::Success(_) = l_brace {
-            let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-            if let ParserResult::Success(_) = identifier {
-            
-              loop {
-                // optionally parse additional identifiers (delimited by comma)
-                if let ParserResult::Success(_) = self.parse_tok(tokens::comma::Comma::start()) {
-                  self.parse_tok(tokens::identifier::Identifier::start());
-                } else {
-                  break;
-                }
-              }
-              
-              let r_brace = self.parse_tok(tokens::braces::RBrace::start());
-              if let ParserResult::Success(_) = r_brace {
-                return r_brace;
-              } else {
-                r_brace.print();
-                return r_brace;
-              }
+        Token::EnumKW => {
+          self.next_tok();
+
+          self.parse_tok(Token::LBrace)?;
+          self.parse_tok(Token::Identifier)?;
+
+          loop {
+            // optionally parse additional identifiers (delimited by comma)
+            if self.parse_tok(Token::Comma).is_ok() {
+              self.parse_tok(Token::Identifier)?;
             } else {
-              identifier.print();
-              return identifier;
+              break;
             }
-          } else {
-            l_brace.print();
-            return l_brace;
           }
-          
+
+          self.parse_tok(Token::RBrace)?;
+
+          return Ok(Type::Enum);
         },
-        _ => { return ParserResult::ErrUnexpectedTok{line_num: tok_entry.line_num, expected: String::from("<some_type_kw>"), actual: String::from(&tok_entry.chars[..])}; }
+        _ => { return Err(Diagnostic::ErrUnexpectedTok{line_num: tok_entry.line_num, expected: String::from("<some_type_kw>"), actual: String::from(&tok_entry.chars[..]), suggestion: None}); }
       }
-    } else { return ParserResult::ErrUnexpectedEnd; }
+    } else { return Err(Diagnostic::ErrUnexpectedEnd); }
   }
-  
-  pub fn parameter_list(&mut self, builder: &mut LLVMBuilderRef, scope: &Scope, procedure: &mut Type) -> ParserResult {
-    
-    let parameter = self.parameter(builder, scope);
-    if let ParserResult::Success(param_entry) = &parameter {
-    
-      // add this parameter type to the procedure type
-      if let Type::Procedure(param_types, _) = procedure {
-        param_types.push(Box::new(Parser::get_type(&param_entry)));
-      } else {
-        println!("Hmmm. non-Type::Procedure type passed into parameter_list");
-      }
-      
-      // optionally parse another parameter list (delimited by comma)
-      let comma = self.parse_tok(tokens::comma::Comma::start());
-      if let ParserResult::Success(_) = comma {
-        // call recursively to parse the rest of the list
-        return self.parameter_list(builder, scope, procedure);
-      } else {
-        return parameter;
-      }
-      
-    } else {
-      parameter.print();
-      return parameter;
+
+  // parameters are collected into `params` (in order)
+  pub fn parameter_list(&mut self, params: &mut Vec<Variable>) -> ParserResult<()> {
+
+    let parameter = self.node(SyntaxKind::Parameter, Parser::parameter)?;
+    params.push(parameter);
+
+    // optionally parse another parameter list (delimited by comma)
+    if self.parse_tok(Token::Comma).is_ok() {
+      // call recursively to parse the rest of the list
+      return self.parameter_list(params);
     }
-    
+
+    return Ok(());
   }
-  
-  pub fn parameter(&mut self, builder: &mut LLVMBuilderRef, scope: &Scope) -> ParserResult {
-    return self.variable_declaration(builder, scope);
+
+  pub fn parameter(&mut self) -> ParserResult<Variable> {
+    return self.variable();
   }
-  
-  pub fn procedure_body(&mut self, builder: &mut LLVMBuilderRef, scope: &Scope, return_type: &Type) -> ParserResult {
-  
-    // TODO break this out to its own function
+
+  pub fn procedure_body(&mut self, procedure: &mut ProcedureDeclaration) -> ParserResult<()> {
+
     // parse an optional number of declarations delimited by semicolon
-    loop {
-      if let Some(tok_entry) = self.lexer.peek() {
+    procedure.declarations = self.declarations();
 
-        match &tok_entry.tok_type {
-          // these tokens are in First(declaration). Parse the declaration and a terminating semicolon
-          Token::GlobalKW(_) | Token::ProcedureKW(_) | Token::VariableKW(_) | Token::TypeKW(_) => {
-            let declaration = self.declaration(builder);
-            if let ParserResult::ErrUnexpectedEnd | ParserResult::ErrUnexpectedTok{..} | ParserResult::Error{..} = declaration {
-              declaration.print();
-            }
-            
-            self.resync();
-          },
-          _ => break
-        }
-      } else {
+    self.body_begin()?;
+
+    // parse an optional number of statements
+    procedure.statements = self.statements(&[Token::EndKW]);
+
+    procedure.end_line = self.block_end(Token::ProcedureKW, procedure.line_num);
+
+    return Ok(());
+  }
+
+  pub fn variable_declaration(&mut self, scope: Scope) -> ParserResult<VariableDeclaration> {
+    let variable = self.variable()?;
+
+    return Ok(VariableDeclaration { scope, variable });
+  }
+
+  // parse a variable (with its type)
+  pub fn variable(&mut self) -> ParserResult<Variable> {
+
+    self.parse_tok(Token::VariableKW)?;
+    let name = self.identifier()?;
+    self.parse_tok(Token::Colon)?;
+    let variable_type = self.type_mark()?;
+
+    // optionally parse a bound (making this variable an array)
+    if self.parse_tok(Token::LBracket).is_ok() {
+      let bound_entry = self.bound()?;
+      self.parse_tok(Token::RBracket)?;
+
+      let arr_size = bound_entry.chars.parse::<u32>().unwrap_or_default();
+
+      return Ok(Variable { name, r#type: Type::Array(Box::new(variable_type), arr_size) });
+    }
+
+    return Ok(Variable { name, r#type: variable_type });
+  }
+
+  pub fn bound(&mut self) -> ParserResult<TokenEntry> {
+
+    // optionally parse a dash (minus)
+    let _ = self.parse_tok(Token::Dash);
+
+    return self.parse_tok(Token::Number);
+
+  }
+
+  pub fn type_declaration(&mut self, scope: Scope) -> ParserResult<TypeDeclaration> {
+    self.parse_tok(Token::TypeKW)?;
+    let name = self.identifier()?;
+    self.parse_tok(Token::IsKW)?;
+    let resolve_type = self.type_mark()?;
+
+    return Ok(TypeDeclaration { scope, name, r#type: Type::Type(Box::new(resolve_type)) });
+  }
+
+  // parse statements (each terminated by a semicolon) until one of `terminators`. A declaration also ends
+  // the statements, since it means the block's 'end' is missing
+  // statements that fail to parse are reported and skipped, and tokens that can't start a statement are deleted
+  pub fn statements(&mut self, terminators: &[Token]) -> Vec<Statement> {
+
+    let mut statements = vec![];
+
+    while let Some(tok_type) = self.lexer.peek().map(|tok_entry| tok_entry.tok_type) {
+      if terminators.contains(&tok_type) || Parser::is_declaration_start(&tok_type) {
         break;
       }
-    }
-    
-    let begin_kw = self.parse_tok(tokens::begin_kw::BeginKW::start());
-    if let ParserResult::Success(_) = begin_kw {
-      // parse an optional number of statements
-      // TODO unused, but use this variable to enforce that a return statement is found
-      let does_return = false;
-      loop {
-        if let Some(tok_entry) = self.lexer.peek() {
-          match &tok_entry.tok_type {
-            Token::Identifier(_) | Token::IfKW(_) | Token::ForKW(_) | Token::ReturnKW(_) => {
-              let statement = self.statement(builder, return_type);
-              self.resync();
-            },
-            _ => break
+
+      self.start_node(SyntaxKind::Statement);
+
+      if Parser::is_statement_start(&tok_type) {
+        match self.statement() {
+          Ok(statement) => {
+            statements.push(statement);
+            self.terminator();
+          },
+          Err(err) => {
+            self.report(err);
+
+            // the body of a block can contain semicolons, so skip to the end of the block instead
+            match tok_type {
+              Token::IfKW | Token::ForKW => self.skip_block(tok_type),
+              _ => self.recover()
+            }
           }
-          
-        } else {
-          break;
         }
+      } else {
+        let err = self.unexpected("(<identifier>|if|for|return)");
+        self.report(err);
+        self.next_tok();
+        self.recover();
       }
-      
-      let end_kw = self.parse_tok(tokens::end_kw::EndKW::start());
-      if let ParserResult::Success(_) = end_kw {
-        let procedure_kw = self.parse_tok(tokens::procedure_kw::ProcedureKW::start());
-        if let ParserResult::Success(_) = procedure_kw {
-        
-          // leave the current scope
-          let popped_scope = self.symbol_table_chain.pop();
-          
-          if let Some(table) = popped_scope {
-            Parser::print_symbol_table(String::from("Procedure scope"), &table);
-          }
-        
-          return procedure_kw;
-          
-        } else { return procedure_kw; }
-      } else { return end_kw; }
-    } else { return begin_kw; }
-  }
-  
-  pub fn variable_declaration(&mut self, builder: &mut LLVMBuilderRef, scope: &Scope) -> ParserResult {
-    
-    let variable_kw = self.parse_tok(tokens::variable_kw::VariableKW::start());
-    if let ParserResult::Success(_) = variable_kw {
-      let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-      if let ParserResult::Success(mut variable_id) = identifier {
-        let colon = self.parse_tok(tokens::colon::Colon::start());
-        if let ParserResult::Success(_) = colon {
-          let type_mark = self.type_mark();
-          if let ParserResult::Success(mut variable_type) = type_mark {
-            
-            variable_type.r#type = Parser::get_type(&variable_type);
-            
-            variable_id.value_ref = unsafe {core::LLVMBuildAlloca(*builder, get_llvm_type(&variable_type.r#type), c_str(&variable_id.chars[..]))};
-            
-            // optionally parse a bound (making this variable an array)
-            let l_bracket = self.parse_tok(tokens::brackets::LBracket::start());
-            if let ParserResult::Success(_) = l_bracket {
-              let bound = self.bound();
-              if let ParserResult::Success(bound_entry) = bound {
-                let r_bracket = self.parse_tok(tokens::brackets::RBracket::start());
-                if let ParserResult::Success(_) = r_bracket {
-                
-                  // update the token type based on the type_mark
-                  let arr_type = Parser::get_type(&variable_type);
-                  let arr_size = match bound_entry.chars.parse::<u32>() {
-                    Ok(val) => val,
-                    Err(_) => 0
-                  };
-                  variable_id.r#type = Type::Array(Box::new(arr_type), arr_size);
-                  
-                  // if successful, add the variable to the symbol table
-                  self.add_symbol(builder, scope, Rc::new(variable_id));
-                
-                  return ParserResult::Success(variable_type);
-                } else {
-                  return r_bracket;
-                }
-              } else {
-                return bound;
-              }
-            }
-            
-            // update the token type baed on the type_mark
-            variable_id.r#type = Parser::get_type(&variable_type);
-            
-            // if successful without bounds, also add to symbol table
-            self.add_symbol(builder, scope, Rc::new(variable_id));
-            
-            return ParserResult::Success(variable_type);
-            
-          } else { return type_mark; }
-        } else { return colon; }
-      } else { return identifier; }
-    } else { return variable_kw; }
-  }
-  
-  pub fn bound(&mut self) -> ParserResult {
-    
-    // optionally parse a dash (minus)
-    self.parse_tok(tokens::dash::Dash::start());
-    
-    return self.parse_tok(tokens::number::Number::start());
-    
+
+      self.finish_node();
+    }
+
+    return statements;
   }
-  
-  pub fn type_declaration(&mut self, builder: &mut LLVMBuilderRef, scope: &Scope) -> ParserResult {
-    let type_kw = self.parse_tok(tokens::type_kw::TypeKW::start());
-    if let ParserResult::Success(_) = type_kw {
-      let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-      if let ParserResult::Success(mut type_id) = identifier {
-        let is_kw = self.parse_tok(tokens::is_kw::IsKW::start());
-        if let ParserResult::Success(_) = is_kw {
-          let type_mark = self.type_mark();
-          if let ParserResult::Success(type_entry) = &type_mark {
-          
-            // change the token entry type
-            type_id.r#type = Type::Type(Box::new(Parser::get_type(&type_entry)));
-            
-            self.add_symbol(builder, scope, Rc::new(type_id));
-          
-            return type_mark;
-          } else { return type_mark; }
-        } else { return is_kw; }
-      } else { return identifier; }
-    } else { return type_kw; }
-    
+
+  pub fn is_statement_start(tok: &Token) -> bool {
+    return matches!(tok, Token::Identifier | Token::IfKW | Token::ForKW | Token::ReturnKW);
   }
-  
-  pub fn statement(&mut self, builder: &mut LLVMBuilderRef, return_type: &Type) -> ParserResult {
+
+  pub fn statement(&mut self) -> ParserResult<Statement> {
+
     let peek_tok = self.lexer.peek();
     if let Some(tok_entry) = peek_tok {
-      let result = match &tok_entry.tok_type {
-        Token::Identifier(_) => self.assignment_statement(builder),
-        Token::IfKW(_) => self.if_statement(builder, return_type),
-        Token::ForKW(_) => self.loop_statement(builder, return_type),
-        Token::ReturnKW(_) => self.return_statement(builder, return_type),
-        _ => { return ParserResult::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from("(<identifier>|if|for|return)"), actual: String::from(&tok_entry.chars[..])} }
+      return match &tok_entry.tok_type {
+        Token::Identifier => Ok(Statement::Assignment(self.node(SyntaxKind::AssignmentStatement, Parser::assignment_statement)?)),
+        Token::IfKW => Ok(Statement::If(self.node(SyntaxKind::IfStatement, Parser::if_statement)?)),
+        Token::ForKW => Ok(Statement::Loop(self.node(SyntaxKind::LoopStatement, Parser::loop_statement)?)),
+        Token::ReturnKW => Ok(Statement::Return(self.node(SyntaxKind::ReturnStatement, Parser::return_statement)?)),
+        _ => Err(Diagnostic::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from("(<identifier>|if|for|return)"), actual: String::from(&tok_entry.chars[..]), suggestion: None})
       };
-      
-      if let ParserResult::Success(_) = result {
-        return result;
-      } else {
-        result.print();
-        return result;
-      }
-      
-    } else { return ParserResult::ErrUnexpectedEnd; }
-  }
-  
-  pub fn procedure_call_w_identifier(&mut self, builder: &mut LLVMBuilderRef, identifier: ParserResult, resolve_type: &Type) -> ParserResult {
-    if let ParserResult::Success(mut procedure_id) = identifier {
-    
-      let l_paren = self.parse_tok(tokens::parens::LParen::start());
-      if let ParserResult::Success(_) = l_paren {
-        
-        // look up the procedure (token_entry)
-        let procedure = match self.get_symbol(&procedure_id.chars) {
-          Some(val) => val,
-          None => return ParserResult::ErrSymbolNotFound{name: String::from(&procedure_id.chars[..]), line_num: procedure_id.line_num}
-        };
-        
-        // check that the retrieved symbol is a procedure
-        let (procedure_params, procedure_ret) = match &procedure.r#type {
-          Type::Procedure(params, ret) => (params.clone(), *ret.clone()),
-          _ => {
-            return ParserResult::ErrInvalidType{line_num: procedure_id.line_num,
-                                                    expected: vec![Type::Procedure(vec![], Box::new(Type::None))],
-                                                    actual: procedure.r#type.clone()};
-            
-          }
-        };
-        
-        let llvm_procedure = procedure.value_ref.clone();
-        
-        // parse optional argument list (includes checking types)
-        let mut arg_list = vec![];
-        self.argument_list(builder, &mut arg_list, procedure_params.iter());
-        
-        let r_paren = self.parse_tok(tokens::parens::RParen::start());
-        if let ParserResult::Success(entry) = &r_paren {
-          procedure_id.r#type = procedure_ret;
-          
-          // llvm function call
-          let res = unsafe {
-            let result = core::LLVMBuildCall(*builder, llvm_procedure, arg_list.as_mut_ptr(), arg_list.len() as u32, null_str());
-            
-            result
-          };
-          
-          procedure_id.value_ref = res;
-          
-          return ParserResult::Success(procedure_id);
-        } else { return r_paren; }
-      } else { return l_paren; }
-    } else { return identifier; }
-  }
-  
-  // this is currently unused since procedure calls currently only occur ambiguously with names (in the factor parse rule)
-  pub fn procedure_call(&mut self, builder: &mut LLVMBuilderRef, resolve_type: &Type) -> ParserResult {
-  
-    let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-    return self.procedure_call_w_identifier(builder, identifier, resolve_type);
-    
+
+    } else { return Err(Diagnostic::ErrUnexpectedEnd); }
   }
-  
-  pub fn name_w_identifier(&mut self, builder: &mut LLVMBuilderRef, mut identifier: ParserResult) -> ParserResult {
-    if let ParserResult::Success(id_entry) = &mut identifier {
-    
-      // make sure the identifier exists
-      let symbol = if let Some(value) = self.get_symbol(&id_entry.chars) {
-        value
-      } else {
-        return ParserResult::ErrSymbolNotFound{line_num: id_entry.line_num, name: String::from(&id_entry.chars[..])};
-      };
-      
-      // extract the type of the identifier
-      let val_type = symbol.r#type.clone();
-      
-      id_entry.r#type = val_type;
-    
-      // prepare a value to load into
-      let value = unsafe { core::LLVMBuildLoad(*builder, symbol.value_ref, c_str(&id_entry.chars[..])) };
-      id_entry.value_ref = value;
-    
-      // optionally parse square bracket
-      let peek_tok = self.lexer.peek();
-      if let Some(tok_entry) = peek_tok {
-        // if next up is an LBracket, commit to parsing this optional portion
-        if let Token::LBracket(_) = &tok_entry.tok_type {
-          // consume the l_bracket
-          self.lexer.next();
-          
-          let arr_idx_type = Type::Integer;
-          let expression = self.expression(builder, &arr_idx_type);
-          if let ParserResult::Success(_) = expression {
-            let r_bracket = self.parse_tok(tokens::brackets::RBracket::start());
-            if let ParserResult::Success(_) = r_bracket {
-            
-              // if indexing, check that this is actually an array
-              if let Type::Array(..) = id_entry.r#type {} else {
-                return ParserResult::ErrInvalidType{line_num: id_entry.line_num,
-                                                    expected: vec![Type::Array(Box::new(Type::None), 0)],
-                                                    actual: id_entry.r#type.clone()};
-              }
-              
-              return identifier;
-            } else { return r_bracket; }
-          } else { return expression; }
-        }
+
+  // the identifier has already been parsed (it was needed to tell a call from a name)
+  pub fn procedure_call_w_identifier(&mut self, procedure: Identifier) -> ParserResult<Expr> {
+
+    self.parse_tok(Token::LParen)?;
+
+    // parse optional argument list
+    let mut args = vec![];
+    if let Some(tok_entry) = self.lexer.peek() {
+      if let Token::RParen = &tok_entry.tok_type {} else {
+        self.node(SyntaxKind::ArgumentList, |slf| slf.argument_list(&mut args))?;
       }
-      
-      return identifier;
-      
-    } else { return identifier; }
+    }
+
+    self.parse_tok(Token::RParen)?;
+
+    return Ok(Expr::Call { procedure, args });
+  }
+
+  // the identifier has already been parsed (it was needed to tell a call from a name)
+  pub fn name_w_identifier(&mut self, identifier: Identifier) -> ParserResult<Name> {
+
+    // optionally parse an index
+    if self.parse_tok(Token::LBracket).is_ok() {
+      let index = self.expression()?;
+      self.parse_tok(Token::RBracket)?;
+
+      return Ok(Name { identifier, index: Some(Box::new(index)) });
+    }
+
+    return Ok(Name { identifier, index: None });
   }
-  
-  pub fn name(&mut self, builder: &mut LLVMBuilderRef) -> ParserResult {
-    let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-    return self.name_w_identifier(builder, identifier);
+
+  pub fn name(&mut self) -> ParserResult<Name> {
+    let identifier = self.identifier()?;
+    return self.name_w_identifier(identifier);
   }
-  
-  pub fn term(&mut self, builder: &mut LLVMBuilderRef, resolve_type: &Type) -> ParserResult {
-  
+
+  pub fn term(&mut self) -> ParserResult<Expr> {
+
     // define function for factored parse rule
-    fn _term<'a>(slf: &mut Parser<'a>, builder: &mut LLVMBuilderRef, resolve_type: &Type, mut left: TokenEntry) -> ParserResult {
+    fn _term(slf: &mut Parser, left: Expr) -> ParserResult<Expr> {
       // accept either a '*' or '/'
-      let asterisk = slf.parse_tok(tokens::asterisk::Asterisk::start());
-      if let ParserResult::Success(_) = asterisk {
-        let factor = slf.factor(builder, resolve_type);
-        if let ParserResult::Success(mut factor_entry) = factor {
-        
-          // ensure that left and factor are integer or float (both compatible with float)
-          let float_type = Type::Float;
-          if !Parser::is_compatible(&float_type, &left.r#type) {
-            return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                expected: vec![float_type, Type::Integer],
-                                                actual: left.r#type};
-          } else if !Parser::is_compatible(&float_type, &factor_entry.r#type) {
-            return ParserResult::ErrInvalidType{line_num: factor_entry.line_num,
-                                                expected: vec![float_type, Type::Integer],
-                                                actual: factor_entry.r#type};
-          } else {
-            // coerce left and factor into float type
-            if let Ok(val) = Parser::coerce(builder, &left.r#type, &Type::Float, &mut left.value_ref) {
-              left.value_ref = val;
-            }
-            
-            if let Ok(val) = Parser::coerce(builder, &mut factor_entry.r#type, &Type::Float, &mut factor_entry.value_ref) {
-              factor_entry.value_ref = val;
-            }
-            
-            // fold factor_entry into left
-            let mul_result = unsafe {
-              core::LLVMBuildFMul(*builder, left.value_ref, factor_entry.value_ref, null_str())
-            };
-            
-            // upgrade the accumulated type to a float
-            left.value_ref = mul_result;
-            left.r#type = float_type;
-            left.line_num = factor_entry.line_num;
-          }
-        
-          return _term(slf, builder, resolve_type, left);
-        } else { return factor; }
-      }
-      
-      let slash = slf.parse_tok(tokens::slash::Slash::start());
-      if let ParserResult::Success(_) = slash {
-        let factor = slf.factor(builder, resolve_type);
-        if let ParserResult::Success(mut factor_entry) = factor {
-        
-          // ensure that left and factor_entry types are compatible here
-          let float_type = Type::Float;
-          if !Parser::is_compatible(&float_type, &factor_entry.r#type) {
-            return ParserResult::ErrInvalidType{line_num: factor_entry.line_num,
-                                                expected: vec![float_type, Type::Integer],
-                                                actual: factor_entry.r#type};
-          } else if !Parser::is_compatible(&float_type, &left.r#type) {
-            return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                expected: vec![float_type, Type::Integer],
-                                                actual: left.r#type};
-          } else {
-            // coerce left and factor into floats
-            if let Ok(val) = Parser::coerce(builder, &left.r#type, &Type::Float, &mut left.value_ref) {
-              left.value_ref = val;
-            }
-            
-            if let Ok(val) = Parser::coerce(builder, &mut factor_entry.r#type, &Type::Float, &mut factor_entry.value_ref) {
-              factor_entry.value_ref = val;
-            }
-          
-            // fold the factor_entry value into left
-            let div_result = unsafe {
-              core::LLVMBuildFDiv(*builder, left.value_ref, factor_entry.value_ref, null_str())
-            };
-            
-            // upgrade the type to a float
-            left.value_ref = div_result;
-            left.r#type = float_type;
-            left.line_num = factor_entry.line_num;
-          }
-        
-          return _term(slf, builder, resolve_type, left);
-        } else { return factor; }
-      }
-      
-      // allow _term to parse nothing
-      // in this case, just return the unmodified lhs
-      return ParserResult::Success(left);
-      
+      let op = if slf.parse_tok(Token::Asterisk).is_ok() {
+        BinaryOp::Mul
+      } else if slf.parse_tok(Token::Slash).is_ok() {
+        BinaryOp::Div
+      } else {
+        // allow _term to parse nothing
+        // in this case, just return the unmodified lhs
+        return Ok(left);
+      };
+
+      let factor = slf.factor()?;
+
+      // fold factor into left
+      return _term(slf, Expr::Binary { op, left: Box::new(left), right: Box::new(factor) });
     }
-  
+
     // read bottomed-out factor rule
-    let factor = self.factor(builder, resolve_type);
-    if let ParserResult::Success(left) = factor {
-      return _term(self, builder, resolve_type, left);
-    } else { return factor; }
-    
+    let factor = self.factor()?;
+    return _term(self, factor);
+
   }
-  
-  pub fn relation(&mut self, builder: &mut LLVMBuilderRef, resolve_type: &Type) -> ParserResult {
-    fn _relation<'a>(slf: &mut Parser<'a>, builder: &mut LLVMBuilderRef, resolve_type: &Type, mut left: TokenEntry) -> ParserResult {
-    
-      let int_type = Type::Integer;
-      let string_type = Type::String;
-    
-      let peek_tok = slf.lexer.peek();
-      if let Some(tok_entry) = peek_tok {
+
+  pub fn relation(&mut self) -> ParserResult<Expr> {
+    fn _relation(slf: &mut Parser, left: Expr) -> ParserResult<Expr> {
+
+      let op = if let Some(tok_entry) = slf.lexer.peek() {
         match &tok_entry.tok_type {
-          Token::LT(_) => {
-            let lt = slf.parse_tok(tokens::lt::LT::start());
-            if let ParserResult::Success(lt_entry) = lt {
-              let term = slf.term(builder, resolve_type);
-              if let ParserResult::Success(term_entry) = term {
-              
-                if !Parser::is_compatible(&int_type, &left.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                      expected: vec![int_type, Type::Float, Type::Bool],
-                                                      actual: left.r#type};
-                }
-                
-                // make sure that left is compatible with term_entry
-                if !Parser::is_compatible(&left.r#type, &term_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: term_entry.line_num,
-                                                      expected: vec![left.r#type],
-                                                      actual: term_entry.r#type};
-                }
-                
-                // fold the term value into left
-                // this will turn left into a bool type
-                left.r#type = Type::Bool;
-                left.line_num = term_entry.line_num;
-              
-                return _relation(slf, builder, resolve_type, left);
-              } else { return term; }
-            } else { return lt; }
-          },
-          Token::GTE(_) => {
-            let gte = slf.parse_tok(tokens::gte::GTE::start());
-            if let ParserResult::Success(gte_entry) = gte {
-              let term = slf.term(builder, resolve_type);
-              if let ParserResult::Success(term_entry) = term {
-              
-                // check that left and term_entry have compatible types
-                if !Parser::is_compatible(&int_type, &left.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                      expected: vec![int_type, Type::Float, Type::Bool],
-                                                      actual: left.r#type};
-                }
-                
-                if !Parser::is_compatible(&left.r#type, &term_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: term_entry.line_num,
-                                                      expected: vec![left.r#type],
-                                                      actual: term_entry.r#type};
-                }
-                
-                // if compatible, fold term_entry into left
-                // which makes it a bool
-                left.r#type = Type::Bool;
-                left.line_num = term_entry.line_num;
-              
-                return _relation(slf, builder, resolve_type, left);
-              } else { return term; }
-            } else { return gte; }
-          },
-          Token::LTE(_) => {
-            let lte = slf.parse_tok(tokens::lte::LTE::start());
-            if let ParserResult::Success(lte_entry) = lte {
-              let term = slf.term(builder, resolve_type);
-              if let ParserResult::Success(term_entry) = term {
-              
-                // check that left and term_entry have compatible types
-                if !Parser::is_compatible(&int_type, &left.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                      expected: vec![int_type, Type::Float, Type::Bool],
-                                                      actual: left.r#type};
-                }
-                
-                if !Parser::is_compatible(&left.r#type, &term_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: term_entry.line_num,
-                                                      expected: vec![left.r#type],
-                                                      actual: term_entry.r#type};
-                }
-                
-                // fold term_entry into left
-                // which converts the type of left to bool
-                left.r#type = Type::Bool;
-                left.line_num = term_entry.line_num;
-              
-                return _relation(slf, builder, resolve_type, left);
-              } else { return term; }
-            } else { return lte; }
-          },
-          Token::GT(_) => {
-            let gt = slf.parse_tok(tokens::gt::GT::start());
-            if let ParserResult::Success(gt_entry) = gt {
-              let term = slf.term(builder, resolve_type);
-              if let ParserResult::Success(term_entry) = term {
-                // check that term_entry type is compatible with left (so that a comparison is possible)
-                if !Parser::is_compatible(&int_type, &left.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                      expected: vec![int_type, Type::Float, Type::Bool],
-                                                      actual: left.r#type};
-                }
-                
-                if !Parser::is_compatible(&left.r#type, &term_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: term_entry.line_num,
-                                                      expected: vec![left.r#type],
-                                                      actual: term_entry.r#type};
-                }
-                
-                // fold term entry into left
-                // which converts the type to bool
-                left.r#type = Type::Bool;
-                left.line_num = term_entry.line_num;
-              
-                return _relation(slf, builder, resolve_type, left);
-              } else { return term; }
-            } else { return gt; }
-          },
-          Token::EQ(_) => {
-            let eq = slf.parse_tok(tokens::eq::EQ::start());
-            if let ParserResult::Success(eq_entry) = eq {
-              let term = slf.term(builder, resolve_type);
-              if let ParserResult::Success(term_entry) = term {
-              
-                if !(Parser::is_compatible(&int_type, &left.r#type) || Parser::is_compatible(&string_type, &left.r#type)) {
-                  return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                      expected: vec![int_type, Type::Float, Type::Bool, string_type],
-                                                      actual: left.r#type};
-                }
-                
-                if !Parser::is_compatible(&left.r#type, &term_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: term_entry.line_num,
-                                                      expected: vec![left.r#type],
-                                                      actual: term_entry.r#type};
-                }
-                
-                // fold term_entry into left by equality op
-                // converts left into a bool
-                left.r#type = Type::Bool;
-                left.line_num = term_entry.line_num;
-              
-                return _relation(slf, builder, resolve_type, left);
-              } else { return term; }
-            } else { return eq; }
-          },
-          Token::NEQ(_) => {
-            let neq = slf.parse_tok(tokens::neq::NEQ::start());
-            if let ParserResult::Success(neq_entry) = neq {
-              let term = slf.term(builder, resolve_type);
-              if let ParserResult::Success(term_entry) = term {
-              
-                if !(Parser::is_compatible(&int_type, &left.r#type) || Parser::is_compatible(&string_type, &left.r#type)) {
-                  return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                      expected: vec![int_type, Type::Float, Type::Bool, string_type],
-                                                      actual: left.r#type};
-                }
-                
-                if !Parser::is_compatible(&left.r#type, &term_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: term_entry.line_num,
-                                                      expected: vec![left.r#type],
-                                                      actual: term_entry.r#type};
-                }
-                
-                // fold term_entry into left via neq op
-                // converts left to a bool
-                left.r#type = Type::Bool;
-                left.line_num = term_entry.line_num;
-              
-                return _relation(slf, builder, resolve_type, left);
-              } else { return term; }
-            } else { return neq; }
-          }
-          _ => { 
+          Token::LT => BinaryOp::LT,
+          Token::GTE => BinaryOp::GTE,
+          Token::LTE => BinaryOp::LTE,
+          Token::GT => BinaryOp::GT,
+          Token::EQ => BinaryOp::EQ,
+          Token::NEQ => BinaryOp::NEQ,
+          _ => {
             // allow nothing to be parsed (allow lambda production)
             // this is the base case for this recursive function
             // will keep recursing until next token is not a comparison (<, >, etc.)
-            return ParserResult::Success(left); 
+            return Ok(left);
           }
         }
       } else {
         // similar to catch-all, allow nothing to be parsed
-        return ParserResult::Success(left);
-      }
+        return Ok(left);
+      };
+
+      // consume the comparison
+      slf.next_tok();
+
+      let term = slf.term()?;
+
+      // fold the term into left
+      return _relation(slf, Expr::Binary { op, left: Box::new(left), right: Box::new(term) });
     }
-    
-    let term = self.term(builder, resolve_type);
-    if let ParserResult::Success(term_entry) = term {
-      let rel = _relation(self, builder, resolve_type, term_entry);
-      return rel;
-    } else { return term; }
+
+    let term = self.term()?;
+    return _relation(self, term);
   }
-  
-  pub fn arith_op(&mut self, builder: &mut LLVMBuilderRef, resolve_type: &Type) -> ParserResult {
-    fn _arith_op(slf: &mut Parser, builder: &mut LLVMBuilderRef, resolve_type: &Type, mut left: TokenEntry) -> ParserResult {
-    
-      let float_type = Type::Float;
-    
-      let peek_tok = slf.lexer.peek();
-      if let Some(tok_entry) = peek_tok {
-        match &tok_entry.tok_type {
-          Token::Plus(_) => {
-            let plus = slf.parse_tok(tokens::plus::Plus::start());
-            if let ParserResult::Success(_) = plus {
-              let relation = slf.relation(builder, resolve_type);
-              if let ParserResult::Success(mut relation_entry) = relation {
-              
-                if !Parser::is_compatible(&float_type, &left.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                      expected: vec![float_type, Type::Integer],
-                                                      actual: left.r#type};
-                }
-                
-                if !Parser::is_compatible(&float_type, &relation_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: relation_entry.line_num,
-                                                      expected: vec![float_type, Type::Integer],
-                                                      actual: relation_entry.r#type};
-                }
-                
-                // (idempotent) sync operand types
-                Parser::upcast(builder, &mut left, &mut relation_entry);
-                
-                // fold relation entry into left
-                left.value_ref = unsafe {
-                  if let &Type::Float = &left.r#type {
-                    core::LLVMBuildFAdd(*builder, left.value_ref, relation_entry.value_ref, null_str())
-                  } else if let &Type::Integer = &left.r#type {
-                    core::LLVMBuildAdd(*builder, left.value_ref, relation_entry.value_ref, null_str())
-                  } else {
-                    panic!("Invalid operand type for addition (not integer or float). The type-checker should have caught this?");
-                  }
-                };
-                
-                left.line_num = relation_entry.line_num;
-              
-                return _arith_op(slf, builder, resolve_type, left);
-              } else {
-                return relation;
-              }
-            } else {
-              return plus;
-            }
-          },
-          Token::Dash(_) => {
-            let dash = slf.parse_tok(tokens::dash::Dash::start());
-            if let ParserResult::Success(_) = dash {
-              let relation = slf.relation(builder, resolve_type);
-              if let ParserResult::Success(mut relation_entry) = relation {
-              
-                // check left and relation_entry have the correct types
-                if !Parser::is_compatible(&float_type, &left.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: left.line_num,
-                                                      expected: vec![float_type, Type::Integer],
-                                                      actual: left.r#type};
-                }
-                
-                if !Parser::is_compatible(&float_type, &relation_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: relation_entry.line_num,
-                                                      expected: vec![float_type, Type::Integer],
-                                                      actual: relation_entry.r#type};
-                }
-                
-                // sync operand types
-                Parser::upcast(builder, &mut left, &mut relation_entry);
-                
-                // fold relation entry into left by '-'
-                left.value_ref = unsafe {
-                  if let &Type::Float = &left.r#type {
-                    core::LLVMBuildFSub(*builder, left.value_ref, relation_entry.value_ref, null_str())
-                  } else if let &Type::Integer = &left.r#type {
-                    core::LLVMBuildSub(*builder, left.value_ref, relation_entry.value_ref, null_str())
-                  } else {
-                    panic!("Invalid operand type for subtraction (not integer or flaot). The type-checker should have caught this?");
-                  }
-                };
-                
-                
-                left.line_num = relation_entry.line_num;
-              
-                return _arith_op(slf, builder, resolve_type, left);
-              } else {
-                return relation;
-              }
-            } else {
-              return dash;
-            }
-          },
-          _ => {
-            // base case: allow nothing to be parsed if '+' and '-' not found
-            return ParserResult::Success(left);
-          }
-        }
+
+  pub fn arith_op(&mut self) -> ParserResult<Expr> {
+    fn _arith_op(slf: &mut Parser, left: Expr) -> ParserResult<Expr> {
+
+      let op = if slf.parse_tok(Token::Plus).is_ok() {
+        BinaryOp::Add
+      } else if slf.parse_tok(Token::Dash).is_ok() {
+        BinaryOp::Sub
       } else {
-        // base case
-        return ParserResult::Success(left);
-      }
+        // base case: allow nothing to be parsed if '+' and '-' not found
+        return Ok(left);
+      };
+
+      let relation = slf.relation()?;
+
+      // fold relation into left
+      return _arith_op(slf, Expr::Binary { op, left: Box::new(left), right: Box::new(relation) });
     }
-    
+
     // parse the initial relation where the recursion bottoms out
-    let relation = self.relation(builder, resolve_type);
-    if let ParserResult::Success(relation_entry) = relation {
-      return _arith_op(self, builder, &Type::Float, relation_entry);
-    } else {
-      return relation;
-    }
+    let relation = self.relation()?;
+    return _arith_op(self, relation);
   }
-  
-  pub fn expression(&mut self, builder: &mut LLVMBuilderRef, resolve_type: &Type) -> ParserResult {
-    fn _expression(slf: &mut Parser, builder: &mut LLVMBuilderRef, resolve_type: &Type, mut left: TokenEntry) -> ParserResult {
-      let peek_tok = slf.lexer.peek();
-      if let Some(tok_entry) = peek_tok {
-        match &tok_entry.tok_type {
-          Token::Ampersand(_) => {
-            let ampersand = slf.parse_tok(tokens::ampersand::Ampersand::start());
-            if let ParserResult::Success(_) = ampersand {
-              let arith_op = slf.arith_op(builder, resolve_type);
-              if let ParserResult::Success(arith_op_entry) = arith_op {
-              
-                // check that arith_op_entyr and left are the same type
-                if mem::discriminant(&arith_op_entry.r#type) != mem::discriminant(&left.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: arith_op_entry.line_num,
-                                                      expected: vec![left.r#type],
-                                                      actual: arith_op_entry.r#type};
-                }
-                
-                // check that type is either int or bool (for bitwise or logical op)
-                if let Type::Bool = &arith_op_entry.r#type {
-                  // perform logical operation (returns bool)
-                  
-                } else if let Type::Integer = &arith_op_entry.r#type {
-                  // perform bitwise operation (return integer)
-                  
-                } else {
-                  // invalid type
-                  return ParserResult::ErrInvalidType{line_num: arith_op_entry.line_num,
-                                                      expected: vec![Type::Bool, Type::Integer],
-                                                      actual: arith_op_entry.r#type};
-                }
-                
-                left.line_num = arith_op_entry.line_num;
-              
-                return _expression(slf, builder, resolve_type, left);
-              } else {
-                return arith_op;
-              }
-            } else {
-              return ampersand;
-            }
-          },
-          Token::Pipe(_) => {
-            let pipe = slf.parse_tok(tokens::pipe::Pipe::start());
-            if let ParserResult::Success(_) = pipe {
-              let arith_op = slf.arith_op(builder, resolve_type);
-              if let ParserResult::Success(arith_op_entry) = arith_op {
-              
-                // check that arith_op_entyr and left are the same type
-                if mem::discriminant(&arith_op_entry.r#type) != mem::discriminant(&left.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: arith_op_entry.line_num,
-                                                      expected: vec![left.r#type],
-                                                      actual: arith_op_entry.r#type};
-                }
-                
-                // check that type is either int or bool (for bitwise or logical op)
-                if let Type::Bool = &arith_op_entry.r#type {
-                  // perform logical operation (returns bool)
-                  
-                } else if let Type::Integer = &arith_op_entry.r#type {
-                  // perform bitwise operation (return integer)
-                  
-                } else {
-                  // invalid type
-                  return ParserResult::ErrInvalidType{line_num: arith_op_entry.line_num,
-                                                      expected: vec![Type::Bool, Type::Integer],
-                                                      actual: arith_op_entry.r#type};
-                }
-                
-                left.line_num = arith_op_entry.line_num;
-
-                return _expression(slf, builder, resolve_type, left);
-              } else {
-                return arith_op;
-              }
-            } else {
-              return pipe;
-            }
-          },
-          _ => {
-            // base case: if non-matching token is hit, do not parse (lambda-production)
-            return ParserResult::Success(left);
-          }
-        }
+
+  pub fn expression(&mut self) -> ParserResult<Expr> {
+    fn _expression(slf: &mut Parser, left: Expr) -> ParserResult<Expr> {
+
+      let op = if slf.parse_tok(Token::Ampersand).is_ok() {
+        BinaryOp::And
+      } else if slf.parse_tok(Token::Pipe).is_ok() {
+        BinaryOp::Or
       } else {
-        // base case: allow nothing to be parsed
-        return ParserResult::Success(left);
-      }
+        // base case: if non-matching token is hit, do not parse (lambda-production)
+        return Ok(left);
+      };
+
+      let arith_op = slf.arith_op()?;
+
+      // fold arith_op into left
+      return _expression(slf, Expr::Binary { op, left: Box::new(left), right: Box::new(arith_op) });
     }
-    
-    // optionally parse a 'not' kw
-    let negate = match self.parse_tok(tokens::not_kw::NotKW::start()) {
-      ParserResult::Success(_) => true,
-      _ => false
-    };
-    
-    let arith_op = self.arith_op(builder, resolve_type);
-    if let ParserResult::Success(arith_op_entry) = arith_op {
-      return _expression(self, builder, resolve_type, arith_op_entry);
-    } else { return arith_op; }
+
+    return self.node(SyntaxKind::Expression, |slf| {
+      // optionally parse a 'not' kw
+      let negate = slf.parse_tok(Token::NotKW).is_ok();
+
+      let mut arith_op = slf.arith_op()?;
+      if negate {
+        arith_op = Expr::Not(Box::new(arith_op));
+      }
+
+      return _expression(slf, arith_op);
+    });
   }
-  
-  pub fn argument_list(&mut self, builder: &mut LLVMBuilderRef, arg_list: &mut Vec<LLVMValueRef>, mut param_types: Iter<Box<Type>>) -> ParserResult {
-  
-    // compare arguments to procedure parameters
-    let curr_arg_type;
-    if let Some(param_type) = param_types.next() {
-      curr_arg_type = param_type;
-    } else {
-      return ParserResult::Error{line_num: 0, msg: String::from("Expected more arguments")};
+
+  pub fn argument_list(&mut self, args: &mut Vec<Expr>) -> ParserResult<()> {
+
+    let expression = self.expression()?;
+    args.push(expression);
+
+    // optionally parse the rest
+    if self.parse_tok(Token::Comma).is_ok() {
+      return self.argument_list(args);
     }
-  
-    let expression = self.expression(builder, curr_arg_type);
-    if let ParserResult::Success(mut entry) = expression {
-    
-      // cast value if needed
-      let arg_val = if let Ok(val) = Parser::coerce(builder, &entry.r#type, &curr_arg_type, &mut entry.value_ref) {
-        val
-      } else {
-        entry.value_ref
-      };
-      
-      arg_list.push(arg_val);
-    
-      // optionally parse the rest
-      let comma = self.parse_tok(tokens::comma::Comma::start());
-      if let ParserResult::Success(..) = comma {
-        return self.argument_list(builder, arg_list, param_types);
-      }
-      
-      return ParserResult::Success(entry);
-    } else { return expression; }
+
+    return Ok(());
   }
-  
-  pub fn assignment_statement(&mut self, builder: &mut LLVMBuilderRef) -> ParserResult {
-    let destination = self.destination(builder);
-    if let ParserResult::Success(mut dest_id) = destination {
-    
-      // look up the destination in the symbol table to retrieve the type
-      if let Some(dest) = self.get_symbol(&dest_id.chars) {
-        let dest_type = dest.r#type.clone();
-        let dest_value_ref = dest.value_ref.clone();
-        
-        let assign = self.parse_tok(tokens::assign::Assign::start());
-        if let ParserResult::Success(assign_entry) = assign {
-          let expression = self.expression(builder, &dest_type);
-          if let ParserResult::Success(mut expr_entry) = expression {
-          
-            // enforce that the expression type is compatible with destination type
-            if !Parser::is_compatible(&dest_type, &expr_entry.r#type) {
-              return ParserResult::ErrInvalidType{line_num: assign_entry.line_num,
-                                                  expected: vec![dest_type],
-                                                  actual: expr_entry.r#type.clone()};
-            }
-            
-            // coerce the type if necessary
-            // cast the expression to the return type
-            let coercion = Parser::coerce(builder, &expr_entry.r#type, &dest_type, &mut expr_entry.value_ref);
-            if let Result::Ok(coerced_val) = coercion {
-              expr_entry.value_ref = coerced_val;
-            }
-            
-            // store the value in alloca
-            unsafe {
-              // TODO check that the value is a pointer (alloca)
-            
-              // store the expression value
-              core::LLVMBuildStore(*builder, expr_entry.value_ref, dest_value_ref);
-            };
-          
-            return ParserResult::Success(dest_id);
-          } else { return expression; }
-        } else { return assign; }
-        
-      } else { return ParserResult::ErrSymbolNotFound{name: dest_id.chars, line_num: dest_id.line_num}; }
-      
-    } else { return destination; }
+
+  pub fn assignment_statement(&mut self) -> ParserResult<Assignment> {
+    let destination = self.destination()?;
+    let assign_entry = self.parse_tok(Token::Assign)?;
+    let expression = self.expression()?;
+
+    return Ok(Assignment { destination, line_num: assign_entry.line_num, expression });
   }
-  
-  pub fn destination(&mut self, builder: &mut LLVMBuilderRef) -> ParserResult {
-    let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-    if let ParserResult::Success(_) = identifier {
-      // optionally parse an index to this value
-      let l_bracket = self.parse_tok(tokens::brackets::LBracket::start());
-      if let ParserResult::Success(_) = l_bracket {
-        let idx_type = Type::Integer;
-        let expression = self.expression(builder, &idx_type);
-        if let ParserResult::Success(_) = expression {
-          if let ParserResult::Success(_) = self.parse_tok(tokens::brackets::RBracket::start()) {
-            return identifier;
-          }
-        } else {
-          return expression;
-        }
-      }
-      
-      return identifier;
-      
-    } else { return identifier; }
+
+  pub fn destination(&mut self) -> ParserResult<Name> {
+    return self.name();
   }
-  
-  pub fn if_statement(&mut self, builder: &mut LLVMBuilderRef, return_type: &Type) -> ParserResult {
-    let if_kw = self.parse_tok(tokens::if_kw::IfKW::start());
-    if let ParserResult::Success(_) = if_kw {
-      let l_paren = self.parse_tok(tokens::parens::LParen::start());
-      if let ParserResult::Success(_) = l_paren {
-      
-        // if statement expressions should evaluate to a boolean
-        let if_expr_type = Type::Bool;
-        let expression = self.expression(builder, &if_expr_type);
-        if let ParserResult::Success(expr_entry) = expression {
-          let r_paren = self.parse_tok(tokens::parens::RParen::start());
-          if let ParserResult::Success(r_paren_entry) = r_paren {
-          
-            // check that that the expression type is compatible with bool
-            if !Parser::is_compatible(&if_expr_type, &expr_entry.r#type) {
-              return ParserResult::ErrInvalidType{line_num: r_paren_entry.line_num,
-                                                  expected: vec![if_expr_type],
-                                                  actual: expr_entry.r#type};
-            }
-          
-            let then_kw = self.parse_tok(tokens::then_kw::ThenKW::start());
-            if let ParserResult::Success(_) = then_kw {
-              
-              // parse an arbitrary number of statements delimited by ';'
-              loop {
-                if let Some(tok_entry) = self.lexer.peek() {
-                  match tok_entry.tok_type {
-                    Token::ElseKW(_) | Token::EndKW(_) => break,
-                    _ => {
-                      let statement = self.statement(builder, return_type);
-                      self.resync();
-                    }
-                  }
-                } else {
-                  break;
-                }
-              }
-              
-              // optionally parse else statement
-              let else_kw = self.parse_tok(tokens::else_kw::ElseKW::start());
-              if let ParserResult::Success(_) = else_kw {
-                // parse an arbitrary number of statements delimited by ';'
-                loop {
-                  if let Some(tok_entry) = self.lexer.peek() {
-                    match tok_entry.tok_type {
-                      Token::EndKW(_) => break,
-                      _ => {
-                        let statement = self.statement(builder, return_type);
-                        self.resync();
-                      }
-                    }
-                  } else {
-                    break;
-                  }
-                }
-              }
-              
-              let end_kw = self.parse_tok(tokens::end_kw::EndKW::start());
-              if let ParserResult::Success(_) = end_kw {
-                return self.parse_tok(tokens::if_kw::IfKW::start());
-              } else { 
-                return end_kw;
-              }
-            } else { return then_kw; }
-          } else { return r_paren; }
-        } else { return expression; }
-      } else { return l_paren; }
-    } else { return if_kw; }
+
+  pub fn if_statement(&mut self) -> ParserResult<IfStatement> {
+    let if_entry = self.parse_tok(Token::IfKW)?;
+    self.parse_tok(Token::LParen)?;
+    let condition = self.expression()?;
+    let r_paren_entry = self.parse_tok(Token::RParen)?;
+    self.parse_tok(Token::ThenKW)?;
+
+    // parse an arbitrary number of statements delimited by ';'
+    let then_statements = self.statements(&[Token::ElseKW, Token::EndKW]);
+
+    // optionally parse else statement
+    let mut else_statements = vec![];
+    if self.parse_tok(Token::ElseKW).is_ok() {
+      // parse an arbitrary number of statements delimited by ';'
+      else_statements = self.statements(&[Token::EndKW]);
+    }
+
+    self.block_end(Token::IfKW, if_entry.line_num);
+
+    return Ok(IfStatement {
+      line_num: if_entry.line_num,
+      condition,
+      condition_line: r_paren_entry.line_num,
+      then_statements,
+      else_statements
+    });
   }
-  
-  pub fn loop_statement(&mut self, builder: &mut LLVMBuilderRef, return_type: &Type) -> ParserResult {
-    let for_kw = self.parse_tok(tokens::for_kw::ForKW::start());
-    if let ParserResult::Success(_) = for_kw {
-      let l_paren = self.parse_tok(tokens::parens::LParen::start());
-      if let ParserResult::Success(_) = l_paren {
-        let assignment_statement = self.assignment_statement(builder);
-        if let ParserResult::Success(_) = assignment_statement {
-          let semicolon = self.parse_tok(tokens::semicolon::Semicolon::start());
-          if let ParserResult::Success(_) = semicolon {
-            
-            // loop statement expressions should evaluate to a boolean (invariant)
-            let loop_expr_type = Type::Bool;
-            let expression = self.expression(builder, &loop_expr_type);
-            if let ParserResult::Success(expr_entry) = expression {
-              let r_paren = self.parse_tok(tokens::parens::RParen::start());
-              if let ParserResult::Success(r_paren_entry) = r_paren {
-              
-                // check that that the expression type is compatible with bool
-                if !Parser::is_compatible(&loop_expr_type, &expr_entry.r#type) {
-                  return ParserResult::ErrInvalidType{line_num: r_paren_entry.line_num,
-                                                      expected: vec![loop_expr_type],
-                                                      actual: expr_entry.r#type};
-                }
-                
-                // parse an arbitrary number of statements delimited by ';'
-                loop {
-                  if let Some(tok_entry) = self.lexer.peek() {
-                    match tok_entry.tok_type {
-                      Token::EndKW(_) => break,
-                      _ => {
-                        let statement = self.statement(builder, return_type);
-                        self.resync();
-                      }
-                    }
-                  } else {
-                    break;
-                  }
-                }
-                
-                let end_kw = self.parse_tok(tokens::end_kw::EndKW::start());
-                if let ParserResult::Success(_) = end_kw {
-                  return self.parse_tok(tokens::for_kw::ForKW::start());
-                } else { return end_kw; }
-              } else { return r_paren; }
-            } else { return expression; }
-          } else { return semicolon; }
-        } else { return assignment_statement; }
-      } else { return l_paren; }
-    } else { return for_kw; }
+
+  pub fn loop_statement(&mut self) -> ParserResult<LoopStatement> {
+    let for_entry = self.parse_tok(Token::ForKW)?;
+    self.parse_tok(Token::LParen)?;
+    let assignment = self.node(SyntaxKind::AssignmentStatement, Parser::assignment_statement)?;
+    self.parse_tok(Token::Semicolon)?;
+    let condition = self.expression()?;
+    let r_paren_entry = self.parse_tok(Token::RParen)?;
+
+    // parse an arbitrary number of statements delimited by ';'
+    let statements = self.statements(&[Token::EndKW]);
+
+    self.block_end(Token::ForKW, for_entry.line_num);
+
+    return Ok(LoopStatement {
+      line_num: for_entry.line_num,
+      assignment,
+      condition,
+      condition_line: r_paren_entry.line_num,
+      statements
+    });
   }
-  
-  pub fn return_statement(&mut self, builder: &mut LLVMBuilderRef, return_type: &Type) -> ParserResult {
-    let return_kw = self.parse_tok(tokens::return_kw::ReturnKW::start());
-    if let ParserResult::Success(return_entry) = return_kw {
-      let expression = self.expression(builder, return_type);
-      if let ParserResult::Success(mut expr_entry) = expression {
-        // check that that the expression type is compatible with expected type
-        if !Parser::is_compatible(&return_type, &expr_entry.r#type) {
-          return ParserResult::ErrInvalidType{line_num: return_entry.line_num,
-                                              expected: vec![return_type.clone()],
-                                              actual: expr_entry.r#type.clone()};
-        }
-        
-        // cast the expression to the return type
-        let coercion = Parser::coerce(builder, &expr_entry.r#type, return_type, &mut expr_entry.value_ref);
-        if let Result::Ok(coerced_val) = coercion {
-          expr_entry.value_ref = coerced_val;
-        }
-        
-        let ret = unsafe {
-          core::LLVMBuildRet(*builder, expr_entry.value_ref)
-        };
-        
-        return ParserResult::Success(expr_entry);
-        
-      } else {
-        return expression;
-      }
-    } else { return return_kw; }
+
+  pub fn return_statement(&mut self) -> ParserResult<ReturnStatement> {
+    let return_entry = self.parse_tok(Token::ReturnKW)?;
+    let expression = self.expression()?;
+
+    return Ok(ReturnStatement { line_num: return_entry.line_num, expression });
   }
 
-  pub fn procedure_call_or_name(&mut self, builder: &mut LLVMBuilderRef, resolve_type: &Type) -> ParserResult {
+  pub fn procedure_call_or_name(&mut self) -> ParserResult<Expr> {
     // this could be a procedure call or a name based on the next token
-    let identifier = self.parse_tok(tokens::identifier::Identifier::start());
-    
-    let peek_tok = self.lexer.peek();
-    
-    if let Some(tok_entry) = &peek_tok {
-      if let Token::LParen(_) = &tok_entry.tok_type {
-        return self.procedure_call_w_identifier(builder, identifier, resolve_type);
+    let identifier = self.identifier()?;
+
+    if let Some(tok_entry) = self.lexer.peek() {
+      if let Token::LParen = &tok_entry.tok_type {
+        return self.procedure_call_w_identifier(identifier);
       }
     }
-    
-    return self.name_w_identifier(builder, identifier);
-    
+
+    return Ok(Expr::Name(self.name_w_identifier(identifier)?));
+
   }
-  
-  pub fn name_or_number(&mut self, builder: &mut LLVMBuilderRef) -> ParserResult {
-    
-    // optionally parse a dash
-    // TODO act on this value. May need to negate the number and reject non-multipliable
-    let negate = if let ParserResult::Success(_) = self.parse_tok(tokens::dash::Dash::start()) {
-      true
-    } else {
-      false
-    };
-    
+
+  pub fn name_or_number(&mut self) -> ParserResult<Expr> {
+
+    self.parse_tok(Token::Dash)?;
+
     let peek_tok = self.lexer.peek();
     if let Some(tok_entry) = &peek_tok {
       let value = match &tok_entry.tok_type {
-        Token::Identifier(_) => { self.name(builder) },
-        Token::Number(_) => { self.parse_tok(tokens::number::Number::start()) },
-        _ => { return ParserResult::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from("(<identifier>|<number>)"), actual: String::from(&tok_entry.chars[..])}; }
+        Token::Identifier => { Expr::Name(self.name()?) },
+        Token::Number => { self.literal(Token::Number)? },
+        _ => { return Err(Diagnostic::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from("(<identifier>|<number>)"), actual: String::from(&tok_entry.chars[..]), suggestion: None}); }
       };
-      
-      if let ParserResult::Success(value_entry) = &value {
-        // TODO apply negation here
-      
-        return value;
-      } else { return value; }
-    } else { return ParserResult::ErrUnexpectedEnd; }
+
+      return Ok(Expr::Negate(Box::new(value)));
+    } else { return Err(Diagnostic::ErrUnexpectedEnd); }
   }
-  
-  pub fn factor(&mut self, builder: &mut LLVMBuilderRef, resolve_type: &Type) -> ParserResult {
+
+  pub fn factor(&mut self) -> ParserResult<Expr> {
     // peek at next token to decide what type of factor this will be
     let peek_tok = self.lexer.peek();
     if let Some(tok_entry) = &peek_tok {
       match &tok_entry.tok_type {
-        Token::LParen(_) => {
+        Token::LParen => {
           // resolve to a subexpression
-          let l_paren = self.parse_tok(tokens::parens::LParen::start());
-          if let ParserResult::Success(_) = l_paren {
-            let expression = self.expression(builder, resolve_type);
-            if let ParserResult::Success(_) = expression {
-              let r_paren = self.parse_tok(tokens::parens::RParen::start());
-              if let ParserResult::Success(_) = r_paren {
-                return expression;
-              } else { return r_paren; }
-            } else { return expression; }
-          } else { return l_paren; }
+          self.parse_tok(Token::LParen)?;
+          let expression = self.expression()?;
+          self.parse_tok(Token::RParen)?;
+
+          return Ok(expression);
         },
-        Token::Identifier(_) => { 
-          return self.procedure_call_or_name(builder, resolve_type);
+        Token::Identifier => {
+          return self.procedure_call_or_name();
         },
-        Token::Dash(_) => {
+        Token::Dash => {
           // this could be a name or number depending on the next token
-          return self.name_or_number(builder);
+          return self.name_or_number();
         },
-        Token::String(_) => {
-          return self.parse_tok(tokens::string::String::start());
+        Token::String => {
+          return self.literal(Token::String);
         },
-        Token::Number(_) => {
-          return self.parse_tok(tokens::number::Number::start()); 
+        Token::Number => {
+          return self.literal(Token::Number);
         }
-        Token::TrueKW(_) => {
-          return self.parse_tok(tokens::true_kw::TrueKW::start());
+        Token::TrueKW => {
+          return self.literal(Token::TrueKW);
         },
-        Token::FalseKW(_) => { return self.parse_tok(tokens::false_kw::FalseKW::start()); },
+        Token::FalseKW => { return self.literal(Token::FalseKW); },
         _ => {
-          return ParserResult::ErrUnexpectedTok {line_num: tok_entry.line_num,
-                                                  expected: String::from("('('|<identifier>|'-'|<number>|<string>|true|false)"),
-                                                  actual: String::from(&tok_entry.chars[..])};
+          return Err(Diagnostic::ErrUnexpectedTok {line_num: tok_entry.line_num,
+                                                   expected: String::from("('('|<identifier>|'-'|<number>|<string>|true|false)"),
+                                                   actual: String::from(&tok_entry.chars[..]), suggestion: None});
 
         }
       }
-    } else { return ParserResult::ErrUnexpectedEnd; }
+    } else { return Err(Diagnostic::ErrUnexpectedEnd); }
+  }
+
+  // parse a literal token (its type was decided by the lexer)
+  pub fn literal(&mut self, target: Token) -> ParserResult<Expr> {
+    let entry = self.parse_tok(target)?;
+
+    return Ok(Expr::Literal { chars: entry.chars, r#type: entry.r#type, line_num: entry.line_num });
+  }
+
+  pub fn identifier(&mut self) -> ParserResult<Identifier> {
+    let entry = self.parse_tok(Token::Identifier)?;
+
+    return Ok(Identifier { name: entry.chars, line_num: entry.line_num, span: entry.span });
   }
-  
-  // TODO get this to use generics so that a dummy target token doesn't need to be passed in
-  pub fn parse_tok(&mut self, target: Token) -> ParserResult {
+
+  // consume the next token if it is the target
+  pub fn parse_tok(&mut self, target: Token) -> ParserResult<TokenEntry> {
     if let Some(tok_entry) = self.lexer.peek() {
       // if the next token matches the target, consume and return success result
-      if mem::discriminant(&tok_entry.tok_type) == mem::discriminant(&target) {
-        if let Some(tok_entry) = self.lexer.next() {
-          return ParserResult::Success(tok_entry);
+      if tok_entry.tok_type == target {
+        if let Some(tok_entry) = self.next_tok() {
+          return Ok(tok_entry);
         } else {
-          return ParserResult::ErrUnexpectedEnd;
+          return Err(Diagnostic::ErrUnexpectedEnd);
         }
-      } else { return ParserResult::ErrUnexpectedTok {line_num: tok_entry.line_num,
-                                                      expected: String::from(target.get_example()),
-                                                      actual: String::from(&tok_entry.chars[..])};
+      } else { return Err(Diagnostic::ErrUnexpectedTok {line_num: tok_entry.line_num,
+                                                        expected: String::from(target.get_example()),
+                                                        actual: String::from(&tok_entry.chars[..]), suggestion: None});
       }
-    } else { return ParserResult::ErrUnexpectedEnd; }
+    } else { return Err(Diagnostic::ErrUnexpectedEnd); }
   }
-  
-  // consume tokens until a semicolon is hit, and then consume the semicolon
-  pub fn resync(&mut self) -> ParserResult {
-    let mut missed_semicolon = false;
-    loop {
-      let result = self.parse_tok(tokens::semicolon::Semicolon::start());
-      
-      match result {
-        ParserResult::Success(_) => return result,
-        ParserResult::ErrUnexpectedEnd => {
-          result.print();
-          return result;
-        },
-        _ => {
-          if !missed_semicolon {
-            result.print();
-            missed_semicolon = true;
-          }
-          self.lexer.next();
-        }
+
+  /*
+    Below are functions for recovering from syntax errors
+    After an error the parser skips ahead to a token that can follow the construct that failed (its follow set),
+    so that one mistake is reported once instead of causing errors in everything after it
+  */
+
+  // the semicolon terminating a declaration or statement. If it's missing it is inserted when the next token
+  // can start something new, and otherwise the tokens up to the next semicolon are deleted
+  fn terminator(&mut self) {
+    if let Err(err) = self.parse_tok(Token::Semicolon) {
+      self.report(err);
+
+      if !self.at_sync_point() {
+        self.recover();
       }
     }
   }
-  
-  // return type based on the type mark token
-  pub fn get_type(variable_entry: &TokenEntry) -> Type {
-    return match variable_entry.tok_type {
-      Token::EnumKW(_) => Type::Enum,
-      Token::IntegerKW(_) => Type::Integer,
-      Token::FloatKW(_) => Type::Float,
-      Token::StringKW(_) => Type::String,
-      Token::BoolKW(_) => Type::Bool,
-      _ => Type::None
-    };
-  }
-  
-  pub fn get_symbol(&self, name: &String) -> Option<&TokenEntry> {
-    // check the local table
-    if let Some(local_table) = self.symbol_table_chain.last() {
-      if let Some(symbol) = local_table.get(name) {
-        return Some(symbol);
-      } else if let Some(global_table) = self.symbol_table_chain.first() {
-        if let Some(symbol) = global_table.get(name) {
-          return Some(symbol);
-        } else {
-          return None;
-        }
-      } else {
-        println!("Hmm. Symbol table not found");
-        return None;
+
+  // consume tokens until a semicolon is hit (consuming it), or a token that starts a new declaration or statement
+  fn recover(&mut self) {
+    while !self.at_sync_point() {
+      let skipped = self.next_tok();
+
+      if let Some(entry) = &skipped {
+        trace!("parser", "skipping '{}' on line {}", entry.chars, entry.line_num);
+      }
+
+      if let Some(Token::Semicolon) = skipped.map(|entry| entry.tok_type) {
+        return;
       }
-    } else {
-      println!("Hmm. Symbol table not found");
-      return None;
     }
   }
-  
-  pub fn add_symbol(&mut self, builder: &mut LLVMBuilderRef, scope: &Scope, tok_entry: Rc<TokenEntry>) {
-    match scope {
-      Scope::Local => {
-        if let Some(table) = self.symbol_table_chain.last_mut() {
-          table.insert(String::from(&tok_entry.chars[..]), tok_entry);
-        }
+
+  // whether the next token starts a new declaration, statement or part of a block (or the program has ended)
+  // an identifier only starts a statement if it is on a new line, since identifiers are common inside statements
+  fn at_sync_point(&mut self) -> bool {
+    let prev_line = self.prev_line;
+
+    return match self.lexer.peek() {
+      Some(tok_entry) => match tok_entry.tok_type {
+        Token::IfKW | Token::ForKW | Token::ReturnKW | Token::EndKW | Token::ElseKW | Token::BeginKW => true,
+        Token::GlobalKW | Token::ProcedureKW | Token::VariableKW | Token::TypeKW => true,
+        Token::Identifier => tok_entry.line_num > prev_line,
+        _ => false
       },
-      Scope::Global => {
-        if let Some(table) = self.symbol_table_chain.first_mut() {
-          table.insert(String::from(&tok_entry.chars[..]), tok_entry);
+      None => true
+    };
+  }
+
+  // skip the rest of a block (its opening keyword has been consumed) up to and including its 'end <kw>' and semicolon
+  // nested blocks are skipped whole, and an 'end' closing an enclosing block is left for that block
+  fn skip_block(&mut self, kw: Token) {
+    // the blocks opened while skipping
+    let mut open = vec![];
+
+    loop {
+      let tok_type = match self.lexer.peek() {
+        Some(tok_entry) => tok_entry.tok_type,
+        None => return
+      };
+
+      match tok_type {
+        Token::EndKW => {
+          let closes = self.lexer.peek_second().map(|entry| entry.tok_type);
+
+          if open.is_empty() && closes != Some(kw) {
+            return;
+          }
+
+          self.next_tok();
+
+          if let Some(closes) = closes {
+            if Parser::is_block_kw(&closes) {
+              self.next_tok();
+
+              // close the innermost block of this kind (and any inside it that were missing their end)
+              if let Some(index) = open.iter().rposition(|&block| block == closes) {
+                open.truncate(index);
+              } else if open.is_empty() {
+                let _ = self.parse_tok(Token::Semicolon);
+                return;
+              }
+            }
+          }
+        },
+        Token::IfKW | Token::ForKW | Token::ProcedureKW => {
+          self.next_tok();
+          open.push(tok_type);
+        },
+        _ => {
+          self.next_tok();
         }
       }
     }
   }
-  
-  pub fn add_builtins(&mut self, builder: &mut LLVMBuilderRef) {
-    let (get_bool, put_bool) = builtins::bool::initialize_bool_funcs(self.llvm_module);
-    self.add_builtin(builder, get_bool);
-    self.add_builtin(builder, put_bool);
-    
-    let (get_integer, put_integer) = builtins::integer::initialize_integer_funcs(self.llvm_module);
-    self.add_builtin(builder, get_integer);
-    self.add_builtin(builder, put_integer);
-    
-    let (get_float, put_float) = builtins::float::initialize_float_funcs(self.llvm_module);
-    self.add_builtin(builder, get_float);
-    self.add_builtin(builder, put_float);
-  }
-  
-  pub fn add_builtin(&mut self, builder: &mut LLVMBuilderRef, builtin: TokenEntry) {
-    self.add_symbol(builder, &Scope::Global, Rc::new(builtin));
+
+  fn is_block_kw(tok: &Token) -> bool {
+    return matches!(tok, Token::IfKW | Token::ForKW | Token::ProcedureKW | Token::ProgramKW);
   }
-  
-  // losslessly converts the value_ref of each token entry to have matching types
-  pub fn upcast(builder: &mut LLVMBuilderRef, from_entry: &mut TokenEntry, to_entry: &mut TokenEntry) {
-  
-    // if types already match, no change required
-    if mem::discriminant(&from_entry.r#type) == mem::discriminant(&to_entry.r#type) {
-      return;
-    }
-  
-    match (&from_entry.r#type, &to_entry.r#type) {
-      (Type::Integer, Type::Float) | (Type::Float, Type::Integer) => {
-        let int_entry = if let Type::Integer = &from_entry.r#type {
-          from_entry
-        } else {
-          to_entry
-        };
-        
-        // upcast int value to float
-        if let Ok(res) = Parser::coerce(builder, &Type::Integer, &Type::Float, &mut int_entry.value_ref) {
-          int_entry.value_ref = res;
-          int_entry.r#type = Type::Float;
-        }
-      },
-      (Type::Integer, Type::Bool) | (Type::Bool, Type::Integer) => {
-        let bool_entry = if let Type::Bool = &from_entry.r#type {
-          from_entry
-        } else {
-          to_entry
-        };
-        
-        // upcast bool value to integer
-        if let Ok(res) = Parser::coerce(builder, &Type::Bool, &Type::Integer, &mut bool_entry.value_ref) {
-          bool_entry.value_ref = res;
-          bool_entry.r#type = Type::Integer;
-        }
-      },
-      _ => () // no change if upcast pattern isn't matched
+
+  // the 'begin' of a body. If it's missing but the statements (or the 'end') follow, it is inserted
+  fn body_begin(&mut self) -> ParserResult<()> {
+    if let Some(tok_entry) = self.lexer.peek() {
+      if tok_entry.tok_type == Token::EndKW || Parser::is_statement_start(&tok_entry.tok_type) {
+        let err = self.unexpected(Token::BeginKW.get_example());
+        self.report(err);
+
+        return Ok(());
+      }
     }
+
+    self.parse_tok(Token::BeginKW)?;
+
+    return Ok(());
   }
-  
-  pub fn coerce(builder: &mut LLVMBuilderRef, from_type: &Type, to_type: &Type, value: &mut LLVMValueRef) -> Result<LLVMValueRef, ()> {
-  
-    if mem::discriminant(from_type) == mem::discriminant(to_type) {
-      return Ok(*value);
-    }
-  
-    return match (from_type, to_type) {
-      (Type::Integer, Type::Bool) => {
-        let res = unsafe {
-          let is_true = core::LLVMBuildICmp(*builder, llvm_sys::LLVMIntPredicate::LLVMIntNE, *value, LLVMConstInt(core::LLVMInt32Type(), 0, 0), null_str());
-          core::LLVMBuildZExt(*builder, is_true, core::LLVMInt32Type(), null_str())
-        };
-        
-        Ok(res)
+
+  // the 'end <kw>' closing a block that starts on `start_line`, returning the line the block ends on
+  // if the 'end' is missing it is inserted, so that the enclosing blocks can still be closed
+  fn block_end(&mut self, kw: Token, start_line: u32) -> u32 {
+    let missing_end = |line_num| Diagnostic::ErrMissingEnd {line_num, construct: String::from(kw.get_example()), start_line};
+
+    let end_line = match self.lexer.peek() {
+      Some(tok_entry) if tok_entry.tok_type == Token::EndKW => tok_entry.line_num,
+      Some(tok_entry) => {
+        let line_num = tok_entry.line_num;
+        self.report(missing_end(line_num));
+
+        return self.prev_line;
       },
-      (Type::Integer, Type::Float) => Ok(unsafe { core::LLVMBuildSIToFP(*builder, *value, get_llvm_type(to_type), null_str()) }),
-      (Type::Float, Type::Integer) => Ok(unsafe { core::LLVMBuildFPToSI(*builder, *value, get_llvm_type(to_type), null_str()) }),
-      (Type::Bool, Type::Integer) => Ok(unsafe { core::LLVMBuildIntCast(*builder, *value, get_llvm_type(to_type), null_str()) }),
-      _ => {
-        println!("error while coercing");
-        Err(())
+      None => {
+        let line_num = self.prev_line;
+        self.report(missing_end(line_num));
+
+        return line_num;
       }
     };
-  }
-  
-  fn is_compatible(expected_type: &Type, actual_type: &Type) -> bool {
-    return match (expected_type, actual_type) {
-      (Type::Integer, Type::Bool) | (Type::Bool, Type::Integer) => true,
-      (Type::Integer, Type::Float) | (Type::Float, Type:: Integer) => true,
-      (Type::Array(el_type_a, size_a), Type::Array(el_type_b, size_b)) => {
-        Parser::is_compatible(el_type_a, el_type_b) && size_a == size_b
+
+    match self.lexer.peek_second().map(|entry| entry.tok_type) {
+      Some(closes) if closes == kw => {
+        self.next_tok();
+        self.next_tok();
       },
-      (Type::Array(el_type, size), other_type) => {
-        Parser::is_compatible(el_type, other_type)
+      // the 'end' closes an enclosing block
+      Some(closes) if Parser::is_block_kw(&closes) => {
+        self.report(missing_end(end_line));
+
+        return self.prev_line;
       },
-      (other_type, Type::Array(el_type, el_size)) => {
-        Parser::is_compatible(el_type, other_type)
+      // 'end' without the keyword
+      _ => {
+        self.next_tok();
+
+        if let Err(err) = self.parse_tok(kw) {
+          self.report(err);
+        }
       }
-      (a, b) => {
-        mem::discriminant(a) == mem::discriminant(b)
+    }
+
+    return end_line;
+  }
+
+  // record an error, unless it was caused by the last one
+  fn report(&mut self, mut err: Diagnostic) {
+    let position = self.position();
+
+    if self.last_error == Some(position) {
+      return;
+    }
+
+    self.last_error = Some(position);
+
+    // an identifier where a keyword was expected may be a misspelling of it
+    if let Diagnostic::ErrUnexpectedTok{expected, actual, suggestion, ..} = &mut err {
+      if suggestion.is_none() {
+        *suggestion = suggest::keyword(actual, expected);
       }
     }
+
+    debug!("parser", "{}", err.message());
+    self.diagnostics.push(err);
+  }
+
+  // the byte position of the next token (or the end of the program)
+  fn position(&mut self) -> usize {
+    return match self.lexer.peek() {
+      Some(tok_entry) => tok_entry.span.start,
+      None => self.lexer.program.len()
+    };
+  }
+
+  // the error for the next token, which isn't what was expected
+  fn unexpected(&mut self, expected: &str) -> Diagnostic {
+    return match self.lexer.peek() {
+      Some(tok_entry) => Diagnostic::ErrUnexpectedTok {line_num: tok_entry.line_num, expected: String::from(expected), actual: String::from(&tok_entry.chars[..]), suggestion: None},
+      None => Diagnostic::ErrUnexpectedEnd
+    };
   }
-  
-  fn print_symbol_table(name: String, table: &HashMap<String, Rc<TokenEntry>>) {
-    // debugging - print contents of the table
-    println!("Printing variables in table for scope: {}", name);
-    for key in table.keys() {
-      if let Some(value) = table.get(key) {
-        let type_str = value.r#type.to_string();
-        
-        println!("key: {} ({})", key, type_str);
+
+  // consume the next token, recording it in the concrete syntax tree
+  fn next_tok(&mut self) -> Option<TokenEntry> {
+    let tok_entry = self.lexer.next();
+
+    if let Some(entry) = &tok_entry {
+      self.prev_line = entry.line_num;
+      self.read += 1;
+
+      if let Some(builder) = &mut self.cst {
+        builder.token(entry);
       }
     }
-    
-    println!("\n");
-    
+
+    return tok_entry;
   }
-  
-}
 
-// TODO add chaining behavior so that we can chain parse rules until a failure is reached. This would really simplify this code by flattening all these nested ifs
-pub enum ParserResult {
-  ErrUnexpectedEnd,
-  ErrUnexpectedTok{ expected: String, actual: String, line_num: u32},
-  ErrSymbolNotFound{name: String, line_num: u32},
-  ErrInvalidType{line_num: u32, expected: Vec<Type>, actual: Type},
-  Error{line_num: u32, msg: String},
-  Success(TokenEntry),
-}
+  // run a parse rule inside a node of the concrete syntax tree. The node is closed even if the rule fails
+  fn node<T, F: FnOnce(&mut Self) -> ParserResult<T>>(&mut self, kind: SyntaxKind, rule: F) -> ParserResult<T> {
+    self.start_node(kind);
+    let result = rule(self);
+    self.finish_node();
 
-impl ParserResult {
-  pub fn print(&self) {
-    match self {
-      ParserResult::ErrUnexpectedEnd => println!("Unexpected end of program."),
-      ParserResult::ErrUnexpectedTok{line_num, expected, actual} => println!("({}) - Unexpected token - Expected: '{}', got: '{}'", line_num, expected, actual),
-      ParserResult::ErrSymbolNotFound{line_num, name} => println!("({}) - Symbol undefined: '{}'", line_num, name),
-      ParserResult::ErrInvalidType{line_num, expected, actual} => {
-        let mut expected_str = String::new();
-        for r#type in expected {
-          expected_str.push_str(&r#type.to_string()[..]);
-          expected_str.push_str(", ");
-        }
-        println!("({}) - Unexpected type: '{}', expected: [{}]", line_num, actual.to_string(), expected_str);
-      },
-      ParserResult::Error{line_num, msg} => println!("({}) - Error: {}", line_num, msg),
-      ParserResult::Success(entry) => println!("({}) - Success", entry.line_num)
+    return result;
+  }
+
+  fn start_node(&mut self, kind: SyntaxKind) {
+    if let Some(builder) = &mut self.cst {
+      builder.start_node(kind);
     }
   }
-}
 
-pub enum Scope {
-  Local,
-  Global
-}
\ No newline at end of file
+  fn finish_node(&mut self) {
+    if let Some(builder) = &mut self.cst {
+      builder.finish_node();
+    }
+  }
+
+  // return type based on the type mark token
+  pub fn get_type(variable_entry: &TokenEntry) -> Type {
+    return match variable_entry.tok_type {
+      Token::EnumKW => Type::Enum,
+      Token::IntegerKW => Type::Integer,
+      Token::FloatKW => Type::Float,
+      Token::StringKW => Type::String,
+      Token::BoolKW => Type::Bool,
+      _ => Type::None
+    };
+  }
+
+}
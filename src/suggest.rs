@@ -0,0 +1,66 @@
+// "did you mean" suggestions for misspelled names and keywords
+
+use crate::tokenize::spec;
+
+// the candidate closest to `name`, if it is close enough that it was probably what was meant
+// ties go to the candidate that sorts first, so the suggestion doesn't depend on the order of the candidates
+pub fn closest<'a, I: IntoIterator<Item = &'a str>>(name: &str, candidates: I) -> Option<String> {
+  // about one typo for every three characters
+  let max_distance = name.chars().count().max(3) / 3;
+
+  let best = candidates.into_iter()
+    .filter(|&candidate| candidate != name)
+    .map(|candidate| (distance(name, candidate), candidate))
+    .filter(|&(distance, _)| distance <= max_distance)
+    .min();
+
+  return best.map(|(_, candidate)| String::from(candidate));
+}
+
+// the number of characters that have to be inserted, deleted, replaced, or swapped with the next one to turn `a` into `b`
+pub fn distance(a: &str, b: &str) -> usize {
+  let a: Vec<char> = a.chars().collect();
+  let b: Vec<char> = b.chars().collect();
+
+  // rows[i][j] is the distance between the first i characters of a and the first j of b
+  let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+  for (i, row) in rows.iter_mut().enumerate() {
+    row[0] = i;
+  }
+  for (j, cell) in rows[0].iter_mut().enumerate() {
+    *cell = j;
+  }
+
+  for i in 1..=a.len() {
+    for j in 1..=b.len() {
+      let replace = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+      let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + replace);
+
+      if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+        best = best.min(rows[i - 2][j - 2] + 1);
+      }
+
+      rows[i][j] = best;
+    }
+  }
+
+  return rows[a.len()][b.len()];
+}
+
+// the keyword `actual` is probably a misspelling of, out of the keywords named in `expected` (the description of
+// what the parser expected, e.g. "(procedure|variable|type)")
+pub fn keyword(actual: &str, expected: &str) -> Option<String> {
+  let actual = actual.to_lowercase();
+
+  // a keyword in the wrong place isn't misspelled
+  if spec::KEYWORDS.iter().any(|&(keyword, _)| keyword == actual) {
+    return None;
+  }
+
+  let expected: Vec<&str> = expected.split(|ch: char| !ch.is_alphanumeric()).collect();
+  let candidates = spec::KEYWORDS.iter().map(|&(keyword, _)| keyword).filter(|keyword| expected.contains(keyword));
+
+  return closest(&actual, candidates);
+}
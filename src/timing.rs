@@ -0,0 +1,103 @@
+// how long each phase of compiling a program took, and how much it produced (printed with `--time-passes`)
+
+use std::time::{Duration, Instant};
+
+use crate::json::{self, Value};
+use crate::options::Format;
+
+pub struct Pass {
+  pub name: &'static str,
+  pub time: Duration,
+
+  // what the pass produced, e.g. the number of tokens lexed
+  pub counts: Vec<(&'static str, usize)>
+}
+
+pub struct Timings {
+  // in the order they ran
+  pub passes: Vec<Pass>,
+
+  // whether to count what the passes produced. Some counts take work of their own (e.g. indexing the program to
+  // count its symbols), so they are only made when the report is wanted
+  pub counting: bool
+}
+
+impl Timings {
+
+  pub fn new(counting: bool) -> Timings {
+    return Timings { passes: vec![], counting };
+  }
+
+  // run a pass, recording how long it took
+  pub fn time<T, F: FnOnce() -> T>(&mut self, name: &'static str, pass: F) -> T {
+    let start = Instant::now();
+    let result = pass();
+
+    self.passes.push(Pass { name, time: start.elapsed(), counts: vec![] });
+    debug!("driver", "{} took {:?}", name, start.elapsed());
+
+    return result;
+  }
+
+  // record something the last pass produced, if counting
+  pub fn count<F: FnOnce() -> usize>(&mut self, name: &'static str, count: F) {
+    if !self.counting {
+      return;
+    }
+
+    if let Some(pass) = self.passes.last_mut() {
+      pass.counts.push((name, count()));
+    }
+  }
+
+  pub fn total(&self) -> Duration {
+    return self.passes.iter().map(|pass| pass.time).sum();
+  }
+
+  pub fn report(&self, format: Format) -> String {
+    return match format {
+      Format::Text => self.report_text(),
+      Format::Json => self.report_json()
+    };
+  }
+
+  fn report_text(&self) -> String {
+    let mut output = format!("{:<10} {:>10}  {}\n", "pass", "time (ms)", "counts");
+
+    for pass in &self.passes {
+      let counts: Vec<String> = pass.counts.iter().map(|(name, count)| format!("{}: {}", name, count)).collect();
+      let line = format!("{:<10} {:>10.3}  {}", pass.name, milliseconds(pass.time), counts.join(", "));
+
+      output.push_str(line.trim_end());
+      output.push('\n');
+    }
+
+    output.push_str(&format!("{:<10} {:>10.3}\n", "total", milliseconds(self.total())));
+
+    return output;
+  }
+
+  fn report_json(&self) -> String {
+    let passes = self.passes.iter().map(|pass| {
+      let counts = pass.counts.iter().map(|(name, count)| (*name, Value::Number(*count as f64))).collect();
+
+      json::object(vec![
+        ("name", Value::String(String::from(pass.name))),
+        ("ms", Value::Number(milliseconds(pass.time))),
+        ("counts", json::object(counts))
+      ])
+    }).collect();
+
+    let report = json::object(vec![
+      ("passes", Value::Array(passes)),
+      ("total_ms", Value::Number(milliseconds(self.total())))
+    ]);
+
+    return format!("{}\n", report.to_json());
+  }
+
+}
+
+fn milliseconds(time: Duration) -> f64 {
+  return time.as_secs_f64() * 1000.0;
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::tokenize::char_group::CharGroup;
+use crate::tokenize::spec::{self, Chars, Pattern, Piece};
+use crate::tokenize::token::Token;
+
+// one column for each ascii character, and a shared one for everything else
+const COLUMNS: usize = 129;
+
+// marks a missing transition
+const DEAD: u16 = u16::MAX;
+
+// a position in one of the spec's patterns: (index of the pattern, index of the next step)
+type Item = (usize, usize);
+
+// a step of a pattern: the characters it matches, the step after matching one, and whether it can be skipped
+type Step = (Chars, usize, bool);
+
+// a deterministic automaton that recognizes every token in the spec.
+// State 0 is the start state
+pub struct Dfa {
+  // the next state for each (state, column), flattened
+  transitions: Vec<u16>,
+
+  // the token recognized on reaching each state, if any
+  accepts: Vec<Option<Token>>,
+
+  keywords: HashMap<&'static str, Token>
+}
+
+impl Chars {
+
+  fn matches(&self, column: usize) -> bool {
+
+    // characters outside of ascii only match negated sets
+    if column == COLUMNS - 1 {
+      return matches!(self, Chars::Except(_));
+    }
+
+    let ch = column as u8 as char;
+
+    return match (self, CharGroup::get(ch)) {
+      (Chars::Char(expected), _) => ch == *expected,
+      (Chars::Letter, CharGroup::AlphaLower(_)) | (Chars::Letter, CharGroup::AlphaUpper(_)) => true,
+      (Chars::Digit, CharGroup::Number(_)) => true,
+      (Chars::Word, CharGroup::Other(other)) => other == '_',
+      (Chars::Word, _) => true,
+      (Chars::OneOf(chars), _) => chars.contains(ch),
+      (Chars::Except(excluded), _) => ch != *excluded,
+      _ => false
+    };
+  }
+
+}
+
+impl Dfa {
+
+  // the automaton for `spec::TOKENS`, built the first time it is needed
+  pub fn get() -> &'static Dfa {
+    static DFA: OnceLock<Dfa> = OnceLock::new();
+
+    return DFA.get_or_init(|| Dfa::build(spec::TOKENS, spec::KEYWORDS));
+  }
+
+  // build the automaton with subset construction. Each pattern is a chain of steps
+  // (a 'many' step loops on itself), and each dfa state is the set of positions the patterns could be at
+  pub fn build(tokens: &[(Token, Pattern)], keywords: &[(&'static str, Token)]) -> Dfa {
+
+    let patterns: Vec<Vec<Step>> = tokens.iter().map(|(_, pattern)| Dfa::steps(pattern)).collect();
+
+    let start = Dfa::closure(&patterns, (0..patterns.len()).map(|i| (i, 0)).collect());
+
+    let mut ids = HashMap::new();
+    ids.insert(start.clone(), 0);
+
+    let mut states = vec![start];
+    let mut transitions = vec![];
+    let mut accepts = vec![];
+
+    let mut curr = 0;
+    while curr < states.len() {
+
+      // positions are sorted, so the first finished pattern is the one listed first in the spec
+      let finished = states[curr].iter().find(|(pattern, step)| *step == patterns[*pattern].len());
+      accepts.push(finished.map(|(pattern, _)| tokens[*pattern].0));
+
+      for column in 0..COLUMNS {
+
+        let mut next = vec![];
+        for (pattern, step) in &states[curr] {
+          if let Some((chars, after, _)) = patterns[*pattern].get(*step) {
+            if chars.matches(column) {
+              next.push((*pattern, *after));
+            }
+          }
+        }
+
+        if next.is_empty() {
+          transitions.push(DEAD);
+          continue;
+        }
+
+        let next = Dfa::closure(&patterns, next);
+
+        let id = match ids.get(&next) {
+          Some(id) => *id,
+          None => {
+            let id = states.len();
+            ids.insert(next.clone(), id);
+            states.push(next);
+            id
+          }
+        };
+
+        transitions.push(id as u16);
+      }
+
+      curr += 1;
+    }
+
+    return Dfa {
+      transitions,
+      accepts,
+      keywords: keywords.iter().cloned().collect()
+    };
+  }
+
+  // the state after reading `ch`, or None if no token continues with it
+  pub fn next(&self, state: usize, ch: char) -> Option<usize> {
+    let column = if ch.is_ascii() { ch as usize } else { COLUMNS - 1 };
+
+    let next = self.transitions[state * COLUMNS + column];
+
+    return if next == DEAD { None } else { Some(next as usize) };
+  }
+
+  pub fn accept(&self, state: usize) -> Option<Token> {
+    return self.accepts[state];
+  }
+
+  // the keyword spelled by a (lowercase) identifier
+  pub fn keyword(&self, chars: &str) -> Option<Token> {
+    return self.keywords.get(chars).cloned();
+  }
+
+  // a pattern as a list of steps
+  fn steps(pattern: &Pattern) -> Vec<Step> {
+    let pieces = match pattern {
+      Pattern::Literal(spelling) => return spelling.chars().enumerate().map(|(i, ch)| (Chars::Char(ch), i + 1, false)).collect(),
+      Pattern::Pieces(pieces) => pieces
+    };
+
+    let mut steps = vec![];
+
+    for piece in pieces.iter() {
+      let i = steps.len();
+
+      match piece {
+        Piece::One(chars) => steps.push((*chars, i + 1, false)),
+        Piece::Many(chars) => steps.push((*chars, i, true)),
+
+        // one digit, then more digits, or an underscore that goes back to needing a digit
+        Piece::Digits(chars) => {
+          steps.push((*chars, i + 1, false));
+          steps.push((*chars, i + 1, true));
+          steps.push((Chars::Char('_'), i, true));
+        }
+      }
+    }
+
+    return steps;
+  }
+
+  // 'many' steps can match nothing, so a position before one is also a position after it
+  fn closure(patterns: &[Vec<Step>], mut items: Vec<Item>) -> Vec<Item> {
+    let mut i = 0;
+    while i < items.len() {
+      let (pattern, step) = items[i];
+
+      if let Some((_, _, true)) = patterns[pattern].get(step) {
+        items.push((pattern, step + 1));
+      }
+
+      i += 1;
+    }
+
+    items.sort();
+    items.dedup();
+
+    return items;
+  }
+
+}
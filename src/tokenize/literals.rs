@@ -0,0 +1,35 @@
+// values of number literals. Underscores between digits are ignored
+
+// hex (0x) and binary (0b) literals, and their digits
+fn radix(chars: &str) -> Option<(u32, &str)> {
+  return match chars.get(..2) {
+    Some("0x") | Some("0X") => Some((16, &chars[2..])),
+    Some("0b") | Some("0B") => Some((2, &chars[2..])),
+    _ => None
+  };
+}
+
+// whether a number literal is a float (has a decimal point or exponent)
+pub fn is_float(chars: &str) -> bool {
+  if radix(chars).is_some() {
+    return false;
+  }
+
+  return chars.contains(&['.', 'e', 'E'][..]);
+}
+
+// the value of an integer literal, or why it couldn't be read (e.g. it doesn't fit in 64 bits)
+pub fn integer_value(chars: &str) -> Result<u64, String> {
+  let chars = chars.replace("_", "");
+
+  let result = match radix(&chars) {
+    Some((radix, digits)) => u64::from_str_radix(digits, radix),
+    None => chars.parse()
+  };
+
+  return result.map_err(|err| err.to_string());
+}
+
+pub fn float_value(chars: &str) -> Result<f64, String> {
+  return chars.replace("_", "").parse().map_err(|err: std::num::ParseFloatError| err.to_string());
+}
@@ -1,6 +1,10 @@
-pub mod lexable;
-
-pub mod state;
 pub mod token;
 
-pub mod char_group;
\ No newline at end of file
+// the declarative description of each token, and the automaton built from it
+pub mod spec;
+pub mod dfa;
+
+// reading the values of literals
+pub mod literals;
+
+pub mod char_group;
@@ -0,0 +1,118 @@
+use crate::tokenize::token::Token;
+
+// a set of characters a pattern can match
+#[derive(Clone, Copy)]
+pub enum Chars {
+  Char(char),
+  Letter,
+  Digit,
+
+  // letters, digits and underscores
+  Word,
+
+  // any of the given characters
+  OneOf(&'static str),
+
+  // anything but the given character
+  Except(char)
+}
+
+// one step of a pattern
+pub enum Piece {
+  One(Chars),
+
+  // zero or more
+  Many(Chars),
+
+  // one or more, with single underscores between them (like `1_000`)
+  Digits(Chars)
+}
+
+pub enum Pattern {
+  // an exact spelling
+  Literal(&'static str),
+  Pieces(&'static [Piece])
+}
+
+use self::Chars::*;
+use self::Piece::*;
+use self::Pattern::*;
+
+const HEX_DIGITS: &str = "0123456789abcdefABCDEF";
+
+// every token the lexer recognizes, and how it is spelled.
+// When two patterns match the same text, the earlier one wins
+pub static TOKENS: &[(Token, Pattern)] = &[
+  (Token::Period, Literal(".")),
+  (Token::Semicolon, Literal(";")),
+  (Token::Colon, Literal(":")),
+  (Token::LParen, Literal("(")),
+  (Token::RParen, Literal(")")),
+  (Token::Comma, Literal(",")),
+  (Token::LBrace, Literal("{")),
+  (Token::RBrace, Literal("}")),
+  (Token::Dash, Literal("-")),
+  (Token::LBracket, Literal("[")),
+  (Token::RBracket, Literal("]")),
+  (Token::Pipe, Literal("|")),
+  (Token::Ampersand, Literal("&")),
+  (Token::Plus, Literal("+")),
+  (Token::LT, Literal("<")),
+  (Token::GT, Literal(">")),
+  (Token::LTE, Literal("<=")),
+  (Token::GTE, Literal(">=")),
+  (Token::EQ, Literal("==")),
+  (Token::NEQ, Literal("!=")),
+  (Token::Asterisk, Literal("*")),
+  (Token::Slash, Literal("/")),
+  (Token::Assign, Literal(":=")),
+
+  // keywords are spelled like identifiers, and are picked out of them using `KEYWORDS`
+  (Token::Identifier, Pieces(&[One(Letter), Many(Word)])),
+
+  // numbers can have underscores between digits, which are ignored. A decimal point needs digits on both sides
+  (Token::Number, Pieces(&[Digits(Digit)])),
+  (Token::Number, Pieces(&[Digits(Digit), One(Char('.')), Digits(Digit)])),
+
+  // exponents (1.5e3, 2e-4)
+  (Token::Number, Pieces(&[Digits(Digit), One(OneOf("eE")), Digits(Digit)])),
+  (Token::Number, Pieces(&[Digits(Digit), One(OneOf("eE")), One(OneOf("+-")), Digits(Digit)])),
+  (Token::Number, Pieces(&[Digits(Digit), One(Char('.')), Digits(Digit), One(OneOf("eE")), Digits(Digit)])),
+  (Token::Number, Pieces(&[Digits(Digit), One(Char('.')), Digits(Digit), One(OneOf("eE")), One(OneOf("+-")), Digits(Digit)])),
+
+  // hex (0x1f) and binary (0b101) integers
+  (Token::Number, Pieces(&[One(Char('0')), One(OneOf("xX")), Digits(OneOf(HEX_DIGITS))])),
+  (Token::Number, Pieces(&[One(Char('0')), One(OneOf("bB")), Digits(OneOf("01"))])),
+
+  (Token::String, Pieces(&[One(Char('"')), Many(Except('"')), One(Char('"'))])),
+
+  (Token::LineComment, Pieces(&[One(Char('/')), One(Char('/')), Many(Except('\n'))])),
+
+  // only the opening of a block comment. They can be nested, which a dfa can't count, so the lexer skips the rest
+  (Token::MultilineComment, Literal("/*"))
+];
+
+// identifiers with these spellings (ignoring case) are keywords
+pub static KEYWORDS: &[(&str, Token)] = &[
+  ("program", Token::ProgramKW),
+  ("begin", Token::BeginKW),
+  ("end", Token::EndKW),
+  ("is", Token::IsKW),
+  ("global", Token::GlobalKW),
+  ("procedure", Token::ProcedureKW),
+  ("variable", Token::VariableKW),
+  ("type", Token::TypeKW),
+  ("integer", Token::IntegerKW),
+  ("float", Token::FloatKW),
+  ("string", Token::StringKW),
+  ("bool", Token::BoolKW),
+  ("enum", Token::EnumKW),
+  ("if", Token::IfKW),
+  ("then", Token::ThenKW),
+  ("else", Token::ElseKW),
+  ("for", Token::ForKW),
+  ("return", Token::ReturnKW),
+  ("not", Token::NotKW),
+  ("true", Token::TrueKW),
+  ("false", Token::FalseKW)
+];
@@ -1,32 +0,0 @@
-// bring this into scope so that token-related utilities can be used
-use crate::tokenize::state::State;
-use crate::tokenize::lexable::Lexable;
-use crate::tokenize::token::Token;
-
-pub struct Asterisk {
-  pub state: Option<State>
-}
-
-impl Lexable for Asterisk {
-  
-  fn start() -> Token {
-    return Token::Asterisk( Asterisk {state: Some(State::new(0))} );
-  }
-  
-  fn next(&mut self, ch: char) {
-    match &mut self.state {
-      Some(state_val) => {
-        match (state_val.label, ch) {
-          (0, '*') => {state_val.to(1, ch).as_accept();},
-          _ => self.state = None
-        }
-      },
-      None => ()
-    }
-  }
-  
-  fn get_state(&self) -> &Option<State> {
-    return &self.state;
-  }
-  
-}
\ No newline at end of file
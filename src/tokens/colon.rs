@@ -1,31 +0,0 @@
-use crate::tokenize::state::State;
-use crate::tokenize::lexable::Lexable;
-use crate::tokenize::token::Token;
-
-pub struct Colon {
-  pub state: Option<State>
-}
-
-impl Lexable for Colon {
-  
-  fn start() -> Token {
-    return Token::Colon( Colon {state: Some(State::new(0))} );
-  }
-  
-  fn next(&mut self, ch: char) {
-    match &mut self.state {
-      Some(state_val) => {
-        match (state_val.label, ch) {
-          (0, ':') => {state_val.to(1, ch).as_accept();},
-          _ => self.state = None
-        }
-      },
-      None => ()
-    }
-  }
-  
-  fn get_state(&self) -> &Option<State> {
-    return &self.state;
-  }
-  
-}
\ No newline at end of file
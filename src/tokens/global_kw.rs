@@ -1,37 +0,0 @@
-use crate::tokenize::state::State;
-use crate::tokenize::lexable::Lexable;
-use crate::tokenize::token::Token;
-
-
-pub struct GlobalKW {
-  pub state: Option<State>
-}
-
-impl Lexable for GlobalKW {
-  
-  fn start() -> Token {
-    return Token::GlobalKW( GlobalKW {state: Some(State::new(0))} );
-  }
-  
-  fn next(&mut self, ch: char) {
-    match &mut self.state {
-      Some(state_val) => {
-        match (state_val.label, ch) {
-          (0, 'g') => {state_val.to(1, ch);},
-          (1, 'l') => {state_val.to(2, ch);},
-          (2, 'o') => {state_val.to(3, ch);},
-          (3, 'b') => {state_val.to(4, ch);},
-          (4, 'a') => {state_val.to(5, ch);},
-          (5, 'l') => {state_val.to(6, ch).as_accept();},
-          _ => self.state = None
-        }
-      },
-      None => ()
-    }
-  }
-  
-  fn get_state(&self) -> &Option<State> {
-    return &self.state;
-  }
-  
-}
\ No newline at end of file
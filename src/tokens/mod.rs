@@ -1,52 +0,0 @@
-// export tokens
-pub mod program_kw;
-pub mod procedure_kw;
-pub mod global_kw;
-pub mod variable_kw;
-pub mod begin_kw;
-pub mod end_kw;
-pub mod is_kw;
-pub mod type_kw;
-pub mod integer_kw;
-pub mod float_kw;
-pub mod string_kw;
-pub mod bool_kw;
-pub mod enum_kw;
-pub mod if_kw;
-pub mod then_kw;
-pub mod else_kw;
-pub mod for_kw;
-pub mod return_kw;
-pub mod not_kw;
-pub mod true_kw;
-pub mod false_kw; //21
-
-pub mod period;
-pub mod semicolon;
-pub mod colon;
-pub mod parens;
-pub mod comma;
-pub mod braces;
-pub mod dash;
-pub mod ampersand;
-pub mod plus;
-pub mod lt;
-pub mod gt;
-pub mod lte;
-pub mod gte;
-pub mod eq;
-pub mod neq;
-pub mod asterisk;
-pub mod slash;
-pub mod brackets;
-pub mod pipe;
-pub mod assign; // 22
-
-pub mod identifier;
-pub mod number;
-pub mod string;
-
-pub mod line_comment;
-pub mod multiline_comment;
-
-pub mod unknown;
\ No newline at end of file
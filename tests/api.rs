@@ -0,0 +1,109 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::Diagnostic;
+use sangzf_compiler::lint::Levels;
+use sangzf_compiler::options::Options;
+
+static CORRECT: &str = "program p is
+variable x : integer;
+begin
+x := 1;
+x := x + 2;
+end program.";
+
+// a type error, found by the checker
+static WRONG_TYPE: &str = "program p is
+variable x : integer;
+begin
+x := \"one\";
+end program.";
+
+// a syntax error, found by the parser
+static MISSING_PAREN: &str = "program p is
+variable x : integer;
+begin
+x := (1 + 2;
+end program.";
+
+#[test]
+fn tokenize_and_lex() {
+  let tokens = sangzf_compiler::tokenize(CORRECT);
+  assert_eq!(tokens.len(), 22);
+  assert_eq!(tokens[0].chars, "program");
+  assert_eq!(tokens[21].chars, ".");
+
+  let (lexed, diagnostics) = sangzf_compiler::lex(CORRECT);
+  assert_eq!(lexed.len(), tokens.len());
+  assert!(diagnostics.list.is_empty());
+
+  // the tokens before the comment are still returned
+  let (lexed, diagnostics) = sangzf_compiler::lex("program p is /* never closed");
+  assert_eq!(lexed.len(), 3);
+
+  match &diagnostics.list[..] {
+    [Diagnostic::ErrUnterminatedComment{line_num: 1}] => (),
+    _ => panic!("expected an unterminated comment")
+  }
+}
+
+#[test]
+fn parse() {
+  let ast = sangzf_compiler::parse(CORRECT);
+  assert!(ast.diagnostics.list.is_empty());
+  assert_eq!(ast.program.unwrap().statements.len(), 2);
+
+  // type errors aren't found by the parser
+  assert!(sangzf_compiler::parse(WRONG_TYPE).diagnostics.list.is_empty());
+
+  match &sangzf_compiler::parse(MISSING_PAREN).diagnostics.list[..] {
+    [Diagnostic::ErrUnexpectedTok{line_num: 4, ..}] => (),
+    _ => panic!("expected a syntax error")
+  }
+}
+
+#[test]
+fn check() {
+  assert!(sangzf_compiler::check(&sangzf_compiler::parse(CORRECT)).list.is_empty());
+
+  match &sangzf_compiler::check(&sangzf_compiler::parse(WRONG_TYPE)).list[..] {
+    [Diagnostic::ErrInvalidType{line_num: 4, ..}] => (),
+    _ => panic!("expected a type error")
+  }
+
+  // syntax errors are included
+  match &sangzf_compiler::check(&sangzf_compiler::parse(MISSING_PAREN)).list[..] {
+    [Diagnostic::ErrUnexpectedTok{line_num: 4, ..}] => (),
+    _ => panic!("expected a syntax error")
+  }
+}
+
+#[test]
+fn diagnose() {
+  assert!(sangzf_compiler::diagnose(CORRECT, &Levels::new()).list.is_empty());
+
+  // the checker's problems come before the lints
+  let diagnostics = sangzf_compiler::diagnose(WRONG_TYPE, &Levels::new());
+  assert!(diagnostics.has_errors());
+
+  match &diagnostics.list[..] {
+    [Diagnostic::ErrInvalidType{..}, Diagnostic::Lint{..}] => (),
+    _ => panic!("expected a type error and a lint")
+  }
+}
+
+#[test]
+fn compile() {
+  let artifact = sangzf_compiler::compile(CORRECT, Options::new());
+  assert_eq!(artifact.name, "p");
+  assert!(artifact.diagnostics.list.is_empty());
+
+  // llvm bitcode starts with 'BC'
+  assert_eq!(&artifact.bitcode.unwrap()[..2], b"BC");
+
+  for program in &[WRONG_TYPE, MISSING_PAREN] {
+    let artifact = sangzf_compiler::compile(program, Options::new());
+
+    assert!(artifact.bitcode.is_none());
+    assert!(artifact.diagnostics.has_errors());
+  }
+}
@@ -0,0 +1,72 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::Diagnostic;
+use sangzf_compiler::tokenize::token::Type;
+
+// check a call to 'f' (declared on line 4) on line 9
+fn check_call(call: &str) -> Vec<Diagnostic> {
+  let program = format!("program p is\nvariable x : integer;\nvariable s : string;\n\
+    procedure f : integer(variable a : integer, variable b : float)\nbegin\nreturn a;\nend procedure;\nbegin\n\
+    x := {};\nx := x;\nend program.", call);
+
+  return sangzf_compiler::check(&sangzf_compiler::parse(&program)).list;
+}
+
+#[test]
+fn matching_call() {
+  assert!(check_call("f(1, 2.5)").is_empty());
+}
+
+#[test]
+fn too_few_arguments() {
+  match &check_call("f(1)")[..] {
+    [Diagnostic::ErrArgumentCount{line_num: 9, name, params, count: 1, decl_line: 4}] => {
+      assert_eq!(name, "f");
+      assert_eq!(params.len(), 2);
+    },
+    _ => panic!("expected an argument count error")
+  }
+}
+
+#[test]
+fn too_many_arguments() {
+  match &check_call("f(1, 2.5, 3)")[..] {
+    [Diagnostic::ErrArgumentCount{line_num: 9, count: 3, decl_line: 4, ..}] => (),
+    _ => panic!("expected an argument count error")
+  }
+}
+
+#[test]
+fn no_arguments() {
+  match &check_call("f()")[..] {
+    [Diagnostic::ErrArgumentCount{count: 0, ..}] => (),
+    _ => panic!("expected an argument count error")
+  }
+}
+
+#[test]
+fn argument_types() {
+  // each mismatched argument is reported
+  match &check_call("f(\"one\", s)")[..] {
+    [Diagnostic::ErrArgumentType{position: 1, expected: Type::Integer, actual: Type::String, decl_line: 4, ..},
+     Diagnostic::ErrArgumentType{position: 2, expected: Type::Float, actual: Type::String, decl_line: 4, ..}] => (),
+    _ => panic!("expected two argument type errors")
+  }
+}
+
+#[test]
+fn builtin_calls() {
+  match &check_call("getinteger(1)")[..] {
+    [Diagnostic::ErrArgumentCount{name, count: 1, decl_line: 0, ..}] => assert_eq!(name, "getinteger"),
+    _ => panic!("expected an argument count error")
+  }
+}
+
+#[test]
+fn count_with_invalid_argument() {
+  // an argument that can't be checked doesn't hide the wrong count
+  match &check_call("f(y)")[..] {
+    [Diagnostic::ErrArgumentCount{count: 1, decl_line: 4, ..}, Diagnostic::ErrSymbolNotFound{name, ..}] => assert_eq!(name, "y"),
+    _ => panic!("expected an argument count error and an undefined symbol")
+  }
+}
@@ -0,0 +1,54 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::lint::{Level, Levels, Lint};
+use sangzf_compiler::options::{Command, Options};
+
+use std::fs;
+
+fn codes(program: &str, levels: &Levels) -> Vec<&'static str> {
+  return sangzf_compiler::diagnose(program, levels).list.iter().map(|diagnostic| diagnostic.code()).collect();
+}
+
+#[test]
+fn reports_every_phase() {
+  let program = "program p is\nvariable x : integer;\nbegin\nx := \"one\";\nx := (1 + 2;\nend program.";
+
+  // the syntax error, the type error and the unused variable ('x' is only assigned)
+  assert_eq!(codes(program, &Levels::new()), vec!["E0102", "E0301", "W0101"]);
+}
+
+#[test]
+fn lint_levels_are_applied() {
+  let program = "program p is\nvariable x : integer;\nbegin\nx := 1.5;\nend program.";
+
+  let mut levels = Levels::new();
+  levels.set(Lint::UnusedVariable, Level::Allow);
+  levels.set(Lint::LossyConversion, Level::Deny);
+
+  let diagnostics = sangzf_compiler::diagnose(program, &levels);
+  assert_eq!(diagnostics.list.iter().map(|diagnostic| diagnostic.code()).collect::<Vec<_>>(), vec!["W0105"]);
+  assert!(diagnostics.has_errors());
+}
+
+#[test]
+fn sample_programs_check() {
+  for entry in fs::read_dir("sample_programs/correct").unwrap() {
+    let path = entry.unwrap().path();
+    let program = fs::read_to_string(&path).unwrap();
+
+    // the same problems the parser and checker find on their own
+    let ast = sangzf_compiler::parse(&program);
+    let mut expected = sangzf_compiler::check(&ast);
+    expected.append(sangzf_compiler::lint(&ast, &Levels::new()));
+
+    assert_eq!(sangzf_compiler::diagnose(&program, &Levels::new()).list.len(), expected.list.len(), "{}", path.display());
+  }
+}
+
+#[test]
+fn check_command_from_args() {
+  let options = Options::from_args(vec![String::from("check"), String::from("p.src")]).ok().unwrap();
+
+  assert!(options.command == Command::Check);
+  assert_eq!(options.program_name, "p.src");
+}
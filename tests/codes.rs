@@ -0,0 +1,64 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::codes::{self, CODES};
+use sangzf_compiler::lint::{Levels, LINTS};
+use sangzf_compiler::options::Options;
+
+// the codes of everything reported for a program, including lints
+fn reported_codes(program: &str) -> Vec<&'static str> {
+  let ast = sangzf_compiler::parse(program);
+
+  let mut diagnostics = sangzf_compiler::check(&ast);
+  diagnostics.append(sangzf_compiler::lint(&ast, &Levels::new()));
+
+  return diagnostics.list.iter().map(|diagnostic| diagnostic.code()).collect();
+}
+
+#[test]
+fn codes_are_unique() {
+  for (i, code) in CODES.iter().enumerate() {
+    assert!(CODES[i + 1..].iter().all(|other| other.code != code.code), "{} is registered twice", code.code);
+  }
+}
+
+#[test]
+fn examples_report_their_code() {
+  for code in CODES.iter().filter(|code| !code.example.is_empty()) {
+    assert!(reported_codes(code.example).contains(&code.code), "the example for {} doesn't report it", code.code);
+  }
+}
+
+#[test]
+fn lints_are_registered() {
+  for lint in LINTS {
+    let code = codes::find(lint.name()).unwrap();
+    assert_eq!(code.code, lint.code());
+  }
+}
+
+#[test]
+fn find_codes() {
+  assert_eq!(codes::find("E0102").unwrap().title, "Unexpected token");
+  assert_eq!(codes::find("e0102").unwrap().code, "E0102");
+  assert_eq!(codes::find("uninitialized").unwrap().code, "W0104");
+  assert!(codes::find("E9999").is_none());
+}
+
+#[test]
+fn explanation_includes_example() {
+  let text = codes::explain(codes::find("E0201").unwrap());
+
+  assert!(text.starts_with("E0201: Undefined symbol\n\n"));
+  assert!(text.contains("For example:\n\n    program p is\n"));
+
+  // internal errors have no example
+  assert!(!codes::explain(codes::find("E0901").unwrap()).contains("For example"));
+}
+
+#[test]
+fn explain_from_args() {
+  let options = Options::from_args(vec![String::from("--explain"), String::from("E0102")]).ok().unwrap();
+  assert_eq!(options.explain, Some(String::from("E0102")));
+
+  assert!(Options::from_args(vec![String::from("--explain")]).is_err());
+}
@@ -0,0 +1,35 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::options::Options;
+
+use std::fs;
+use std::thread;
+
+fn bitcode(program: &str) -> Vec<u8> {
+  return sangzf_compiler::compile(program, Options::new()).bitcode.unwrap();
+}
+
+#[test]
+fn compilations_are_independent() {
+  let program = fs::read_to_string("sample_programs/correct/multipleProcs.src").unwrap();
+  let first = bitcode(&program);
+
+  // nothing is left behind by the first compilation, or by compiling a different program in between
+  bitcode("program q is\nvariable x : integer;\nbegin\nx := 1;\nend program.");
+  assert!(first == bitcode(&program));
+}
+
+#[test]
+fn compilations_on_many_threads() {
+  let program = fs::read_to_string("sample_programs/correct/multipleProcs.src").unwrap();
+  let expected = bitcode(&program);
+
+  let threads: Vec<_> = (0..4).map(|_| {
+    let program = program.clone();
+    thread::spawn(move || bitcode(&program))
+  }).collect();
+
+  for thread in threads {
+    assert!(thread.join().unwrap() == expected);
+  }
+}
@@ -0,0 +1,122 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::cst::{Cst, Node, SyntaxKind, TriviaKind};
+
+use std::fs;
+
+fn cst(program: &str) -> Cst {
+  return sangzf_compiler::parse_lossless(program).1;
+}
+
+// the kinds of every node in the tree, in preorder
+fn kinds(node: &Node) -> Vec<SyntaxKind> {
+  let mut list = vec![node.kind];
+
+  for child in node.nodes() {
+    list.extend(kinds(child));
+  }
+
+  return list;
+}
+
+fn round_trips(program: &str) {
+  assert_eq!(cst(program).text(), program);
+}
+
+#[test]
+fn sample_programs_round_trip() {
+  for entry in fs::read_dir("sample_programs/correct").unwrap() {
+    let path = entry.unwrap().path();
+    let program = fs::read_to_string(&path).unwrap();
+
+    assert_eq!(cst(&program).text(), program, "{}", path.display());
+  }
+}
+
+#[test]
+fn erroneous_programs_round_trip() {
+  round_trips("");
+  round_trips("  // only a comment\n");
+  round_trips("program p is begin x := ; y := (1 + ; end program.");
+  round_trips("program p is\nvariable x : integer\nbegin\n@ # $\nend");
+  round_trips("program p is begin end program. trailing tokens /* and an unterminated comment");
+  round_trips("PROGRAM P IS BEGIN X := \"Mixed Case\"; END PROGRAM.");
+}
+
+#[test]
+fn ast_matches_parse() {
+  let program = "program p is begin x := ; end program. extra";
+
+  let (ast, _) = sangzf_compiler::parse_lossless(program);
+  let expected = sangzf_compiler::parse(program);
+
+  assert_eq!(ast.diagnostics.list.len(), expected.diagnostics.list.len());
+  assert_eq!(ast.program.is_some(), expected.program.is_some());
+}
+
+#[test]
+fn tokens_keep_original_text() {
+  let tree = cst("PROGRAM Test IS BEGIN END PROGRAM.");
+
+  let texts: Vec<&str> = tree.root.tokens().iter().map(|token| &token.text[..]).collect();
+  assert_eq!(texts, vec!["PROGRAM", "Test", "IS", "BEGIN", "END", "PROGRAM", "."]);
+}
+
+#[test]
+fn trivia_is_attached_to_following_token() {
+  let tree = cst("program p is // header\n/* a /* nested */ comment */ begin end program. // done\n");
+  let tokens = tree.root.tokens();
+
+  let begin = tokens.iter().find(|token| token.text == "begin").unwrap();
+  let leading: Vec<(TriviaKind, &str)> = begin.leading.iter().map(|trivia| (trivia.kind, &trivia.text[..])).collect();
+
+  assert_eq!(leading, vec![
+    (TriviaKind::Whitespace, " "),
+    (TriviaKind::LineComment, "// header"),
+    (TriviaKind::Whitespace, "\n"),
+    (TriviaKind::BlockComment, "/* a /* nested */ comment */"),
+    (TriviaKind::Whitespace, " ")
+  ]);
+
+  let trailing: Vec<TriviaKind> = tree.trailing.iter().map(|trivia| trivia.kind).collect();
+  assert_eq!(trailing, vec![TriviaKind::Whitespace, TriviaKind::LineComment, TriviaKind::Whitespace]);
+}
+
+#[test]
+fn structure_follows_parse_rules() {
+  let tree = cst("program p is\nvariable x : integer;\nbegin\nx := 1 + 2;\nend program.");
+
+  assert_eq!(kinds(&tree.root), vec![
+    SyntaxKind::File,
+    SyntaxKind::Program,
+    SyntaxKind::ProgramHeader,
+    SyntaxKind::ProgramBody,
+    SyntaxKind::Declaration,
+    SyntaxKind::VariableDeclaration,
+    SyntaxKind::Statement,
+    SyntaxKind::AssignmentStatement,
+    SyntaxKind::Expression
+  ]);
+}
+
+#[test]
+fn procedures_and_calls() {
+  let tree = cst("program p is\nprocedure f : integer(variable a : integer, variable b : float)\nbegin\nreturn a;\nend procedure;\nbegin\nx := f(1, 2.0);\nend program.");
+  let kinds = kinds(&tree.root);
+
+  assert!(kinds.contains(&SyntaxKind::ProcedureHeader));
+  assert!(kinds.contains(&SyntaxKind::ProcedureBody));
+  assert!(kinds.contains(&SyntaxKind::ReturnStatement));
+  assert!(kinds.contains(&SyntaxKind::ArgumentList));
+  assert_eq!(kinds.iter().filter(|kind| **kind == SyntaxKind::Parameter).count(), 2);
+}
+
+#[test]
+fn skipped_tokens_stay_in_their_statement() {
+  let tree = cst("program p is begin x := ) ) ; end program.");
+  let body = tree.root.nodes()[0].nodes()[1];
+  let statement = body.nodes()[0];
+
+  assert_eq!(statement.kind, SyntaxKind::Statement);
+  assert_eq!(statement.text(), " x := ) ) ;");
+}
@@ -0,0 +1,68 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::dump;
+use sangzf_compiler::index::Index;
+use sangzf_compiler::json;
+use sangzf_compiler::options::Format;
+
+const PROGRAM: &str = "program p is
+global variable total : integer;
+variable count : integer;
+procedure add : integer(variable x : integer)
+  variable step : integer;
+  procedure inner : bool(variable y : float)
+  begin
+    return true;
+  end procedure;
+begin
+  step := x;
+  return step + total;
+end procedure;
+begin
+  count := add(1);
+end program.";
+
+fn index() -> Index {
+  return Index::new(&sangzf_compiler::parse(PROGRAM));
+}
+
+#[test]
+fn symtab_text() {
+  // skip the builtins
+  let text = dump::symtab(&index(), Format::Text);
+  let lines: Vec<&str> = text.lines().filter(|line| !line.contains("builtin")).collect();
+
+  assert_eq!(lines, vec![
+    "global:",
+    "  total            variable   integer                          2:17",
+    "  program:",
+    "    count            variable   integer                          3:10",
+    "    add              procedure  procedure(integer,) -> integer   4:11",
+    "    procedure add:",
+    "      x                parameter  integer                          4:34",
+    "      step             variable   integer                          5:12",
+    "      inner            procedure  procedure(float,) -> bool        6:13",
+    "      procedure inner:",
+    "        y                parameter  float                            6:35"
+  ]);
+}
+
+#[test]
+fn symtab_json() {
+  let global = json::parse(&dump::symtab(&index(), Format::Json)).unwrap();
+  assert_eq!(global.get("kind").as_str(), Some("global"));
+  assert_eq!(global.get("symbols").as_array()[0].get("kind").as_str(), Some("builtin"));
+
+  let program = &global.get("tables").as_array()[0];
+  assert_eq!(program.get("kind").as_str(), Some("program"));
+
+  let add = &program.get("tables").as_array()[0];
+  assert_eq!(add.get("name").as_str(), Some("add"));
+
+  let names: Vec<&str> = add.get("symbols").as_array().iter().map(|symbol| symbol.get("name").as_str().unwrap()).collect();
+  assert_eq!(names, vec!["x", "step", "inner"]);
+
+  let y = &add.get("tables").as_array()[0].get("symbols").as_array()[0];
+  assert_eq!(y.get("type").as_str(), Some("float"));
+  assert_eq!(y.get("line").as_u64(), Some(6));
+}
@@ -0,0 +1,107 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::emit;
+use sangzf_compiler::json;
+use sangzf_compiler::lint::Levels;
+use sangzf_compiler::options::Format;
+
+const PROGRAM: &str = "program P is\n/* comment */ begin\n  x := 1.5;\nend program.";
+
+#[test]
+fn emit_tokens_text() {
+  let (tokens, _) = sangzf_compiler::lex(PROGRAM);
+
+  assert_eq!(emit::tokens(&tokens, Format::Text), "\
+1:1      program        \"program\"                _        0..7
+1:9      <identifier>   \"p\"                      _        8..9
+1:11     is             \"is\"                     _        10..12
+2:15     begin          \"begin\"                  _        27..32
+3:3      <identifier>   \"x\"                      _        35..36
+3:5      :=             \":=\"                     _        37..39
+3:8      <number>       \"1.5\"                    float    40..43
+3:11     ;              \";\"                      _        43..44
+4:1      end            \"end\"                    _        45..48
+4:5      program        \"program\"                _        49..56
+4:12     .              \".\"                      _        56..57
+");
+}
+
+#[test]
+fn emit_tokens_json() {
+  let (tokens, _) = sangzf_compiler::lex("x := \"a\tb\";");
+
+  assert_eq!(emit::tokens(&tokens, Format::Json), concat!(
+    "[{\"kind\":\"<identifier>\",\"text\":\"x\",\"type\":null,\"line\":1,\"column\":1,\"start\":0,\"end\":1},",
+    "{\"kind\":\":=\",\"text\":\":=\",\"type\":null,\"line\":1,\"column\":3,\"start\":2,\"end\":4},",
+    "{\"kind\":\"<string>\",\"text\":\"\\\"a\\tb\\\"\",\"type\":\"string\",\"line\":1,\"column\":6,\"start\":5,\"end\":10},",
+    "{\"kind\":\";\",\"text\":\";\",\"type\":null,\"line\":1,\"column\":11,\"start\":10,\"end\":11}]\n"
+  ));
+}
+
+#[test]
+fn emit_no_tokens_json() {
+  let (tokens, _) = sangzf_compiler::lex("// only a comment");
+
+  assert_eq!(emit::tokens(&tokens, Format::Json), "[]\n");
+}
+
+#[test]
+fn columns_count_characters() {
+  let (tokens, _) = sangzf_compiler::lex("\"é\" x");
+
+  assert_eq!(tokens[1].span.column, 5);
+  assert_eq!(tokens[1].span.start, 5);
+}
+
+const SYMBOLS: &str = "program p is
+global variable total : integer;
+procedure add : integer(variable x : integer)
+begin
+return x + total;
+end procedure;
+begin
+total := add(1);
+end program.";
+
+#[test]
+fn emit_symbols_text() {
+  let (index, diagnostics) = sangzf_compiler::symbols(SYMBOLS, &Levels::new());
+  assert!(diagnostics.list.is_empty());
+
+  // the builtins come first
+  let text = emit::symbols(&index, Format::Text);
+  let lines: Vec<&str> = text.lines().skip(7).collect();
+
+  assert_eq!(lines, vec![
+    "6    2:17     29..34     variable   total            global       integer",
+    "7    3:11     56..59     procedure  add              program      procedure(integer,) -> integer",
+    "8    3:34     79..80     parameter  x                add          integer",
+    "references:",
+    "5:8      105..106   8    parameter  x",
+    "5:12     109..114   6    variable   total",
+    "8:1      137..142   6    variable   total",
+    "8:10     146..149   7    procedure  add"
+  ]);
+}
+
+#[test]
+fn emit_symbols_json() {
+  let (index, _) = sangzf_compiler::symbols(SYMBOLS, &Levels::new());
+  let output = json::parse(&emit::symbols(&index, Format::Json)).unwrap();
+
+  let symbols = output.get("symbols").as_array();
+  assert_eq!(symbols[0].get("kind").as_str(), Some("builtin"));
+  assert!(symbols[0].get("line").is_null());
+
+  let x = &symbols[8];
+  assert_eq!(x.get("name").as_str(), Some("x"));
+  assert_eq!(x.get("scope").as_str(), Some("add"));
+  assert_eq!(x.get("type").as_str(), Some("integer"));
+  assert_eq!(x.get("start").as_u64(), Some(79));
+
+  // each reference names the symbol it resolves to
+  let references = output.get("references").as_array();
+  assert_eq!(references.iter().map(|reference| reference.get("symbol").as_u64().unwrap()).collect::<Vec<_>>(), vec![8, 6, 6, 7]);
+  assert_eq!(references[3].get("kind").as_str(), Some("procedure"));
+  assert_eq!(references[3].get("line").as_u64(), Some(8));
+}
@@ -0,0 +1,99 @@
+extern crate sangzf_compiler;
+
+use std::fs;
+
+fn format(program: &str) -> String {
+  match sangzf_compiler::format(program) {
+    Ok(formatted) => return formatted,
+    Err(_) => panic!("'{}' has syntax errors", program)
+  }
+}
+
+// the tokens of a program, ignoring the case of keywords and identifiers
+fn tokens(program: &str) -> Vec<String> {
+  return sangzf_compiler::tokenize(program).into_iter().map(|token| token.chars.to_lowercase()).collect();
+}
+
+#[test]
+fn canonical_style() {
+  let program = "PROGRAM Test IS\n\
+    GLOBAL VARIABLE x:INTEGER; variable Arr : integer[ -3 ];\n\
+    procedure f:integer ( variable a:integer,variable b:float )\n\
+    variable y : integer;\n\
+    begin\n\
+    IF(a<-1)THEN return -a; ELSE y:=Arr[a]+f(a,b);end if;\n\
+    return y;\n\
+    END PROCEDURE;\n\
+    begin\n\
+    for(x:=0;x<10) x := x+1; end for;\n\
+    end program.";
+
+  assert_eq!(format(program), "\
+program Test is
+global variable x : integer;
+variable Arr : integer[-3];
+procedure f : integer(variable a : integer, variable b : float)
+  variable y : integer;
+begin
+  if (a < -1) then
+    return -a;
+  else
+    y := Arr[a] + f(a, b);
+  end if;
+  return y;
+end procedure;
+begin
+  for (x := 0; x < 10)
+    x := x + 1;
+  end for;
+end program.
+");
+}
+
+#[test]
+fn comments_are_kept() {
+  let program = "// header\nprogram p is // after the header\n\n\n/* before\n   begin */ begin\nx := 1; // same line\n  // own line\nend program. // done";
+
+  assert_eq!(format(program), "\
+// header
+program p is // after the header
+
+/* before
+   begin */
+begin
+  x := 1; // same line
+  // own line
+end program. // done
+");
+}
+
+#[test]
+fn comment_inside_statement() {
+  assert_eq!(format("program p is begin x := 1 + // one\n2; end program."), "\
+program p is
+begin
+  x := 1 + // one
+    2;
+end program.
+");
+}
+
+#[test]
+fn sample_programs_are_formatted_consistently() {
+  for entry in fs::read_dir("sample_programs/correct").unwrap() {
+    let path = entry.unwrap().path();
+    let program = fs::read_to_string(&path).unwrap();
+
+    if let Ok(formatted) = sangzf_compiler::format(&program) {
+      // formatting only changes the layout, and formatting again changes nothing
+      assert_eq!(tokens(&formatted), tokens(&program), "{}", path.display());
+      assert_eq!(format(&formatted), formatted, "{}", path.display());
+    }
+  }
+}
+
+#[test]
+fn syntax_errors_are_not_formatted() {
+  assert!(sangzf_compiler::format("program p is begin x := ; end program.").is_err());
+  assert!(sangzf_compiler::format("program p is begin end program. /* unterminated").is_err());
+}
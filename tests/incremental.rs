@@ -0,0 +1,177 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::checker::Checker;
+use sangzf_compiler::incremental::{Document, Edit, Reuse};
+
+use std::fs;
+
+static PROGRAM: &str = "program p is
+global variable total : integer;
+variable count : integer;
+procedure add : integer(variable x : integer)
+variable step : integer;
+begin
+step := x + 1;
+return step;
+end procedure;
+procedure double : integer(variable x : integer)
+begin
+return x * 2;
+end procedure;
+begin
+count := add(1);
+total := double(count);
+end program.";
+
+// everything found in a document: its tokens, problems and symbols
+fn summary(document: &Document) -> String {
+  let mut text = String::new();
+
+  for token in &document.tokens {
+    text.push_str(&format!("{:?} {} {} {:?}\n", token.tok_type, token.chars, token.line_num, token.span));
+  }
+
+  for diagnostic in &Checker::new().check(&document.ast).list {
+    text.push_str(&format!("{}\n", diagnostic.message()));
+  }
+
+  for symbol in &document.index.symbols {
+    text.push_str(&format!("{} {} {:?} {:?} {:?}\n", symbol.name, symbol.line_num, symbol.span, symbol.procedure, symbol.lines));
+  }
+
+  for reference in &document.index.references {
+    text.push_str(&format!("{} {} {:?}\n", reference.symbol, reference.line_num, reference.span));
+  }
+
+  return text;
+}
+
+fn edit(document: &mut Document, start: usize, end: usize, text: &str) {
+  document.edit(&Edit { range: start..end, text: String::from(text) });
+  assert_eq!(summary(document), summary(&Document::new(document.text.clone())), "after replacing {}..{} with {:?}", start, end, text);
+}
+
+// replace the first occurrence of `old` after `after`
+fn replace(document: &mut Document, after: &str, old: &str, new: &str) {
+  let after = document.text.find(after).unwrap();
+  let start = after + document.text[after..].find(old).unwrap();
+
+  edit(document, start, start + old.len(), new);
+}
+
+#[test]
+fn edits_in_a_procedure_are_reparsed_alone() {
+  let mut document = Document::new(String::from(PROGRAM));
+
+  replace(&mut document, "step := x + ", "1", "10");
+  assert_eq!(document.reuse, Reuse { relexed: 1, reparsed: Some(1), symbols: true });
+
+  // new lines move everything after the procedure
+  replace(&mut document, "return step", ";", ";\nstep := step;\n");
+  assert_eq!(document.reuse.reparsed, Some(1));
+  assert!(document.reuse.symbols);
+}
+
+#[test]
+fn changed_declarations_are_indexed_again() {
+  let mut document = Document::new(String::from(PROGRAM));
+
+  // renaming a variable changes the symbols, so the whole index is built again (the tree is still kept)
+  replace(&mut document, "variable step", "step", "stride");
+  assert_eq!(document.reuse.reparsed, Some(1));
+  assert!(!document.reuse.symbols);
+}
+
+#[test]
+fn edits_in_the_body_are_parsed_again() {
+  let mut document = Document::new(String::from(PROGRAM));
+
+  replace(&mut document, "count := ", "add(1)", "add(2)");
+  assert_eq!(document.reuse.reparsed, None);
+}
+
+#[test]
+fn whitespace_edits_relex_nothing() {
+  let mut document = Document::new(String::from(PROGRAM));
+
+  // the lexer looked at the newline after the semicolon, but not past it
+  let start = document.text.find("return step").unwrap();
+  edit(&mut document, start, start, "\n");
+  assert_eq!(document.reuse, Reuse { relexed: 0, reparsed: Some(1), symbols: true });
+}
+
+#[test]
+fn syntax_errors_are_reported() {
+  let mut document = Document::new(String::from(PROGRAM));
+
+  replace(&mut document, "step := ", "x + 1", "x +");
+  assert_eq!(document.ast.diagnostics.list.len(), 1);
+
+  replace(&mut document, "step := ", "x +", "x + 1");
+  assert!(document.ast.diagnostics.list.is_empty());
+
+  // an unclosed comment hides the rest of the program, until it is closed
+  replace(&mut document, "return step", ";", "; /*");
+  replace(&mut document, "return step", "/*", "/* */");
+  assert!(document.ast.diagnostics.list.is_empty());
+}
+
+// a small random number generator, so the edits are the same every run
+struct Random(u64);
+
+impl Random {
+  fn next(&mut self, bound: usize) -> usize {
+    self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    return ((self.0 >> 33) as usize) % bound;
+  }
+}
+
+#[test]
+fn random_edits_match_a_full_parse() {
+  let snippets = [" ", "\n", "x", "1", ";", ":= 2;", "(", ")", "end", "begin", "procedure", "/*", "*/", "//", "\"", "e5", ".", "step := 1;\n"];
+  let mut random = Random(7);
+
+  let mut programs = vec![String::from(PROGRAM)];
+  for entry in fs::read_dir("sample_programs/correct").unwrap() {
+    programs.push(fs::read_to_string(entry.unwrap().path()).unwrap());
+  }
+
+  // an edit that ends the declarations, so the body's missing 'begin' is no longer hidden by an error there
+  let mut document = Document::new(String::from("program p is\nprocedure f : integer()\nbegin\nreturn 1;\neocedure;\nbegin\nend program."));
+  replace(&mut document, "eoce", "dure;\nbegi", "end procedure;\n");
+  assert_eq!(document.reuse.reparsed, None);
+
+  for program in programs {
+    let mut document = Document::new(program);
+
+    for _ in 0..100 {
+      // some edits are around where the declarations end and the body begins
+      let mut start = match (random.next(4), document.text.rfind("begin")) {
+        (0, Some(body)) => (body + random.next(24)).saturating_sub(12).min(document.text.len()),
+        _ => random.next(document.text.len() + 1)
+      };
+      while !document.text.is_char_boundary(start) {
+        start -= 1;
+      }
+
+      let mut end = (start + random.next(4)).min(document.text.len());
+      while !document.text.is_char_boundary(end) {
+        end += 1;
+      }
+
+      // mostly insertions, and some deletions
+      let text = if random.next(3) == 0 { "" } else { snippets[random.next(snippets.len())] };
+      let before = document.text.clone();
+
+      document.edit(&Edit { range: start..end, text: String::from(text) });
+      assert_eq!(summary(&document), summary(&Document::new(document.text.clone())), "replacing {:?} with {:?} in:\n{}", &before[start..end], text, before);
+
+      // most edits are undone, so that most start from a program without errors
+      if random.next(4) != 0 {
+        document.edit(&Edit { range: start..start + text.len(), text: String::from(&before[start..end]) });
+        assert_eq!(document.text, before);
+        assert_eq!(summary(&document), summary(&Document::new(before.clone())), "undoing {:?} in:\n{}", text, before);
+      }
+    }
+  }
+}
@@ -0,0 +1,89 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::index::{Index, SymbolKind};
+
+static PROGRAM: &str = "program p is
+global variable total : integer;
+variable count : integer;
+procedure add : integer(variable count : integer)
+variable step : integer;
+begin
+step := count;
+return add(step) + total;
+end procedure;
+begin
+count := add(1);
+total := count;
+end program.";
+
+fn index() -> Index {
+  return Index::new(&sangzf_compiler::parse(PROGRAM));
+}
+
+// the offset of the nth occurrence of a name
+fn offset(name: &str, nth: usize) -> usize {
+  return PROGRAM.match_indices(name).nth(nth).unwrap().0;
+}
+
+fn names(index: &Index, symbols: &[usize]) -> Vec<String> {
+  return symbols.iter().map(|&symbol| index.symbols[symbol].name.clone()).collect();
+}
+
+#[test]
+fn declarations_are_indexed() {
+  let index = index();
+  let declared: Vec<(&str, SymbolKind)> = index.symbols.iter()
+    .filter(|symbol| symbol.kind != SymbolKind::Builtin)
+    .map(|symbol| (&symbol.name[..], symbol.kind))
+    .collect();
+
+  assert_eq!(declared, vec![
+    ("total", SymbolKind::Variable),
+    ("count", SymbolKind::Variable),
+    ("add", SymbolKind::Procedure),
+    ("count", SymbolKind::Parameter),
+    ("step", SymbolKind::Variable)
+  ]);
+}
+
+#[test]
+fn references_resolve_like_the_checker() {
+  let index = index();
+
+  // inside 'add', 'count' is the parameter. Outside it, the program's variable
+  let parameter = index.symbol_at(offset("count", 2)).unwrap();
+  assert_eq!(index.symbols[parameter].kind, SymbolKind::Parameter);
+
+  let variable = index.symbol_at(offset("count", 3)).unwrap();
+  assert_eq!(index.symbols[variable].kind, SymbolKind::Variable);
+  assert_eq!(index.references_to(variable).len(), 2);
+
+  // the recursive call, and the call from the program's body
+  let add = index.symbol_at(offset("add", 1)).unwrap();
+  assert_eq!(index.references_to(add).iter().map(|reference| reference.line_num).collect::<Vec<_>>(), vec![8, 11]);
+}
+
+#[test]
+fn symbol_at_the_end_of_a_name() {
+  let index = index();
+  let end = offset("step", 1) + "step".len();
+
+  assert_eq!(index.symbol_at(end), index.symbol_at(offset("step", 0)));
+  assert_eq!(index.symbol_at(offset("begin", 0)), None);
+}
+
+#[test]
+fn visible_symbols() {
+  let index = index();
+
+  // inside 'add', the parameter hides the program's 'count', and globals and the procedure itself are visible
+  let inside = names(&index, &index.visible_at(7));
+  assert!(inside.contains(&String::from("step")) && inside.contains(&String::from("total")) && inside.contains(&String::from("add")));
+  assert_eq!(inside.iter().filter(|name| *name == "count").count(), 1);
+  assert_eq!(index.symbols[*index.visible_at(7).iter().find(|&&symbol| index.symbols[symbol].name == "count").unwrap()].kind, SymbolKind::Parameter);
+
+  // outside it, its locals aren't
+  let outside = names(&index, &index.visible_at(11));
+  assert!(!outside.contains(&String::from("step")));
+  assert!(outside.contains(&String::from("putinteger")));
+}
@@ -0,0 +1,157 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::Diagnostic;
+use sangzf_compiler::lexer::Lexer;
+use sangzf_compiler::tokenize::token::TokenEntry;
+
+// lex a program, returning its tokens and the lines of any unterminated comments
+fn lex(program: &str) -> (Vec<TokenEntry>, Vec<u32>) {
+  let mut lexer = Lexer::new(program);
+  let tokens = lexer.by_ref().collect();
+
+  let unterminated = lexer.diagnostics.list.iter().filter_map(|diagnostic| match diagnostic {
+    Diagnostic::ErrUnterminatedComment{line_num} => Some(*line_num),
+    _ => None
+  }).collect();
+
+  return (tokens, unterminated);
+}
+
+fn chars(tokens: &[TokenEntry]) -> Vec<&str> {
+  return tokens.iter().map(|token| &token.chars[..]).collect();
+}
+
+#[test]
+fn block_comment_is_skipped() {
+  let (tokens, unterminated) = lex("a /* b */ c");
+
+  assert_eq!(chars(&tokens), vec!["a", "c"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn empty_block_comment() {
+  let (tokens, unterminated) = lex("a /**/ c");
+
+  assert_eq!(chars(&tokens), vec!["a", "c"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn nested_block_comments() {
+  let (tokens, unterminated) = lex("a /* b /* c */ d /* e /* f */ */ g */ h");
+
+  assert_eq!(chars(&tokens), vec!["a", "h"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn deeply_nested_block_comments() {
+  let depth = 10000;
+  let program = format!("a {} b {} c", "/*".repeat(depth), "*/".repeat(depth));
+
+  let (tokens, unterminated) = lex(&program);
+
+  assert_eq!(chars(&tokens), vec!["a", "c"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn block_comment_counts_lines() {
+  let (tokens, _) = lex("a\n/* b\n/* c\n*/\n*/ d\ne");
+
+  assert_eq!(chars(&tokens), vec!["a", "d", "e"]);
+  assert_eq!(tokens.iter().map(|token| token.line_num).collect::<Vec<u32>>(), vec![1, 5, 6]);
+}
+
+#[test]
+fn unterminated_block_comment_reports_opening_line() {
+  let (tokens, unterminated) = lex("a\nb /* never\nclosed");
+
+  assert_eq!(chars(&tokens), vec!["a", "b"]);
+  assert_eq!(unterminated, vec![2]);
+}
+
+#[test]
+fn unterminated_nested_block_comment_reports_outer_opening() {
+  let (tokens, unterminated) = lex("a\n/* outer\n/* inner */\n");
+
+  assert_eq!(chars(&tokens), vec!["a"]);
+  assert_eq!(unterminated, vec![2]);
+}
+
+#[test]
+fn slash_after_opening_does_not_close() {
+  // the '*' of the opening can't be reused to close the comment
+  let (tokens, unterminated) = lex("a /*/");
+
+  assert_eq!(chars(&tokens), vec!["a"]);
+  assert_eq!(unterminated, vec![1]);
+}
+
+#[test]
+fn slash_after_opening_then_close() {
+  let (tokens, unterminated) = lex("a /*/ b */ c");
+
+  assert_eq!(chars(&tokens), vec!["a", "c"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn stars_before_close() {
+  let (tokens, unterminated) = lex("a /*** b ***/ c");
+
+  assert_eq!(chars(&tokens), vec!["a", "c"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn close_without_opening() {
+  let (tokens, unterminated) = lex("a **/ b");
+
+  assert_eq!(chars(&tokens), vec!["a", "*", "*", "/", "b"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn opening_inside_line_comment() {
+  let (tokens, unterminated) = lex("a // /* not a block comment\nb");
+
+  assert_eq!(chars(&tokens), vec!["a", "b"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn line_comment_inside_block_comment() {
+  // the block comment ends at '*/' even though it is after '//'
+  let (tokens, unterminated) = lex("a /* // */ b");
+
+  assert_eq!(chars(&tokens), vec!["a", "b"]);
+  assert!(unterminated.is_empty());
+}
+
+#[test]
+fn unterminated_comment_is_reported_by_parser() {
+  let ast = sangzf_compiler::parse("program p is\nbegin\nend program.\n/* trailing");
+
+  match ast.diagnostics.list.first() {
+    Some(Diagnostic::ErrUnterminatedComment{line_num}) => assert_eq!(*line_num, 4),
+    _ => panic!("expected an unterminated comment diagnostic first")
+  }
+}
+
+#[test]
+fn unrecognized_characters() {
+  let mut lexer = Lexer::new("a $\nb # c");
+  let tokens: Vec<TokenEntry> = lexer.by_ref().collect();
+
+  // each one is still a token, so the parser can report what it was in
+  assert_eq!(chars(&tokens), vec!["a", "$", "b", "#", "c"]);
+
+  match &lexer.diagnostics.list[..] {
+    [Diagnostic::ErrUnrecognizedChar{line_num: 1, chars: first}, Diagnostic::ErrUnrecognizedChar{line_num: 2, chars: second}] => {
+      assert_eq!((&first[..], &second[..]), ("$", "#"));
+    },
+    _ => panic!("expected two unrecognized characters")
+  }
+}
@@ -0,0 +1,178 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::{Diagnostic, Diagnostics};
+use sangzf_compiler::lint::{Level, Levels, Lint};
+use sangzf_compiler::options::Options;
+
+fn lint_with(program: &str, levels: &Levels) -> Diagnostics {
+  return sangzf_compiler::lint(&sangzf_compiler::parse(program), levels);
+}
+
+// the lints reported for a program, with the line and name of each
+fn lints(program: &str) -> Vec<(Lint, u32, String)> {
+  return lint_with(program, &Levels::new()).list.iter().filter_map(|diagnostic| match diagnostic {
+    Diagnostic::Lint{lint, line_num, name, ..} => Some((*lint, *line_num, name.clone())),
+    _ => None
+  }).collect();
+}
+
+fn lint(lint: Lint, line_num: u32, name: &str) -> (Lint, u32, String) {
+  return (lint, line_num, String::from(name));
+}
+
+#[test]
+fn unused_variables() {
+  let program = "program p is\nvariable a : integer;\nvariable b : integer;\nbegin\na := 1;\nb := a;\nend program.";
+
+  // 'b' is only assigned to
+  assert_eq!(lints(program), vec![lint(Lint::UnusedVariable, 3, "b")]);
+}
+
+#[test]
+fn unused_parameters_and_procedures() {
+  let program = "program p is\n\
+    procedure f : integer(variable a : integer, variable b : integer)\n\
+    begin\nreturn a;\nend procedure;\n\
+    procedure g : integer()\n\
+    begin\nreturn g();\nend procedure;\n\
+    variable x : integer;\n\
+    begin\nx := f(1, 2);\nx := x + 1;\nend program.";
+
+  // calling itself doesn't make 'g' used
+  assert_eq!(lints(program), vec![
+    lint(Lint::UnusedParameter, 2, "b"),
+    lint(Lint::UnusedProcedure, 6, "g")
+  ]);
+}
+
+#[test]
+fn builtins_are_not_reported() {
+  assert!(lints("program p is\nbegin\nend program.").is_empty());
+}
+
+#[test]
+fn read_before_assignment() {
+  let program = "program p is\nvariable a : integer;\nvariable b : integer;\nbegin\nb := a + 1;\na := b;\nb := a;\nend program.";
+
+  assert_eq!(lints(program), vec![lint(Lint::Uninitialized, 5, "a")]);
+}
+
+#[test]
+fn assigned_in_both_branches() {
+  let program = "program p is\nvariable a : integer;\nvariable b : integer;\nbegin\n\
+    if (true) then a := 1; else a := 2; end if;\nb := a;\nb := b;\nend program.";
+
+  assert!(lints(program).is_empty());
+}
+
+#[test]
+fn assigned_in_one_branch() {
+  let program = "program p is\nvariable a : integer;\nvariable b : integer;\nbegin\n\
+    if (true) then a := 1; end if;\nb := a;\nb := b;\nend program.";
+
+  assert_eq!(lints(program), vec![lint(Lint::Uninitialized, 6, "a")]);
+}
+
+#[test]
+fn branch_that_returns() {
+  let program = "program p is\n\
+    procedure f : integer(variable c : bool)\nvariable a : integer;\nbegin\n\
+    if (c) then return 0; else a := 1; end if;\nreturn a;\nend procedure;\n\
+    variable x : integer;\nbegin\nx := f(true);\nx := x;\nend program.";
+
+  assert!(lints(program).is_empty());
+}
+
+#[test]
+fn assigned_in_loop() {
+  let program = "program p is\nvariable i : integer;\nvariable a : integer;\nbegin\n\
+    for (i := 0; i < 10) a := i; i := i + 1; end for;\ni := a;\nend program.";
+
+  // the loop's first assignment always runs, but its body may not
+  assert_eq!(lints(program), vec![lint(Lint::Uninitialized, 6, "a")]);
+}
+
+#[test]
+fn globals_and_arrays_are_not_tracked() {
+  let program = "program p is\nglobal variable g : integer;\nvariable arr : integer[2];\nvariable x : integer;\nbegin\n\
+    x := g + arr[0];\nx := x;\nend program.";
+
+  assert!(lints(program).is_empty());
+}
+
+#[test]
+fn reported_once() {
+  let program = "program p is\nvariable a : integer;\nvariable b : integer;\nbegin\nb := a;\nb := a + b;\nend program.";
+
+  assert_eq!(lints(program), vec![lint(Lint::Uninitialized, 5, "a")]);
+}
+
+#[test]
+fn allowed_and_denied_lints() {
+  let program = "program p is\nvariable a : integer;\nbegin\nend program.";
+
+  let mut levels = Levels::new();
+  levels.set(Lint::UnusedVariable, Level::Allow);
+  assert!(lint_with(program, &levels).list.is_empty());
+
+  // lints are warnings unless they are denied
+  assert!(!lint_with(program, &Levels::new()).has_errors());
+
+  levels.set(Lint::UnusedVariable, Level::Deny);
+  assert!(lint_with(program, &levels).has_errors());
+}
+
+#[test]
+fn lint_levels_from_args() {
+  let args = vec![String::from("--deny=uninitialized"), String::from("--allow=unused-variable"), String::from("p.src")];
+
+  let options = Options::from_args(args).ok().unwrap();
+  assert_eq!(options.lints.get(Lint::Uninitialized), Level::Deny);
+  assert_eq!(options.lints.get(Lint::UnusedVariable), Level::Allow);
+  assert_eq!(options.lints.get(Lint::UnusedProcedure), Level::Warn);
+
+  assert!(Options::from_args(vec![String::from("--deny=unused")]).is_err());
+}
+
+// the line and types of each lossy conversion the checker reports
+fn conversions(statements: &str) -> Vec<(u32, String)> {
+  let program = format!("program p is\nvariable i : integer;\nvariable b : bool;\nvariable f : float;\nvariable arr : integer[2];\n\
+    procedure g : bool(variable x : integer)\nbegin\nreturn x;\nend procedure;\nbegin\n{}\nend program.", statements);
+
+  return sangzf_compiler::check(&sangzf_compiler::parse(&program)).list.iter().filter_map(|diagnostic| match diagnostic {
+    Diagnostic::Lint{lint: Lint::LossyConversion, line_num, name, ..} => Some((*line_num, name.clone())),
+    _ => None
+  }).collect();
+}
+
+#[test]
+fn lossy_conversions() {
+  // the return in 'g' converts an integer to a bool
+  assert_eq!(conversions("i := f;\nb := i;\narr[0] := f;\nb := g(f);"), vec![
+    (8, String::from("from 'integer' to 'bool'")),
+    (11, String::from("from 'float' to 'integer'")),
+    (12, String::from("from 'integer' to 'bool'")),
+    (13, String::from("from 'float' to 'integer'")),
+    (14, String::from("from 'float' to 'integer'"))
+  ]);
+}
+
+#[test]
+fn widening_conversions_are_not_lossy() {
+  // only the return in 'g'
+  assert_eq!(conversions("f := i;\ni := b;\nf := arr[0];"), vec![(8, String::from("from 'integer' to 'bool'"))]);
+}
+
+#[test]
+fn lossy_conversions_can_be_denied() {
+  let ast = sangzf_compiler::parse("program p is\nvariable i : integer;\nbegin\ni := 1.5;\nend program.");
+
+  let mut levels = Levels::new();
+  assert!(!levels.apply(sangzf_compiler::check(&ast)).has_errors());
+
+  levels.set(Lint::LossyConversion, Level::Deny);
+  assert!(levels.apply(sangzf_compiler::check(&ast)).has_errors());
+
+  levels.set(Lint::LossyConversion, Level::Allow);
+  assert!(levels.apply(sangzf_compiler::check(&ast)).list.is_empty());
+}
@@ -0,0 +1,131 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::Diagnostic;
+use sangzf_compiler::tokenize::literals;
+use sangzf_compiler::tokenize::token::{Token, Type};
+
+// the text and type of each token in a program
+fn lex(program: &str) -> Vec<(String, String)> {
+  return sangzf_compiler::tokenize(program).iter().map(|token| (token.chars.clone(), token.r#type.to_string())).collect();
+}
+
+// the literals reported out of range in a statement
+fn out_of_range(statement: &str) -> Vec<String> {
+  let program = format!("program p is\nvariable i : integer;\nvariable f : float;\nbegin\n{}\nend program.", statement);
+
+  let ast = sangzf_compiler::parse(&program);
+  let diagnostics = sangzf_compiler::check(&ast);
+
+  return diagnostics.list.iter().filter_map(|diagnostic| match diagnostic {
+    Diagnostic::ErrLiteralRange{chars, ..} => Some(chars.clone()),
+    _ => None
+  }).collect();
+}
+
+fn token(chars: &str, r#type: &str) -> (String, String) {
+  return (String::from(chars), String::from(r#type));
+}
+
+#[test]
+fn lex_number_types() {
+  assert_eq!(lex("1_000 1.5 1_2.3_4 1.5e3 2E-4 3e+2 0x1F 0b1010"), vec![
+    token("1_000", "integer"),
+    token("1.5", "float"),
+    token("1_2.3_4", "float"),
+    token("1.5e3", "float"),
+    token("2E-4", "float"),
+    token("3e+2", "float"),
+    token("0x1F", "integer"),
+    token("0b1010", "integer")
+  ]);
+}
+
+#[test]
+fn lex_incomplete_numbers() {
+  // without digits after them, the exponent and prefixes are identifiers
+  let tokens = sangzf_compiler::tokenize("1e 0x 0b2");
+
+  let kinds: Vec<(&str, Token)> = tokens.iter().map(|token| (&token.chars[..], token.tok_type)).collect();
+  assert_eq!(kinds, vec![
+    ("1", Token::Number),
+    ("e", Token::Identifier),
+    ("0", Token::Number),
+    ("x", Token::Identifier),
+    ("0", Token::Number),
+    ("b2", Token::Identifier)
+  ]);
+}
+
+#[test]
+fn lex_malformed_numbers() {
+  // a decimal point needs a digit after it, and underscores have to be between digits
+  let tokens = sangzf_compiler::tokenize("1. 1_2. 1_ 1__0 0x1_");
+
+  let chars: Vec<&str> = tokens.iter().map(|token| &token.chars[..]).collect();
+  assert_eq!(chars, vec!["1", ".", "1_2", ".", "1", "_", "1", "_", "_", "0", "0x1", "_"]);
+}
+
+#[test]
+fn malformed_numbers_are_errors() {
+  for (number, code) in &[("1.", "E0102"), ("1_2.", "E0102"), ("1_", "E0002"), ("1__0", "E0002")] {
+    let program = format!("program p is\nvariable f : float;\nbegin\nf := {};\nend program.", number);
+    let codes: Vec<&str> = sangzf_compiler::parse(&program).diagnostics.list.iter().map(|diagnostic| diagnostic.code()).collect();
+
+    assert!(codes.contains(code), "expected {} for '{}', got {:?}", code, number, codes);
+  }
+}
+
+#[test]
+fn integer_values() {
+  assert_eq!(literals::integer_value("1_000"), Ok(1000));
+  assert_eq!(literals::integer_value("0x1f"), Ok(31));
+  assert_eq!(literals::integer_value("0XFF_FF"), Ok(65535));
+  assert_eq!(literals::integer_value("0b1_01"), Ok(5));
+  assert!(literals::integer_value("99999999999999999999").is_err());
+}
+
+#[test]
+fn float_values() {
+  assert_eq!(literals::float_value("1_2.5"), Ok(12.5));
+  assert_eq!(literals::float_value("1.5e3"), Ok(1500.0));
+  assert_eq!(literals::float_value("2_5e-1"), Ok(2.5));
+}
+
+#[test]
+fn float_type_of_literals() {
+  assert!(literals::is_float("1.0"));
+  assert!(literals::is_float("1e3"));
+  assert!(!literals::is_float("0xE"));
+  assert!(!literals::is_float("0b1"));
+}
+
+#[test]
+fn integers_in_range() {
+  assert!(out_of_range("i := 2147483647;").is_empty());
+  assert!(out_of_range("i := -2147483648;").is_empty());
+  assert!(out_of_range("i := 0x7fff_ffff;").is_empty());
+}
+
+#[test]
+fn integers_out_of_range() {
+  assert_eq!(out_of_range("i := 2147483648;"), vec!["2147483648"]);
+  assert_eq!(out_of_range("i := -2147483649;"), vec!["2147483649"]);
+  assert_eq!(out_of_range("i := 0xffff_ffff;"), vec!["0xffff_ffff"]);
+  assert_eq!(out_of_range("i := 99999999999999999999;"), vec!["99999999999999999999"]);
+}
+
+#[test]
+fn floats_out_of_range() {
+  assert!(out_of_range("f := 3.4e38;").is_empty());
+  assert_eq!(out_of_range("f := 1e39;"), vec!["1e39"]);
+}
+
+#[test]
+fn out_of_range_reports_type() {
+  let ast = sangzf_compiler::parse("program p is\nvariable i : integer;\nbegin\ni := 4294967296;\nend program.");
+
+  match sangzf_compiler::check(&ast).list.first() {
+    Some(Diagnostic::ErrLiteralRange{line_num, r#type: Type::Integer, ..}) => assert_eq!(*line_num, 4),
+    _ => panic!("expected an out of range integer")
+  }
+}
@@ -0,0 +1,79 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::Diagnostic;
+use sangzf_compiler::log::{Filter, Level};
+use sangzf_compiler::options::Options;
+
+fn options(args: &[&str]) -> Result<Options, String> {
+  return Options::from_args(args.iter().map(|arg| String::from(*arg)).collect());
+}
+
+#[test]
+fn quiet_by_default() {
+  let filter = Filter::new();
+
+  assert!(filter.enabled(Level::Warn, "parser"));
+  assert!(!filter.enabled(Level::Info, "parser"));
+}
+
+#[test]
+fn verbosity_flags() {
+  assert!(options(&["-v", "p.src"]).ok().unwrap().log.enabled(Level::Info, "codegen"));
+
+  let filter = options(&["-vv", "p.src"]).ok().unwrap().log;
+  assert!(filter.enabled(Level::Debug, "codegen") && !filter.enabled(Level::Trace, "codegen"));
+
+  assert_eq!(options(&["-vvv", "p.src"]).ok().unwrap().log.level, Level::Trace);
+}
+
+#[test]
+fn levels_per_target() {
+  let filter = options(&["--log=parser=trace,codegen=off", "-v", "p.src"]).ok().unwrap().log;
+
+  assert!(filter.enabled(Level::Trace, "parser"));
+  assert!(!filter.enabled(Level::Error, "codegen"));
+  assert!(filter.enabled(Level::Info, "lexer") && !filter.enabled(Level::Debug, "lexer"));
+
+  // a later directive for the same target replaces an earlier one
+  let mut filter = Filter::new();
+  filter.parse("lexer=trace,lexer=info").unwrap();
+  assert!(!filter.enabled(Level::Debug, "lexer"));
+}
+
+#[test]
+fn unknown_targets_and_levels() {
+  assert!(options(&["--log=typer=debug", "p.src"]).is_err());
+  assert!(options(&["--log=parser=loud", "p.src"]).is_err());
+  assert!(options(&["--log=", "p.src"]).is_err());
+}
+
+#[test]
+fn internal_errors_are_reported() {
+  // codegen has no llvm type for enums yet
+  let program = "program p is\nvariable x : enum { a, b };\nbegin\nend program.";
+  let artifact = sangzf_compiler::compile(program, Options::new());
+
+  assert!(artifact.bitcode.is_none());
+
+  match artifact.diagnostics.list.last() {
+    Some(Diagnostic::ErrCompilerBug{phase, msg}) => {
+      assert_eq!(phase, "codegen");
+      assert_eq!(msg, "type 'enum' not supported yet");
+    },
+    _ => panic!("expected an internal error")
+  }
+}
+
+#[test]
+fn arrays_are_not_generated_yet() {
+  // one value can't hold every element, so no bitcode is written rather than the wrong one
+  let program = "program p is\nvariable xs : integer[4];\nbegin\nxs[1] := 2;\nend program.";
+  let artifact = sangzf_compiler::compile(program, Options::new());
+
+  assert!(artifact.bitcode.is_none());
+
+  match artifact.diagnostics.list.last() {
+    Some(Diagnostic::ErrCompilerBug{phase, ..}) => assert_eq!(phase, "codegen"),
+    _ => panic!("expected an internal error")
+  }
+}
@@ -0,0 +1,214 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::json::{self, Value};
+use sangzf_compiler::lint::Levels;
+use sangzf_compiler::lsp::{self, Server};
+
+static URI: &str = "file:///p.src";
+
+static PROGRAM: &str = "program p is
+variable count : integer;
+procedure add : integer(variable x : integer)
+begin
+return x + 1;
+end procedure;
+begin
+count := add(1);
+count := add(count);
+end program.";
+
+fn message(text: &str) -> Value {
+  return json::parse(text).unwrap();
+}
+
+// a server with the program open
+fn server(program: &str) -> Server {
+  let mut server = Server::new(Levels::new());
+  server.handle(&message(r#"{"jsonrpc":"2.0","id":0,"method":"initialize","params":{}}"#));
+
+  let open = format!(r#"{{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{{"textDocument":{{"uri":"{}","text":{}}}}}}}"#, URI, json::string(program));
+  server.handle(&message(&open));
+
+  return server;
+}
+
+// send a request about a position in the document, returning its result
+fn request(server: &mut Server, method: &str, line: u32, character: u32) -> Value {
+  let text = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{}","params":{{"textDocument":{{"uri":"{}"}},"position":{{"line":{},"character":{}}},"context":{{"includeDeclaration":true}}}}}}"#, method, URI, line, character);
+
+  let replies = server.handle(&message(&text));
+  assert_eq!(replies.len(), 1);
+
+  return replies[0].get("result").clone();
+}
+
+fn lines(locations: &Value) -> Vec<u64> {
+  return locations.as_array().iter().map(|location| location.get("range").get("start").get("line").as_u64().unwrap()).collect();
+}
+
+#[test]
+fn framed_session() {
+  let mut input = String::new();
+
+  for content in &[
+    r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+    r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#,
+    r#"{"jsonrpc":"2.0","method":"exit"}"#
+  ] {
+    input.push_str(&format!("Content-Length: {}\r\n\r\n{}", content.len(), content));
+  }
+
+  let mut output = vec![];
+  let code = Server::new(Levels::new()).run(input.as_bytes(), &mut output).unwrap();
+  assert_eq!(code, 0);
+
+  let mut output = &output[..];
+  let initialize = message(&lsp::read_message(&mut output).unwrap().unwrap());
+  assert_eq!(initialize.get("id").as_u64(), Some(1));
+  assert_eq!(initialize.get("result").get("capabilities").get("hoverProvider").as_bool(), Some(true));
+
+  let shutdown = message(&lsp::read_message(&mut output).unwrap().unwrap());
+  assert!(shutdown.get("result").is_null() && shutdown.get("error").is_null());
+
+  assert!(lsp::read_message(&mut output).unwrap().is_none());
+}
+
+#[test]
+fn diagnostics_on_change() {
+  let mut server = server(PROGRAM);
+
+  let change = format!(r#"{{"jsonrpc":"2.0","method":"textDocument/didChange","params":{{"textDocument":{{"uri":"{}","version":2}},"contentChanges":[{{"text":{}}}]}}}}"#,
+    URI, json::string("program p is\nvariable x : integer;\nbegin\nx := \"one\";\nend program."));
+
+  let replies = server.handle(&message(&change));
+  assert_eq!(replies[0].get("method").as_str(), Some("textDocument/publishDiagnostics"));
+
+  let diagnostics = replies[0].get("params").get("diagnostics").as_array();
+  let codes: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.get("code").as_str().unwrap()).collect();
+  assert_eq!(codes, vec!["E0301", "W0101"]);
+
+  // the type error covers line 4, and the unused variable is a warning
+  assert_eq!(diagnostics[0].get("range").get("start").get("line").as_u64(), Some(3));
+  assert_eq!(diagnostics[0].get("range").get("end").get("character").as_u64(), Some(11));
+  assert_eq!(diagnostics[1].get("severity").as_u64(), Some(2));
+}
+
+#[test]
+fn incremental_changes() {
+  let mut server = server(PROGRAM);
+
+  // each change applies to the text left by the one before: the '1' in 'return x + 1;', then a new line before it
+  let change = format!(r#"{{"jsonrpc":"2.0","method":"textDocument/didChange","params":{{"textDocument":{{"uri":"{}","version":2}},"contentChanges":[
+    {{"range":{{"start":{{"line":4,"character":11}},"end":{{"line":4,"character":12}}}},"text":"\"one\""}},
+    {{"range":{{"start":{{"line":4,"character":0}},"end":{{"line":4,"character":0}}}},"text":"\n"}}]}}}}"#, URI);
+
+  let replies = server.handle(&message(&change));
+  let diagnostics = replies[0].get("params").get("diagnostics").as_array();
+  assert_eq!(diagnostics.iter().map(|diagnostic| diagnostic.get("code").as_str().unwrap()).collect::<Vec<_>>(), vec!["E0301"]);
+  assert_eq!(diagnostics[0].get("range").get("start").get("line").as_u64(), Some(5));
+
+  // positions after the change have moved
+  let hover = request(&mut server, "textDocument/hover", 9, 14);
+  assert_eq!(hover.get("contents").get("value").as_str(), Some("variable count : integer"));
+}
+
+#[test]
+fn hover_shows_types() {
+  let mut server = server(PROGRAM);
+
+  let hover = request(&mut server, "textDocument/hover", 7, 10);
+  assert_eq!(hover.get("contents").get("value").as_str(), Some("procedure add : procedure(integer,) -> integer"));
+
+  let hover = request(&mut server, "textDocument/hover", 4, 7);
+  assert_eq!(hover.get("contents").get("value").as_str(), Some("parameter x : integer"));
+
+  assert!(request(&mut server, "textDocument/hover", 3, 2).is_null());
+}
+
+#[test]
+fn definition_and_references() {
+  let mut server = server(PROGRAM);
+
+  let definition = request(&mut server, "textDocument/definition", 8, 14);
+  assert_eq!(definition.get("range").get("start").get("line").as_u64(), Some(1));
+  assert_eq!(definition.get("range").get("start").get("character").as_u64(), Some(9));
+
+  // the declaration, then each use
+  assert_eq!(lines(&request(&mut server, "textDocument/references", 1, 10)), vec![1, 7, 8, 8]);
+  assert_eq!(lines(&request(&mut server, "textDocument/references", 2, 11)), vec![2, 7, 8]);
+}
+
+#[test]
+fn document_symbols() {
+  let mut server = server(PROGRAM);
+
+  let symbols = request(&mut server, "textDocument/documentSymbol", 0, 0);
+  let names: Vec<&str> = symbols.as_array().iter().map(|symbol| symbol.get("name").as_str().unwrap()).collect();
+  assert_eq!(names, vec!["count", "add"]);
+
+  let add = &symbols.as_array()[1];
+  assert_eq!(add.get("kind").as_u64(), Some(12));
+  assert_eq!(add.get("range").get("end").get("line").as_u64(), Some(5));
+  assert_eq!(add.get("children").as_array()[0].get("name").as_str(), Some("x"));
+}
+
+#[test]
+fn completion_of_visible_names() {
+  let mut server = server(PROGRAM);
+
+  let labels = |items: &Value| -> Vec<String> {
+    return items.as_array().iter().map(|item| String::from(item.get("label").as_str().unwrap())).collect();
+  };
+
+  // 'x' is only visible inside 'add', and 'count' isn't declared global
+  let inside = labels(&request(&mut server, "textDocument/completion", 4, 0));
+  assert!(inside.contains(&String::from("x")) && inside.contains(&String::from("add")) && !inside.contains(&String::from("count")));
+
+  let outside = labels(&request(&mut server, "textDocument/completion", 7, 0));
+  assert!(outside.contains(&String::from("count")) && !outside.contains(&String::from("x")));
+  assert!(outside.contains(&String::from("getinteger")) && outside.contains(&String::from("procedure")));
+}
+
+#[test]
+fn unknown_methods() {
+  let mut server = server(PROGRAM);
+
+  let replies = server.handle(&message(r#"{"jsonrpc":"2.0","id":3,"method":"textDocument/rename","params":{}}"#));
+  assert_eq!(replies[0].get("error").get("code"), &Value::Number(-32601.0));
+
+  // notifications are never answered
+  assert!(server.handle(&message(r#"{"jsonrpc":"2.0","method":"$/cancelRequest","params":{"id":3}}"#)).is_empty());
+}
+
+#[test]
+fn malformed_messages() {
+  let mut input = String::new();
+
+  // nesting this deep would overflow the stack if it were parsed
+  let nested = "[".repeat(200000);
+
+  for content in &[&nested[..], r#"{"jsonrpc":"2.0","method":"exit"}"#] {
+    input.push_str(&format!("Content-Length: {}\r\n\r\n{}", content.len(), content));
+  }
+
+  let mut output = vec![];
+  Server::new(Levels::new()).run(input.as_bytes(), &mut output).unwrap();
+
+  let mut output = &output[..];
+  let reply = message(&lsp::read_message(&mut output).unwrap().unwrap());
+  assert_eq!(reply.get("error").get("code"), &Value::Number(-32700.0));
+}
+
+#[test]
+fn json_limits() {
+  assert!(json::parse(&format!("{}{}", "[".repeat(128), "]".repeat(128))).is_ok());
+  assert!(json::parse(&format!("{}{}", "[".repeat(129), "]".repeat(129))).is_err());
+  assert!(json::parse(&format!("{}1{}", r#"{"a":"#.repeat(129), "}".repeat(129))).is_err());
+
+  // '\u' escapes are exactly 4 hex digits
+  assert_eq!(json::parse(r#""\u00e9\ud83d\ude00""#), Ok(Value::String(String::from("\u{e9}\u{1f600}"))));
+  assert!(json::parse(r#""\u+0e9""#).is_err());
+  assert!(json::parse(r#""\u-0e9""#).is_err());
+  assert!(json::parse(r#""\ue9""#).is_err());
+  assert!(json::parse(r#""\u0e""#).is_err());
+}
@@ -0,0 +1,85 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::optimize::{self, Pass};
+use sangzf_compiler::options::{OptLevel, Options};
+
+const PROGRAM: &str = "program p is
+variable total : integer;
+variable i : integer;
+procedure double : integer(variable x : integer)
+begin
+return x + x;
+end procedure;
+begin
+total := 0;
+for (i := 0; i < 10)
+  total := total + double(i);
+  i := i + 1;
+end for;
+total := putinteger(total);
+end program.";
+
+fn compile(level: OptLevel) -> sangzf_compiler::Artifact {
+  let mut options = Options::new();
+  options.opt_level = level;
+
+  // the instructions are only counted for the report
+  options.time_passes = true;
+
+  return sangzf_compiler::compile(PROGRAM, options);
+}
+
+// the instructions counted after a pass
+fn instructions(artifact: &sangzf_compiler::Artifact, name: &str) -> usize {
+  let pass = artifact.timings.passes.iter().find(|pass| pass.name == name).unwrap();
+
+  return pass.counts.iter().find(|(count, _)| *count == "instructions").unwrap().1;
+}
+
+#[test]
+fn levels() {
+  assert!(optimize::passes(OptLevel::O0).is_empty());
+  assert_eq!(optimize::passes(OptLevel::O1)[0], Pass::PromoteMemoryToRegister);
+
+  let o2 = optimize::passes(OptLevel::O2);
+  for pass in &[Pass::FunctionInlining, Pass::Gvn, Pass::InstructionCombining, Pass::LoopRotate, Pass::Licm] {
+    assert!(o2.contains(pass), "-O2 doesn't run {}", pass.name());
+  }
+  assert!(!o2.contains(&Pass::LoopUnroll));
+
+  assert!(optimize::passes(OptLevel::O3).contains(&Pass::LoopUnroll));
+
+  // nothing that makes code larger
+  let os = optimize::passes(OptLevel::Os);
+  assert!(!os.contains(&Pass::FunctionInlining) && !os.contains(&Pass::LoopRotate) && !os.contains(&Pass::LoopUnroll));
+}
+
+#[test]
+fn unoptimized_by_default() {
+  assert_eq!(Options::new().opt_level, OptLevel::O0);
+
+  let artifact = compile(OptLevel::O0);
+  assert!(artifact.bitcode.is_some());
+  assert!(artifact.timings.passes.iter().all(|pass| pass.name != "optimize"));
+}
+
+#[test]
+fn optimizing_removes_instructions() {
+  for &level in &[OptLevel::O1, OptLevel::O2, OptLevel::O3, OptLevel::Os] {
+    let artifact = compile(level);
+
+    assert!(artifact.diagnostics.list.is_empty());
+    assert!(artifact.bitcode.is_some());
+    assert!(instructions(&artifact, "optimize") < instructions(&artifact, "codegen"), "{:?} didn't remove anything", level);
+  }
+}
+
+#[test]
+fn level_flags() {
+  let level = |flag: &str| Options::from_args(vec![String::from(flag), String::from("p.src")]).ok().unwrap().opt_level;
+
+  assert_eq!(level("-O0"), OptLevel::O0);
+  assert_eq!(level("-O2"), OptLevel::O2);
+  assert_eq!(level("-Os"), OptLevel::Os);
+  assert!(Options::from_args(vec![String::from("-O4"), String::from("p.src")]).is_err());
+}
@@ -0,0 +1,120 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::ast::Ast;
+use sangzf_compiler::diagnostics::Diagnostic;
+
+fn parse(program: &str) -> Ast {
+  return sangzf_compiler::parse(program);
+}
+
+// the line and expected/actual tokens of each syntax error
+fn unexpected(ast: &Ast) -> Vec<(u32, String, String)> {
+  return ast.diagnostics.list.iter().filter_map(|diagnostic| match diagnostic {
+    Diagnostic::ErrUnexpectedTok{line_num, expected, actual, ..} => Some((*line_num, expected.clone(), actual.clone())),
+    _ => None
+  }).collect();
+}
+
+fn error(line_num: u32, expected: &str, actual: &str) -> (u32, String, String) {
+  return (line_num, String::from(expected), String::from(actual));
+}
+
+fn statement_count(ast: &Ast) -> usize {
+  return ast.program.as_ref().map_or(0, |program| program.statements.len());
+}
+
+#[test]
+fn missing_end_if() {
+  let ast = parse("program p is\nvariable x : integer;\nbegin\nif (x < 1) then\nx := 1;\nx := 2;\nend program.");
+
+  // the 'end if' is inserted before the end of the program, which is still closed
+  match &ast.diagnostics.list[..] {
+    [Diagnostic::ErrMissingEnd{line_num: 7, construct, start_line: 4}] => assert_eq!(construct, "if"),
+    _ => panic!("expected a missing 'end if'")
+  }
+
+  assert_eq!(statement_count(&ast), 1);
+}
+
+#[test]
+fn missing_end_inside_another_block() {
+  let ast = parse("program p is\nvariable x : integer;\nbegin\nfor (x := 0; x < 2)\nif (true) then x := 1;\nend for;\nx := 3;\nend program.");
+
+  match &ast.diagnostics.list[..] {
+    [Diagnostic::ErrMissingEnd{line_num: 6, start_line: 5, ..}] => (),
+    _ => panic!("expected a missing 'end if'")
+  }
+
+  // the loop is still closed by its own 'end for'
+  assert_eq!(statement_count(&ast), 2);
+}
+
+#[test]
+fn stray_begin() {
+  let ast = parse("program p is\nvariable x : integer;\nbegin\nbegin\nx := 1;\nend program.");
+
+  assert_eq!(unexpected(&ast), vec![error(4, "(<identifier>|if|for|return)", "begin")]);
+  assert_eq!(statement_count(&ast), 1);
+}
+
+#[test]
+fn missing_begin() {
+  let ast = parse("program p is\nvariable x : integer;\nx := 1;\nend program.");
+
+  assert_eq!(unexpected(&ast), vec![error(3, "begin", "x")]);
+  assert_eq!(statement_count(&ast), 1);
+}
+
+#[test]
+fn missing_semicolon_is_inserted() {
+  let ast = parse("program p is\nvariable x : integer\nbegin\nx := 1\nx := 2;\nreturn x\nend program.");
+
+  assert_eq!(unexpected(&ast), vec![error(3, ";", "begin"), error(5, ";", "x"), error(7, ";", "end")]);
+  assert_eq!(statement_count(&ast), 3);
+}
+
+#[test]
+fn extra_tokens_are_deleted() {
+  let ast = parse("program p is\nvariable x : integer;\nbegin\nx := 1 2 3;\nx := 2;\nend program.");
+
+  assert_eq!(unexpected(&ast), vec![error(4, ";", "2")]);
+  assert_eq!(statement_count(&ast), 2);
+}
+
+#[test]
+fn failed_block_is_skipped() {
+  // the semicolons in the body of the if statement don't end it
+  let ast = parse("program p is\nvariable x : integer;\nbegin\nif (x then\nx := 1;\nfor (x := 0; x < 2) x := x + 1; end for;\nend if;\nx := 2;\nend program.");
+
+  assert_eq!(unexpected(&ast), vec![error(4, ")", "then")]);
+  assert_eq!(statement_count(&ast), 1);
+}
+
+#[test]
+fn failed_procedure_is_skipped() {
+  let ast = parse("program p is\nprocedure f : integer(variable a integer)\nbegin\nif (a < 1) then return 1; end if;\nreturn a;\nend procedure;\n\
+    variable y : integer;\nbegin\ny := 1;\nend program.");
+
+  assert_eq!(unexpected(&ast), vec![error(2, ":", "integer")]);
+
+  let program = ast.program.unwrap();
+  assert_eq!(program.declarations.len(), 1);
+  assert_eq!(program.statements.len(), 1);
+}
+
+#[test]
+fn cascading_errors_are_suppressed() {
+  // one missing parenthesis is reported once, not once per token after it
+  let ast = parse("program p is\nvariable x : integer;\nbegin\nx := (1 + 2;\nx := 3;\nend program.");
+
+  assert_eq!(unexpected(&ast), vec![error(4, ")", ";")]);
+  assert_eq!(statement_count(&ast), 1);
+}
+
+#[test]
+fn end_of_program_is_reported_once() {
+  let ast = parse("program p is\nvariable x : integer;\nbegin\nif (x < 1) then\nfor (x := 0; x < 2)\nx := (1 +");
+
+  assert_eq!(ast.diagnostics.list.len(), 1);
+  assert!(ast.diagnostics.has_errors());
+}
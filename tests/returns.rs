@@ -0,0 +1,50 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::Diagnostic;
+
+// the problems the checker finds in a procedure `f` with the given statements
+fn check(statements: &str) -> Vec<Diagnostic> {
+  let program = format!("program p is\nprocedure f : integer(variable x : integer)\nvariable i : integer;\nbegin\n{}\nend procedure;\nbegin\nend program.", statements);
+  let ast = sangzf_compiler::parse(&program);
+  assert!(ast.diagnostics.list.is_empty());
+
+  return sangzf_compiler::check(&ast).list;
+}
+
+#[test]
+fn if_without_else_may_not_return() {
+  match &check("if (x > 0) then\nreturn 1;\nend if;")[..] {
+    [Diagnostic::ErrMissingReturn{name, start_line: 2, end_line: 8}] => assert_eq!(name, "f"),
+    _ => panic!("expected a missing return")
+  }
+}
+
+#[test]
+fn if_and_else_both_returning() {
+  assert!(check("if (x > 0) then\nreturn 1;\nelse\nreturn 2;\nend if;").is_empty());
+}
+
+#[test]
+fn loops_may_not_run() {
+  match &check("for (i := 0; i < x)\nreturn i;\nend for;")[..] {
+    [Diagnostic::ErrMissingReturn{start_line: 2, end_line: 8, ..}] => (),
+    _ => panic!("expected a missing return")
+  }
+}
+
+#[test]
+fn statements_after_a_return() {
+  // only the first unreachable statement is reported
+  match &check("return 1;\ni := 2;\ni := 3;")[..] {
+    [Diagnostic::WarnUnreachable{line_num: 6}] => (),
+    _ => panic!("expected an unreachable statement")
+  }
+}
+
+#[test]
+fn program_body_doesnt_return() {
+  // procedures always have a type, but the body of the program has none and doesn't return
+  let ast = sangzf_compiler::parse("program p is\nvariable i : integer;\nbegin\ni := 1;\nend program.");
+
+  assert!(sangzf_compiler::check(&ast).list.is_empty());
+}
@@ -0,0 +1,66 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::Diagnostic;
+use sangzf_compiler::suggest;
+
+// the suggestion for each unknown symbol or unexpected token
+fn suggestions(program: &str) -> Vec<Option<String>> {
+  return sangzf_compiler::check(&sangzf_compiler::parse(program)).list.iter().filter_map(|diagnostic| match diagnostic {
+    Diagnostic::ErrSymbolNotFound{suggestion, ..} | Diagnostic::ErrUnexpectedTok{suggestion, ..} => Some(suggestion.clone()),
+    _ => None
+  }).collect();
+}
+
+fn suggestion(name: &str) -> Option<String> {
+  return Some(String::from(name));
+}
+
+#[test]
+fn edit_distance() {
+  assert_eq!(suggest::distance("count", "count"), 0);
+  assert_eq!(suggest::distance("count", "cont"), 1);
+  assert_eq!(suggest::distance("count", "conut"), 1);
+  assert_eq!(suggest::distance("", "abc"), 3);
+  assert_eq!(suggest::distance("kitten", "sitting"), 3);
+}
+
+#[test]
+fn closest_candidate() {
+  assert_eq!(suggest::closest("totl", vec!["total", "count"]), suggestion("total"));
+  assert_eq!(suggest::closest("x", vec!["total", "count"]), None);
+
+  // ties go to the first in sorted order
+  assert_eq!(suggest::closest("ab", vec!["ac", "aa"]), suggestion("aa"));
+}
+
+#[test]
+fn misspelled_variables() {
+  let program = "program p is\nglobal variable total : integer;\nprocedure f : integer(variable count : integer)\nbegin\ncount := cuont;\nreturn totl;\nend procedure;\n\
+    begin\ntotal := f(1) + count;\nend program.";
+
+  // 'count' isn't visible outside 'f', and the global 'total' is visible inside it
+  assert_eq!(suggestions(program), vec![suggestion("count"), suggestion("total"), None]);
+}
+
+#[test]
+fn misspelled_procedures() {
+  let program = "program p is\nvariable putint : integer;\nbegin\nputint := putinteger(1);\nputint := putintger(1);\nend program.";
+
+  // only procedures are suggested for calls, including builtins
+  assert_eq!(suggestions(program), vec![suggestion("putinteger")]);
+}
+
+#[test]
+fn misspelled_keywords() {
+  assert_eq!(suggestions("program p si\nbegin\nend program."), vec![suggestion("is")]);
+
+  // the procedure is skipped along with its body
+  let program = "program p is\nprocedur f : integer()\nbegin\nreturn 1;\nend procedure;\nvaraible x : integer;\nbegin\nend program.";
+  assert_eq!(suggestions(program), vec![suggestion("procedure"), suggestion("variable")]);
+}
+
+#[test]
+fn only_expected_keywords_are_suggested() {
+  // 'x' is close to 'if', but only ';' was expected
+  assert_eq!(suggestions("program p is\nvariable x : integer;\nbegin\nx := 1\nx := 2;\nend program."), vec![None]);
+}
@@ -0,0 +1,87 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::json;
+use sangzf_compiler::options::{Format, Options};
+
+const PROGRAM: &str = "program p is
+variable count : integer;
+procedure add : integer(variable x : integer)
+begin
+return x + 1;
+end procedure;
+begin
+count := add(1);
+count := count;
+end program.";
+
+// compile with the report turned on, so what each pass produced is counted
+fn compile(program: &str) -> sangzf_compiler::Artifact {
+  let mut options = Options::new();
+  options.time_passes = true;
+
+  return sangzf_compiler::compile(program, options);
+}
+
+fn counts(artifact: &sangzf_compiler::Artifact) -> Vec<(&str, Vec<(&str, usize)>)> {
+  return artifact.timings.passes.iter().map(|pass| (pass.name, pass.counts.clone())).collect();
+}
+
+#[test]
+fn every_pass_is_timed() {
+  let artifact = compile(PROGRAM);
+  assert!(artifact.bitcode.is_some());
+
+  // 'main' and 'add' are generated, the builtins are only declared
+  assert_eq!(counts(&artifact), vec![
+    ("lex", vec![("tokens", 42)]),
+    ("parse", vec![("declarations", 2)]),
+    ("check", vec![("symbols", 3)]),
+    ("lint", vec![("findings", 0)]),
+    ("codegen", vec![("functions", 2), ("instructions", 12)]),
+    ("verify", vec![]),
+    ("bitcode", vec![("bytes", artifact.bitcode.as_ref().unwrap().len())])
+  ]);
+
+  let total: std::time::Duration = artifact.timings.passes.iter().map(|pass| pass.time).sum();
+  assert_eq!(artifact.timings.total(), total);
+}
+
+#[test]
+fn errors_stop_before_codegen() {
+  let artifact = compile("program p is\nbegin\nx := 1;\nend program.");
+
+  let names: Vec<&str> = artifact.timings.passes.iter().map(|pass| pass.name).collect();
+  assert_eq!(names, vec!["lex", "parse", "check", "lint"]);
+}
+
+#[test]
+fn counts_only_when_reported() {
+  let artifact = sangzf_compiler::compile(PROGRAM, Options::new());
+
+  // every pass is still timed
+  assert_eq!(artifact.timings.passes.len(), 7);
+  assert!(artifact.timings.passes.iter().all(|pass| pass.counts.is_empty()));
+}
+
+#[test]
+fn reports() {
+  let artifact = compile(PROGRAM);
+
+  let text = artifact.timings.report(Format::Text);
+  let lines: Vec<&str> = text.lines().collect();
+  assert!(lines[0].starts_with("pass"));
+  assert!(lines[1].starts_with("lex") && lines[1].ends_with("tokens: 42"));
+  assert!(lines.last().unwrap().starts_with("total"));
+
+  let report = json::parse(&artifact.timings.report(Format::Json)).unwrap();
+  let codegen = &report.get("passes").as_array()[4];
+  assert_eq!(codegen.get("name").as_str(), Some("codegen"));
+  assert_eq!(codegen.get("counts").get("functions").as_u64(), Some(2));
+  assert!(!report.get("total_ms").is_null());
+}
+
+#[test]
+fn time_passes_option() {
+  let options = Options::from_args(vec![String::from("--time-passes"), String::from("p.src")]).ok().unwrap();
+  assert!(options.time_passes);
+}
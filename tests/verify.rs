@@ -0,0 +1,34 @@
+extern crate sangzf_compiler;
+
+use sangzf_compiler::diagnostics::Diagnostic;
+use sangzf_compiler::options::Options;
+
+// strings aren't generated yet, so storing one makes a module that fails verification
+static BROKEN: &str = "program p is\nvariable s : string;\nbegin\ns := \"a\";\nend program.";
+
+#[test]
+fn verification_failures_are_reported() {
+  let artifact = sangzf_compiler::compile(BROKEN, Options::new());
+
+  assert!(artifact.bitcode.is_none());
+  assert!(artifact.ir.is_none());
+  assert!(artifact.diagnostics.has_internal_error());
+
+  match artifact.diagnostics.list.last() {
+    Some(Diagnostic::ErrInternal{procedures, msg}) => {
+      assert_eq!(procedures, &vec![String::from("main")]);
+      assert!(msg.contains("Stored value type does not match pointer operand type"));
+    },
+    _ => panic!("expected the module to fail verification")
+  }
+}
+
+#[test]
+fn broken_ir_is_kept_for_bug_reports() {
+  let mut options = Options::new();
+  options.dump_ir = true;
+
+  let artifact = sangzf_compiler::compile(BROKEN, options);
+
+  assert!(artifact.ir.unwrap().contains("define void @main()"));
+}